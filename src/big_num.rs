@@ -1,14 +1,193 @@
-use std::{ops::{Add, Sub, Mul, Div, Rem}, fmt::Display};
+use std::{ops::{Add, Sub, Mul, Div, Rem, AddAssign, SubAssign, MulAssign, DivAssign, RemAssign, Shl, Shr, ShlAssign, ShrAssign, BitAnd, BitOr, BitXor, Neg}, iter::{Sum, Product}, fmt::{Display, LowerHex, UpperHex}, cmp::Ordering, str::FromStr, sync::OnceLock};
 
 extern crate num_primes;
 use num_primes::{Generator};
 extern crate num;
-use num::{bigint, FromPrimitive, Integer};
+use num::{bigint, FromPrimitive, ToPrimitive, Integer};
 use serde::{Serialize, Deserialize};
+use rand::{RngCore, CryptoRng};
+
+use crate::modular;
+
+pub(crate) use self::montgomery::MontgomeryCtx;
+
+mod montgomery;
+#[cfg(feature = "proptest")]
+pub mod strategy;
 
 pub type Sign = num::bigint::Sign;
 
-#[derive(PartialEq, PartialOrd)]
+/// Implements `owned op &ref` and `&ref op owned` by delegating to the `&ref op &ref` impl.
+macro_rules! forward_mixed_ref_binop {
+    (impl $imp:ident, $method:ident for $t:ty, $output:ty) => {
+        impl $imp<&$t> for $t {
+            type Output = $output;
+            fn $method(self, rhs: &$t) -> Self::Output {
+                $imp::$method(&self, rhs)
+            }
+        }
+
+        impl $imp<$t> for &$t {
+            type Output = $output;
+            fn $method(self, rhs: $t) -> Self::Output {
+                $imp::$method(self, &rhs)
+            }
+        }
+    };
+}
+
+/// Implements the shift operators for [`BigUint`] with a primitive shift amount.
+macro_rules! impl_shift {
+    ($bits:ty) => {
+        impl Shl<$bits> for BigUint {
+            type Output = BigUint;
+            fn shl(self, rhs: $bits) -> Self::Output {
+                BigUint{inner: &self.inner << rhs as usize}
+            }
+        }
+
+        impl Shl<$bits> for &BigUint {
+            type Output = BigUint;
+            fn shl(self, rhs: $bits) -> Self::Output {
+                BigUint{inner: &self.inner << rhs as usize}
+            }
+        }
+
+        impl Shr<$bits> for BigUint {
+            type Output = BigUint;
+            fn shr(self, rhs: $bits) -> Self::Output {
+                BigUint{inner: &self.inner >> rhs as usize}
+            }
+        }
+
+        impl Shr<$bits> for &BigUint {
+            type Output = BigUint;
+            fn shr(self, rhs: $bits) -> Self::Output {
+                BigUint{inner: &self.inner >> rhs as usize}
+            }
+        }
+
+        impl ShlAssign<$bits> for BigUint {
+            fn shl_assign(&mut self, rhs: $bits) {
+                self.inner <<= rhs as usize;
+            }
+        }
+
+        impl ShrAssign<$bits> for BigUint {
+            fn shr_assign(&mut self, rhs: $bits) {
+                self.inner >>= rhs as usize;
+            }
+        }
+    };
+}
+
+/// Implements `owned op u32` and `&ref op u32` for an infallible [`BigUint`] operator.
+macro_rules! impl_scalar_binop {
+    (impl $imp:ident, $method:ident, $op:tt) => {
+        impl $imp<u32> for BigUint {
+            type Output = BigUint;
+            fn $method(self, rhs: u32) -> Self::Output {
+                BigUint{inner: &self.inner $op rhs}
+            }
+        }
+
+        impl $imp<u32> for &BigUint {
+            type Output = BigUint;
+            fn $method(self, rhs: u32) -> Self::Output {
+                BigUint{inner: &self.inner $op rhs}
+            }
+        }
+    };
+}
+
+/// Implements the infallible conversion from an unsigned primitive into [`BigUint`].
+macro_rules! impl_from_unsigned {
+    ($t:ty) => {
+        impl From<$t> for BigUint {
+            fn from(value: $t) -> Self {
+                BigUint{inner: num_primes::BigUint::from(value)}
+            }
+        }
+    };
+}
+
+/// Implements the conversion from a signed primitive into [`BigUint`], which fails only on negative values.
+macro_rules! impl_try_from_signed {
+    ($t:ty) => {
+        impl TryFrom<$t> for BigUint {
+            type Error = String;
+
+            fn try_from(value: $t) -> Result<Self, Self::Error> {
+                if value < 0 {
+                    return Err(format!("{value} is negative and cannot be converted to BigUint"))
+                }
+                Ok(BigUint::from(value.unsigned_abs()))
+            }
+        }
+    };
+}
+
+/// Implements the conversion from [`BigUint`] into an unsigned primitive, which fails if the value doesn't fit.
+macro_rules! impl_try_into_unsigned {
+    ($t:ty, $method:ident) => {
+        impl TryFrom<BigUint> for $t {
+            type Error = String;
+
+            fn try_from(value: BigUint) -> Result<Self, Self::Error> {
+                value.$method().ok_or(format!("{value} does not fit in {}", stringify!($t)))
+            }
+        }
+    };
+}
+
+/// Implements `==` and the ordering operators between a big number and an unsigned primitive, in both directions.
+/// Delegates to the type's `cmp_u64`, so neither side is converted into a big number.
+macro_rules! impl_primitive_cmp {
+    ($t:ty, $prim:ty) => {
+        impl PartialEq<$prim> for $t {
+            fn eq(&self, other: &$prim) -> bool {
+                self.cmp_u64(u64::from(*other)) == Ordering::Equal
+            }
+        }
+
+        impl PartialEq<$t> for $prim {
+            fn eq(&self, other: &$t) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$prim> for $t {
+            fn partial_cmp(&self, other: &$prim) -> Option<Ordering> {
+                Some(self.cmp_u64(u64::from(*other)))
+            }
+        }
+
+        impl PartialOrd<$t> for $prim {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(other.cmp_u64(u64::from(*self)).reverse())
+            }
+        }
+    };
+}
+
+/// Implements `x op= rhs` for owned and borrowed `rhs` by delegating to the inner representation.
+macro_rules! forward_assign_op {
+    (impl $imp:ident, $method:ident for $t:ty) => {
+        impl $imp for $t {
+            fn $method(&mut self, rhs: $t) {
+                self.inner.$method(&rhs.inner)
+            }
+        }
+
+        impl $imp<&$t> for $t {
+            fn $method(&mut self, rhs: &$t) {
+                self.inner.$method(&rhs.inner)
+            }
+        }
+    };
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[derive(Clone)]
 #[derive(Debug)]
 #[derive(Default)]
@@ -16,10 +195,24 @@ pub struct BigUint {
     inner: num_primes::BigUint
 }
 
+/// Wipes the limbs when the value goes out of scope, so secrets like the primes of a
+/// [`SecretKey`](crate::rsa::SecretKey) don't linger in freed memory. See [`BigUint::zeroize`].
+#[cfg(feature = "zeroize")]
+impl Drop for BigUint {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// Human-readable formats like JSON get a hex string (see [`BigUint::to_hex`]),
+/// while binary formats like bincode get the big-endian bytes.
 impl Serialize for BigUint {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer {
+        if serializer.is_human_readable() {
+            return self.to_hex().serialize(serializer)
+        }
         let bytes: Vec<u8> = self.clone().into();
         bytes.serialize(serializer)
     }
@@ -29,10 +222,17 @@ impl <'de>Deserialize<'de> for BigUint {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            let hex = String::deserialize(deserializer)?;
+            return BigUint::from_hex(&hex).map_err(serde::de::Error::custom)
+        }
         Ok(Vec::<u8>::deserialize(deserializer)?.into())
     }
 }
 
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq)]
+#[derive(Debug)]
 pub enum Digit {
     _0,
     _1,
@@ -46,45 +246,574 @@ pub enum Digit {
     _9,
 }
 
+impl Digit {
+    fn value(self) -> u32 {
+        match self {
+            Digit::_0 => 0,
+            Digit::_1 => 1,
+            Digit::_2 => 2,
+            Digit::_3 => 3,
+            Digit::_4 => 4,
+            Digit::_5 => 5,
+            Digit::_6 => 6,
+            Digit::_7 => 7,
+            Digit::_8 => 8,
+            Digit::_9 => 9,
+        }
+    }
+}
+
+/// Converts the numeric value `0..=9`, not an ASCII character code; see `TryFrom<char>` for those.
+impl TryFrom<u8> for Digit {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Digit::_0),
+            1 => Ok(Digit::_1),
+            2 => Ok(Digit::_2),
+            3 => Ok(Digit::_3),
+            4 => Ok(Digit::_4),
+            5 => Ok(Digit::_5),
+            6 => Ok(Digit::_6),
+            7 => Ok(Digit::_7),
+            8 => Ok(Digit::_8),
+            9 => Ok(Digit::_9),
+            _ => Err(format!("{value} is not a decimal digit")),
+        }
+    }
+}
+
+impl TryFrom<char> for Digit {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        let digit = value.to_digit(10).ok_or(format!("'{value}' is not a decimal digit"))?;
+        Digit::try_from(digit as u8)
+    }
+}
+
 impl BigUint {
     pub fn new(digits: Vec<u32>) -> Self {
         BigUint{inner: num_primes::BigUint::new(digits)}
     }
 
+    /// The base `2^32` limbs of the number, least significant limb first, as taken by [`BigUint::new`].
+    /// There are no trailing zero limbs, so zero gives an empty vector.
+    pub fn to_u32_digits(&self) -> Vec<u32> {
+        self.inner.to_u32_digits()
+    }
+
+    /// Interprets `digits` as a base 10 number, most significant digit first.
+    /// An empty slice gives zero.
+    pub fn from_digits(digits: &[Digit]) -> Self {
+        let ten = num_primes::BigUint::new(vec![10]);
+        let inner = digits.iter().fold(num_primes::BigUint::default(), |acc, digit| acc * &ten + digit.value());
+        BigUint{inner}
+    }
+
+    pub fn zero() -> Self {
+        BigUint::default()
+    }
+
+    pub fn one() -> Self {
+        BigUint::new(vec![1])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        num::Zero::is_zero(&self.inner)
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.inner.bits() == 1
+    }
+
     pub fn from_i32(value: i32) -> Result<Self,String> {
         let inner = num_primes::BigUint::from_i32(value).ok_or(format!("{value} could not be converted to BigInt"))?;
         Ok(BigUint{inner})
     }
 
+    /// Gives `self^exponent mod modulus`, using Montgomery multiplication when the modulus is odd,
+    /// as RSA moduli are.
     pub fn modpow(&self, exponent: &Self, modulus: &Self) -> Self {
-        BigUint { inner: self.inner.modpow(&exponent.inner, &modulus.inner) }
+        match MontgomeryCtx::new(modulus) {
+            Some(ctx) => ctx.pow(self, exponent),
+            None => BigUint { inner: self.inner.modpow(&exponent.inner, &modulus.inner) },
+        }
+    }
+
+    /// Raises `self` to `exp` by square-and-multiply.
+    pub fn pow(&self, exp: u32) -> Self {
+        let mut result = BigUint::new(vec![1]);
+        let mut base = self.clone();
+        let mut exp = exp;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Gives `2^k`.
+    pub fn two_pow(k: u32) -> Self {
+        BigUint::new(vec![1]) << k
+    }
+
+    /// Floor square root, see [`BigUint::nth_root`].
+    pub fn sqrt(&self) -> Self {
+        self.nth_root(2)
+    }
+
+    /// Floor `n`th root `r`, such that `r^n <= self < (r + 1)^n`, found by Newton iteration.
+    ///
+    /// Roots are a concrete reason not to use textbook RSA with a small exponent:
+    /// with `e = 3`, a message `m` where `m^3 < n` is recovered from the ciphertext by a cube root.
+    /// ```rust
+    /// use rustnetworking::big_num::BigUint;
+    ///
+    /// let m = BigUint::parse("1234567890").unwrap();
+    /// assert_eq!(m, m.pow(3).nth_root(3));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    pub fn nth_root(&self, n: u32) -> Self {
+        assert!(n > 0, "the 0th root is undefined");
+
+        if n == 1 || self.inner <= num_primes::BigUint::new(vec![1]) {
+            return self.clone()
+        }
+
+        let n_big = BigUint::new(vec![n]);
+        let n_minus_one = BigUint::new(vec![n - 1]);
+
+        // 2^ceil(bits / n) is always at least the root, so the iteration decreases towards it.
+        let mut x = BigUint::two_pow(self.bit_size().div_ceil(n));
+        loop {
+            let y = (&n_minus_one * &x + self / &x.pow(n - 1)) / n_big.clone();
+            if y >= x {
+                return x
+            }
+            x = y;
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self,String> {
+        BigUint::from_str_radix(s, 10)
+    }
+
+    /// Parses a string of digits in the given `radix`, which must be in `2..=36`.
+    /// Digits above 9 are letters, in either case.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self,String> {
+        if !(2..=36).contains(&radix) {
+            return Err(format!("radix {radix} is not in the range 2 to 36"))
+        }
+
+        if s.is_empty() {
+            return Err("cannot parse BigUint from empty string".into())
+        }
+
+        if let Some((i, c)) = s.char_indices().find(|(_, c)| !c.is_digit(radix)) {
+            return Err(format!("invalid digit '{c}' for radix {radix} at position {i} in \"{s}\""))
+        }
+
+        let inner = num_primes::BigUint::parse_bytes(s.as_bytes(), radix).ok_or(format!("{s} could not be converted to BigUint"))?;
+        Ok(BigUint{inner})
+    }
+
+    /// Formats `self` in the given `radix` with lowercase letters for digits above 9.
+    ///
+    /// # Panics
+    /// Panics if `radix` is not in `2..=36`.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix {radix} is not in the range 2 to 36");
+        self.inner.to_str_radix(radix)
+    }
+
+    /// Parses a hexadecimal string, with or without a `0x` prefix, in either case.
+    pub fn from_hex(s: &str) -> Result<Self,String> {
+        let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+        BigUint::from_str_radix(digits, 16)
+    }
+
+    /// Hex encodes the bytes of [`BigUint::to_bytes_be`], two lowercase digits per byte.
+    /// Unlike `format!("{:x}", x)` this keeps the leading zero of the first byte.
+    pub fn to_hex(&self) -> String {
+        self.to_bytes_be().iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Samples uniformly from `[0, bound)`, rejecting candidates at or above `bound` rather than reducing
+    /// them modulo `bound`, which would bias the result towards small values.
+    ///
+    /// # Panics
+    /// Panics if `bound` is zero, since the range is then empty.
+    pub fn random_below(bound: &BigUint, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        assert!(!bound.is_zero(), "cannot sample below a bound of 0");
+
+        loop {
+            let candidate = BigUint::random_up_to_bits(bound.bit_size(), rng);
+            if &candidate < bound {
+                return candidate
+            }
+        }
+    }
+
+    /// Samples a uniform value of exactly `bits` bits, meaning the highest bit is always set.
+    pub fn random_bits(bits: u32, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        if bits == 0 {
+            return BigUint::zero()
+        }
+
+        let mut value = BigUint::random_up_to_bits(bits, rng);
+        value.inner |= num_primes::BigUint::new(vec![1]) << (bits as usize - 1);
+        value
+    }
+
+    fn random_up_to_bits(bits: u32, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+        rng.fill_bytes(&mut bytes);
+
+        let excess = bytes.len() as u32 * 8 - bits;
+        if let Some(first) = bytes.first_mut() {
+            *first &= 0xff >> excess;
+        }
+        BigUint::from_bytes_be(&bytes)
     }
 
     pub fn from_bytes_be(bytes: &[u8]) -> BigUint {
         BigUint{inner: num_primes::BigUint::from_bytes_be(bytes)}
     }
 
+    pub fn from_bytes_le(bytes: &[u8]) -> BigUint {
+        BigUint{inner: num_primes::BigUint::from_bytes_le(bytes)}
+    }
+
+    /// Little-endian counterpart of `From<Vec<u8>>`, which reads bytes as big-endian.
+    pub fn from_le(bytes: Vec<u8>) -> BigUint {
+        BigUint::from_bytes_le(&bytes[..])
+    }
+
+    /// Probabilistic primality test, see [`BigUint::is_probable_prime`], with [`MILLER_RABIN_ROUNDS`] rounds.
     pub fn prime(&self) -> bool {
-        num_primes::Verification::is_prime(&self.inner)
+        self.is_probable_prime(MILLER_RABIN_ROUNDS, &mut rand::thread_rng())
+    }
+
+    /// The smallest probable prime strictly greater than `self`.
+    ///
+    /// Only odd candidates are considered and each is sieved against the small primes, by keeping
+    /// its residues up to date, before running [`BigUint::prime`] on it.
+    pub fn next_prime(&self) -> Self {
+        if *self < 2u32 {
+            return BigUint::from(2u32)
+        }
+
+        let start = if self.is_even() { self + &BigUint::one() } else { self + &BigUint::from(2u32) };
+        let small_start = start.to_u64();
+        let mut residues: Vec<u32> = small_primes().iter()
+            .map(|&r| (&start.inner % r).to_u32().unwrap_or(0))
+            .collect();
+
+        let mut offset: u64 = 0;
+        loop {
+            let sieved = small_primes().iter().zip(&residues)
+                .all(|(&r, &residue)| residue != 0 || small_start.map(|s| s + offset) == Some(r as u64));
+
+            if sieved {
+                let candidate = &start + &BigUint::from(offset);
+                if candidate.prime() {
+                    return candidate
+                }
+            }
+
+            offset += 2;
+            for (&r, residue) in small_primes().iter().zip(residues.iter_mut()) {
+                *residue = (*residue + 2) % r;
+            }
+        }
+    }
+
+    /// Whether the number has a prime factor below [`SMALL_PRIME_BOUND`] other than itself,
+    /// found by trial division against the precomputed small primes. Zero is divisible by every prime.
+    ///
+    /// This is far cheaper than a single Miller–Rabin round, so prime generation uses it to reject
+    /// most candidates before [`BigUint::is_probable_prime`] does any modular exponentiation.
+    pub fn divisible_by_small_prime(&self) -> bool {
+        let digits = self.to_u32_digits();
+        small_primes().iter().any(|&p| {
+            let residue = digits.iter().rev().fold(0u64, |r, &digit| (r << 32 | digit as u64) % p as u64);
+            residue == 0 && *self != p
+        })
+    }
+
+    /// Miller–Rabin primality test with `rounds` random bases. A composite passes with probability
+    /// at most `4^(-rounds)`, while primes always pass. Numbers below [`SMALL_PRIME_BOUND`] are looked
+    /// up in the small prime table, and larger numbers with a small prime factor are rejected by
+    /// [`BigUint::divisible_by_small_prime`] before any of the expensive rounds.
+    ///
+    /// For more see `https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test`
+    pub fn is_probable_prime(&self, rounds: u32, rng: &mut (impl RngCore + CryptoRng)) -> bool {
+        if let Some(small) = self.to_u32().filter(|&n| (n as usize) < SMALL_PRIME_BOUND) {
+            return small_primes().binary_search(&small).is_ok()
+        }
+        if self.divisible_by_small_prime() {
+            return false
+        }
+
+        let one = BigUint::one();
+        let n_minus_one = self.saturating_sub(&one);
+        let s = n_minus_one.trailing_zeros().unwrap_or(0);
+        let d = &n_minus_one >> s as usize;
+        let bases = n_minus_one.saturating_sub(&BigUint::from(2u32));
+
+        'rounds: for _ in 0..rounds {
+            let a = BigUint::random_below(&bases, rng) + BigUint::from(2u32);
+            let mut x = a.modpow(&d, self);
+            if x == one || x == n_minus_one {
+                continue
+            }
+            for _ in 1..s {
+                x = &(&x * &x) % self;
+                if x == n_minus_one {
+                    continue 'rounds
+                }
+            }
+            return false
+        }
+        true
     }
 
     pub fn gcd(&self, other: &Self) -> Self {
         BigUint{inner: self.inner.gcd(&other.inner)}
     }
 
+    /// Least common multiple, computed as `a / gcd(a, b) * b` so the intermediate never exceeds the result.
+    /// The lcm of zero and anything is zero.
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero()
+        }
+        &(self / &self.gcd(other)) * other
+    }
+
     pub fn co_prime(&self, other: &Self) -> bool {
-        self.gcd(other) == BigUint::new(vec![1])
+        self.gcd(other) == 1u32
+    }
+
+    /// The modular inverse `self^(-1) (mod modulus)`, borrowing both operands, see [`modular::inverse`].
+    ///
+    /// # Errors
+    /// See [`modular::inverse`].
+    pub fn modinv(&self, modulus: &BigUint) -> Result<BigUint,modular::Error> {
+        modular::inverse(self, modulus)
+    }
+
+    /// Subtracts `rhs`, returning [`None`] instead of underflowing.
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        if self.inner < rhs.inner {
+            return None
+        }
+        Some(BigUint{inner: &self.inner - &rhs.inner})
+    }
+
+    /// Subtracts `rhs`, clamping at zero instead of underflowing.
+    pub fn saturating_sub(&self, rhs: &Self) -> Self {
+        self.checked_sub(rhs).unwrap_or_default()
     }
 
     pub fn to_bytes_be(&self) -> Vec<u8> {
         self.inner.to_bytes_be()
     }
 
+    /// Big-endian bytes left-padded with zeros to exactly `len` bytes, the fixed-length integer encoding
+    /// (I2OSP) used by the RSA standards.
+    ///
+    /// # Errors
+    /// An error is returned if the value needs more than `len` bytes.
+    pub fn to_bytes_be_padded(&self, len: usize) -> Result<Vec<u8>,String> {
+        let size = self.byte_size();
+        if size > len {
+            return Err(format!("{self} needs {size} bytes and does not fit in {len}"))
+        }
+
+        let bytes = self.to_bytes_be();
+        let mut padded = vec![0; len - size];
+        padded.extend_from_slice(&bytes[bytes.len() - size..]);
+        Ok(padded)
+    }
+
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        self.inner.to_bytes_le()
+    }
+
     pub fn bit_size(&self) -> u32 {
         self.inner.bits() as u32
     }
+
+    /// The number of bytes needed to hold the number, `(bit_size + 7) / 8`.
+    /// Zero needs no bytes, so its byte size is 0.
+    pub fn byte_size(&self) -> usize {
+        self.inner.bits().div_ceil(8)
+    }
+
+    /// Gives the quotient and remainder of dividing by `rhs` in a single division.
+    ///
+    /// # Panics
+    /// Panics if `rhs` is zero, see [`BigUint::checked_div_rem`].
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let (quotient, remainder) = self.inner.div_rem(&rhs.inner);
+        (BigUint{inner: quotient}, BigUint{inner: remainder})
+    }
+
+    /// Gives `self / rhs`, or [`None`] when dividing by zero instead of panicking like [`Div`].
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None
+        }
+        Some(self / rhs)
+    }
+
+    /// Gives `self % rhs`, or [`None`] when dividing by zero instead of panicking like [`Rem`].
+    pub fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None
+        }
+        Some(self % rhs)
+    }
+
+    /// Like [`BigUint::div_rem`], but gives [`None`] when dividing by zero.
+    pub fn checked_div_rem(&self, rhs: &Self) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            return None
+        }
+        Some(self.div_rem(rhs))
+    }
+
+    /// Compares in time that depends only on the byte length of the longer operand, not on where the values differ,
+    /// so comparing secret-derived values doesn't leak how much of them matched.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        let (mut a, mut b) = (self.to_bytes_le(), other.to_bytes_le());
+        let len = a.len().max(b.len());
+        a.resize(len, 0);
+        b.resize(len, 0);
+        ct_eq_bytes(&a, &b)
+    }
+
+    /// Overwrites the limbs with zeros in place, leaving `self` equal to zero.
+    ///
+    /// This is best-effort: it wipes the current buffer, but not copies left behind by earlier
+    /// reallocations, and not clones.
+    pub fn zeroize(&mut self) {
+        let limbs = self.bit_size().div_ceil(32) as usize;
+        self.inner.assign_from_slice(&vec![0; limbs]);
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.inner.is_even()
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.inner.is_odd()
+    }
+
+    /// The largest `k` such that `2^k` divides `self`, or [`None`] for zero, which every power of two divides.
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        let bytes = self.to_bytes_le();
+        let index = bytes.iter().position(|byte| *byte != 0)?;
+        Some(index as u64 * 8 + bytes[index].trailing_zeros() as u64)
+    }
+
+    /// Gives bit `i`, counting from the least significant bit. Bits beyond [`BigUint::bit_size`] are zero.
+    pub fn bit(&self, i: u64) -> bool {
+        let digits = self.inner.to_u32_digits();
+        digits.get((i / 32) as usize).is_some_and(|digit| digit >> (i % 32) & 1 == 1)
+    }
+
+    /// Sets bit `i` to `value`, growing the number when a bit above [`BigUint::bit_size`] is set.
+    pub fn set_bit(&mut self, i: u64, value: bool) {
+        let index = (i / 32) as usize;
+        let mask = 1u32 << (i % 32);
+        let mut digits = self.inner.to_u32_digits();
+        if index >= digits.len() {
+            if !value {
+                return
+            }
+            digits.resize(index + 1, 0);
+        }
+
+        if value {
+            digits[index] |= mask;
+        } else {
+            digits[index] &= !mask;
+        }
+        self.inner.assign_from_slice(&digits);
+    }
+
+    /// Splits `self` into its top 64 bits and the number of bits shifted out below them,
+    /// so that `self ~ top * 2^shift`.
+    fn top_bits(&self) -> (u64, u32) {
+        let shift = self.bit_size().saturating_sub(64);
+        let top = (&self.inner >> shift as usize).to_u64().unwrap_or(u64::MAX);
+        (top, shift)
+    }
+
+    /// Approximates the number as an `f64` from its top 64 bits, truncating the bits below them.
+    /// Values beyond [`f64::MAX`] give infinity.
+    pub fn to_f64_lossy(&self) -> f64 {
+        let (top, shift) = self.top_bits();
+        if shift > f64::MAX_EXP as u32 {
+            return f64::INFINITY
+        }
+        top as f64 * 2f64.powi(shift as i32)
+    }
+
+    /// Approximate base 2 logarithm, finite even for numbers too large for [`BigUint::to_f64_lossy`].
+    /// The logarithm of zero is negative infinity.
+    pub fn log2(&self) -> f64 {
+        let (top, shift) = self.top_bits();
+        (top as f64).log2() + shift as f64
+    }
+
+    fn cmp_u64(&self, other: u64) -> Ordering {
+        self.inner.to_u64().map_or(Ordering::Greater, |value| value.cmp(&other))
+    }
+
+    pub fn to_u32(&self) -> Option<u32> {
+        self.inner.to_u32()
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.inner.to_u64()
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        self.inner.to_u128()
+    }
 }
 
+impl_from_unsigned!(u8);
+impl_from_unsigned!(u16);
+impl_from_unsigned!(u32);
+impl_from_unsigned!(u64);
+impl_from_unsigned!(u128);
+impl_from_unsigned!(usize);
+
+impl_try_from_signed!(i32);
+impl_try_from_signed!(i64);
+
+impl_try_into_unsigned!(u32, to_u32);
+impl_try_into_unsigned!(u64, to_u64);
+impl_try_into_unsigned!(u128, to_u128);
+
+impl_primitive_cmp!(BigUint, u32);
+impl_primitive_cmp!(BigUint, u64);
+
 impl From<Vec<u8>> for BigUint {
     fn from(value: Vec<u8>) -> Self {
         BigUint::from_bytes_be(&value[..])
@@ -114,23 +843,43 @@ impl From<BigUint> for Vec<u8> {
     }
 }
 
+impl FromStr for BigUint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigUint::parse(s)
+    }
+}
+
 impl Display for BigUint {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+        Display::fmt(&self.inner, f)
+    }
+}
+
+impl LowerHex for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.inner, f)
+    }
+}
+
+impl UpperHex for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        UpperHex::fmt(&self.inner, f)
     }
 }
 
 impl Rem for BigUint {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output {
-        BigUint{inner: self.inner % rhs.inner}
+        BigUint{inner: &self.inner % &rhs.inner}
     }
 }
 
 impl Add for BigUint {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        BigUint{inner: self.inner + rhs.inner}
+        BigUint{inner: &self.inner + &rhs.inner}
     }
 }
 
@@ -141,21 +890,190 @@ impl Sub for BigUint {
         if self.inner < rhs.inner {
             return Err(format!("{self} is smaller than {rhs}"))
         }
-        Ok(BigUint{inner: self.inner - rhs.inner})
+        Ok(BigUint{inner: &self.inner - &rhs.inner})
     }
 }
 
 impl Mul for BigUint {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
-        BigUint{inner: self.inner * rhs.inner}
+        BigUint{inner: &self.inner * &rhs.inner}
     }
 }
 
 impl Div for BigUint {
     type Output = Self;
     fn div(self, rhs: Self) -> Self::Output {
-        BigUint{inner: self.inner / rhs.inner}
+        BigUint{inner: &self.inner / &rhs.inner}
+    }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner % &rhs.inner}
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner + &rhs.inner}
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = Result<BigUint,String>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        if self.inner < rhs.inner {
+            return Err(format!("{self} is smaller than {rhs}"))
+        }
+        Ok(BigUint{inner: &self.inner - &rhs.inner})
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner * &rhs.inner}
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner / &rhs.inner}
+    }
+}
+
+forward_mixed_ref_binop!(impl Rem, rem for BigUint, BigUint);
+forward_mixed_ref_binop!(impl Add, add for BigUint, BigUint);
+forward_mixed_ref_binop!(impl Sub, sub for BigUint, Result<BigUint,String>);
+forward_mixed_ref_binop!(impl Mul, mul for BigUint, BigUint);
+forward_mixed_ref_binop!(impl Div, div for BigUint, BigUint);
+
+impl_scalar_binop!(impl Add, add, +);
+impl_scalar_binop!(impl Mul, mul, *);
+impl_scalar_binop!(impl Div, div, /);
+impl_scalar_binop!(impl Rem, rem, %);
+
+impl Sub<u32> for &BigUint {
+    type Output = Result<BigUint,String>;
+
+    fn sub(self, rhs: u32) -> Self::Output {
+        if self.inner < num_primes::BigUint::from(rhs) {
+            return Err(format!("{self} is smaller than {rhs}"))
+        }
+        Ok(BigUint{inner: &self.inner - rhs})
+    }
+}
+
+impl Sub<u32> for BigUint {
+    type Output = Result<BigUint,String>;
+
+    fn sub(self, rhs: u32) -> Self::Output {
+        &self - rhs
+    }
+}
+
+impl BitAnd for BigUint {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner & &rhs.inner}
+    }
+}
+
+impl BitOr for BigUint {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner | &rhs.inner}
+    }
+}
+
+impl BitXor for BigUint {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner ^ &rhs.inner}
+    }
+}
+
+impl BitAnd for &BigUint {
+    type Output = BigUint;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner & &rhs.inner}
+    }
+}
+
+impl BitOr for &BigUint {
+    type Output = BigUint;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner | &rhs.inner}
+    }
+}
+
+impl BitXor for &BigUint {
+    type Output = BigUint;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        BigUint{inner: &self.inner ^ &rhs.inner}
+    }
+}
+
+forward_mixed_ref_binop!(impl BitAnd, bitand for BigUint, BigUint);
+forward_mixed_ref_binop!(impl BitOr, bitor for BigUint, BigUint);
+forward_mixed_ref_binop!(impl BitXor, bitxor for BigUint, BigUint);
+
+impl_shift!(u32);
+impl_shift!(usize);
+
+forward_assign_op!(impl AddAssign, add_assign for BigUint);
+forward_assign_op!(impl MulAssign, mul_assign for BigUint);
+forward_assign_op!(impl DivAssign, div_assign for BigUint);
+forward_assign_op!(impl RemAssign, rem_assign for BigUint);
+
+/// # Panics
+/// Unlike [`Sub`], which returns an error, subtracting in place panics if `rhs` is larger than `self`.
+/// Use [`BigUint::checked_sub`] when underflow is possible.
+impl SubAssign<&BigUint> for BigUint {
+    fn sub_assign(&mut self, rhs: &BigUint) {
+        if self.inner < rhs.inner {
+            panic!("{self} is smaller than {rhs}")
+        }
+        self.inner -= &rhs.inner;
+    }
+}
+
+/// # Panics
+/// Panics if `rhs` is larger than `self`, see `SubAssign<&BigUint>`.
+impl SubAssign for BigUint {
+    fn sub_assign(&mut self, rhs: BigUint) {
+        *self -= &rhs;
+    }
+}
+
+/// The sum of an empty iterator is zero.
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, x| acc + x)
+    }
+}
+
+/// The product of an empty iterator is one.
+impl Product for BigUint {
+    fn product<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BigUint> for BigUint {
+    fn product<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, x| acc * x)
     }
 }
 
@@ -167,11 +1085,77 @@ pub struct BigInt {
     inner: bigint::BigInt
 }
 
+/// Encoded as the sign followed by the magnitude, the magnitude being encoded like [`BigUint`].
+/// Human-readable formats get a hex string with a leading `-` for negative values,
+/// while binary formats get a tuple of the sign as `-1`, `0` or `1`, and the big-endian magnitude bytes.
+impl Serialize for BigInt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer {
+        let (sign, magnitude) = self.inner.to_bytes_be();
+        if serializer.is_human_readable() {
+            let hex = BigUint::from_bytes_be(&magnitude).to_hex();
+            return match sign {
+                Sign::Minus => format!("-{hex}"),
+                _ => hex,
+            }.serialize(serializer)
+        }
+
+        let sign: i8 = match sign {
+            Sign::Minus => -1,
+            Sign::NoSign => 0,
+            Sign::Plus => 1,
+        };
+        (sign, magnitude).serialize(serializer)
+    }
+}
+
+impl <'de>Deserialize<'de> for BigInt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            let hex = String::deserialize(deserializer)?;
+            let (sign, digits) = match hex.strip_prefix('-') {
+                Some(digits) => (Sign::Minus, digits),
+                None => (Sign::Plus, hex.as_str()),
+            };
+            let magnitude = BigUint::from_hex(digits).map_err(serde::de::Error::custom)?;
+            return Ok(BigInt{inner: bigint::BigInt::from_bytes_be(sign, &magnitude.to_bytes_be())})
+        }
+
+        let (sign, magnitude) = <(i8, Vec<u8>)>::deserialize(deserializer)?;
+        let sign = match sign {
+            -1 => Sign::Minus,
+            0 => Sign::NoSign,
+            1 => Sign::Plus,
+            _ => return Err(serde::de::Error::custom(format!("{sign} is not a valid sign"))),
+        };
+        Ok(BigInt{inner: bigint::BigInt::from_bytes_be(sign, &magnitude)})
+    }
+}
+
 impl BigInt {
     pub fn new(sign: Sign, digits: Vec<u32>) -> Self {
         BigInt{inner: bigint::BigInt::new(sign, digits)}
     }
 
+    pub fn zero() -> Self {
+        BigInt{inner: bigint::BigInt::default()}
+    }
+
+    pub fn one() -> Self {
+        BigInt::new(Sign::Plus, vec![1])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        num::Zero::is_zero(&self.inner)
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.inner.sign() == Sign::Plus && self.inner.magnitude().bits() == 1
+    }
+
     pub fn from_i32(value: i32) -> Result<Self,String> {
         let inner = bigint::BigInt::from_i32(value).ok_or(format!("{value} could not be converted to BigInt"))?;
         Ok(BigInt{inner})
@@ -180,11 +1164,149 @@ impl BigInt {
     pub fn gcd(&self, other: &Self) -> Self {
         BigInt{inner: self.inner.gcd(&other.inner)}
     }
+
+    pub fn sign(&self) -> Sign {
+        self.inner.sign()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.inner.sign() == Sign::Minus
+    }
+
+    pub fn abs(&self) -> Self {
+        BigInt{inner: num::Signed::abs(&self.inner)}
+    }
+
+    /// Reduces `self` into `[0, modulus)`, as in mathematical `mod`. Unlike `%`, whose remainder takes the sign
+    /// of `self`, this is never negative, so `-1` gives `modulus - 1`.
+    ///
+    /// # Panics
+    /// Panics if `modulus` is zero.
+    pub fn mod_floor(&self, modulus: &BigUint) -> BigUint {
+        self.rem_euclid(&BigInt::from(modulus)).into()
+    }
+
+    /// Gives the quotient, truncated towards zero, and the remainder, which has the sign of `self`.
+    ///
+    /// # Panics
+    /// Panics if `rhs` is zero, see [`BigInt::checked_div_rem`].
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let (quotient, remainder) = self.inner.div_rem(&rhs.inner);
+        (BigInt{inner: quotient}, BigInt{inner: remainder})
+    }
+
+    /// Euclidean division, the quotient `q` such that `self = q * rhs + r` with `0 <= r < |rhs|`,
+    /// mirroring [`i64::div_euclid`].
+    ///
+    /// # Panics
+    /// Panics if `rhs` is zero.
+    pub fn div_euclid(&self, rhs: &Self) -> Self {
+        self.div_rem_euclid(rhs).0
+    }
+
+    /// Euclidean remainder, always in `[0, |rhs|)` regardless of the signs, mirroring [`i64::rem_euclid`].
+    ///
+    /// # Panics
+    /// Panics if `rhs` is zero.
+    pub fn rem_euclid(&self, rhs: &Self) -> Self {
+        self.div_rem_euclid(rhs).1
+    }
+
+    fn div_rem_euclid(&self, rhs: &Self) -> (Self, Self) {
+        let (quotient, remainder) = self.div_rem(rhs);
+        if !remainder.is_negative() {
+            return (quotient, remainder)
+        }
+        if rhs.is_negative() {
+            (quotient + BigInt::one(), remainder - rhs)
+        } else {
+            (quotient - BigInt::one(), remainder + rhs)
+        }
+    }
+
+    fn cmp_u64(&self, other: u64) -> Ordering {
+        match self.inner.to_i128() {
+            Some(value) => value.cmp(&i128::from(other)),
+            None if self.is_negative() => Ordering::Less,
+            None => Ordering::Greater,
+        }
+    }
+
+    /// Gives `self / rhs`, or [`None`] when dividing by zero instead of panicking like [`Div`].
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None
+        }
+        Some(self / rhs)
+    }
+
+    /// Gives `self % rhs`, or [`None`] when dividing by zero instead of panicking like [`Rem`].
+    pub fn checked_rem(&self, rhs: &Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None
+        }
+        Some(self % rhs)
+    }
+
+    /// Like [`BigInt::div_rem`], but gives [`None`] when dividing by zero.
+    pub fn checked_div_rem(&self, rhs: &Self) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            return None
+        }
+        Some(self.div_rem(rhs))
+    }
+
+    /// Extended Euclidean algorithm, giving `(gcd, x, y)` such that `self * x + other * y == gcd`
+    /// with a non-negative `gcd`.
+    ///
+    /// For more see `https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm`
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let (mut old_r, mut r) = (self.clone(), other.clone());
+        let (mut old_x, mut x) = (BigInt::one(), BigInt::zero());
+        let (mut old_y, mut y) = (BigInt::zero(), BigInt::one());
+
+        while !r.is_zero() {
+            let (quotient, remainder) = old_r.div_rem(&r);
+            (old_r, r) = (r, remainder);
+            (old_x, x) = (x.clone(), old_x - &quotient * &x);
+            (old_y, y) = (y.clone(), old_y - &quotient * &y);
+        }
+
+        if old_r.is_negative() {
+            return (-old_r, -old_x, -old_y)
+        }
+        (old_r, old_x, old_y)
+    }
 }
 
 impl Display for BigInt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.inner.fmt(f)
+        Display::fmt(&self.inner, f)
+    }
+}
+
+/// Formats the magnitude in hex with the sign in front, so `-255` gives `-ff`, or `-0xff` with `{:#x}`.
+impl LowerHex for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.inner, f)
+    }
+}
+
+impl UpperHex for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        UpperHex::fmt(&self.inner, f)
+    }
+}
+
+/// Parses a decimal string with an optional leading `-`.
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(magnitude) => Ok(-BigInt::from(BigUint::parse(magnitude)?)),
+            None => Ok(BigInt::from(BigUint::parse(s)?)),
+        }
     }
 }
 
@@ -217,9 +1339,86 @@ impl Div for BigInt {
     }
 }
 
+impl Add for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: Self) -> Self::Output {
+        BigInt{inner: &self.inner + &rhs.inner}
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: Self) -> Self::Output {
+        BigInt{inner: &self.inner - &rhs.inner}
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: Self) -> Self::Output {
+        BigInt{inner: &self.inner * &rhs.inner}
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: Self) -> Self::Output {
+        BigInt{inner: &self.inner / &rhs.inner}
+    }
+}
+
+impl Rem for BigInt {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        BigInt{inner: self.inner % rhs.inner}
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: Self) -> Self::Output {
+        BigInt{inner: &self.inner % &rhs.inner}
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        BigInt{inner: -self.inner}
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> Self::Output {
+        BigInt{inner: -&self.inner}
+    }
+}
+
+forward_mixed_ref_binop!(impl Rem, rem for BigInt, BigInt);
+forward_mixed_ref_binop!(impl Add, add for BigInt, BigInt);
+forward_mixed_ref_binop!(impl Sub, sub for BigInt, BigInt);
+forward_mixed_ref_binop!(impl Mul, mul for BigInt, BigInt);
+forward_mixed_ref_binop!(impl Div, div for BigInt, BigInt);
+
+forward_assign_op!(impl AddAssign, add_assign for BigInt);
+forward_assign_op!(impl SubAssign, sub_assign for BigInt);
+forward_assign_op!(impl MulAssign, mul_assign for BigInt);
+forward_assign_op!(impl DivAssign, div_assign for BigInt);
+forward_assign_op!(impl RemAssign, rem_assign for BigInt);
+
+impl_primitive_cmp!(BigInt, u32);
+impl_primitive_cmp!(BigInt, u64);
+
+impl From<&BigUint> for BigInt {
+    fn from(item: &BigUint) -> Self {
+        BigInt{inner: bigint::BigInt::from_bytes_be(bigint::Sign::Plus, &item.inner.to_bytes_be())}
+    }
+}
+
 impl From<BigUint> for BigInt {
     fn from(item: BigUint) -> Self {
-        BigInt{inner: bigint::BigInt::from_bytes_be(bigint::Sign::Plus, &item.inner.to_bytes_be())}
+        BigInt::from(&item)
     }
 }
 
@@ -246,6 +1445,206 @@ impl From<Digit> for BigInt {
     }
 }
 
+/// Number of Miller–Rabin rounds used by [`BigUint::prime`], bounding the chance of
+/// accepting a composite, even a maliciously chosen one, by `2^(-80)`.
+pub const MILLER_RABIN_ROUNDS: u32 = 40;
+
+/// Primes are sieved up to this bound for trial division, giving a table of the first 2262 primes.
+pub const SMALL_PRIME_BOUND: usize = 20000;
+
+/// The primes below [`SMALL_PRIME_BOUND`], sieved once on first use.
+fn small_primes() -> &'static [u32] {
+    static PRIMES: OnceLock<Vec<u32>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        let mut composite = vec![false; SMALL_PRIME_BOUND];
+        let mut primes = vec![];
+        for i in 2..SMALL_PRIME_BOUND {
+            if composite[i] {
+                continue
+            }
+            primes.push(i as u32);
+            for multiple in (i * i..SMALL_PRIME_BOUND).step_by(i) {
+                composite[multiple] = true;
+            }
+        }
+        primes
+    })
+}
+
+/// Compares two byte slices in time that depends only on the length of the longer one.
+/// Slices of different lengths are never equal.
+pub fn ct_eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = a.len() ^ b.len();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= (x ^ y) as usize;
+    }
+    std::hint::black_box(diff) == 0
+}
+
 pub fn new_prime(n: usize) -> BigUint {
     BigUint{inner: Generator::new_prime(n)}
+}
+
+/// Generates a safe prime `p` of `bits` bits, meaning `(p - 1) / 2` is also prime.
+///
+/// Candidates `q` for `(p - 1) / 2` are walked in steps of 6 from a random start, keeping `q = 5 (mod 6)`
+/// since otherwise 2 or 3 divides `q` or `p`. Each step is sieved against the small primes by
+/// updating the residues of `q`, so only candidates where neither `q` nor `2q + 1` has a small factor
+/// reach the primality tests.
+///
+/// # Panics
+/// Panics if `bits` is less than 3, as 5 is the smallest safe prime.
+pub fn new_safe_prime(bits: usize) -> BigUint {
+    assert!(bits >= 3, "there are no safe primes with fewer than 3 bits");
+    let mut rng = rand::thread_rng();
+    let q_bits = bits as u32 - 1;
+
+    // Below 64 bits q might itself be one of the small primes, which the sieve would wrongly reject.
+    if bits <= 64 {
+        loop {
+            let q = BigUint::random_bits(q_bits, &mut rng);
+            let p = (&q << 1u32) + BigUint::one();
+            if q.prime() && p.prime() {
+                return p
+            }
+        }
+    }
+
+    const STEPS: u64 = 1 << 16;
+    loop {
+        let start = BigUint::random_bits(q_bits, &mut rng);
+        let start = start.saturating_sub(&(&start % &BigUint::from(6u32))) + BigUint::from(5u32);
+        let mut residues: Vec<u32> = small_primes().iter()
+            .map(|&r| (&start.inner % r).to_u32().unwrap_or(0))
+            .collect();
+
+        for step in 0..STEPS {
+            let sieved = small_primes().iter().zip(&residues)
+                .all(|(&r, &residue)| residue != 0 && (2 * residue + 1) % r != 0);
+
+            if sieved {
+                let q = &start + &BigUint::from(6 * step);
+                if q.bit_size() != q_bits {
+                    break
+                }
+                let p = (&q << 1u32) + BigUint::one();
+                if q.is_probable_prime(1, &mut rng) && p.is_probable_prime(1, &mut rng) && q.prime() && p.prime() {
+                    return p
+                }
+            }
+
+            for (&r, residue) in small_primes().iter().zip(residues.iter_mut()) {
+                *residue = (*residue + 6) % r;
+            }
+        }
+    }
+}
+
+/// Samples candidates uniformly from `[lo, hi)` until one is prime, as judged by [`BigUint::prime`].
+///
+/// # Errors
+/// An error is returned if the range is empty, or if no prime is found after a number of candidates
+/// far beyond what a range this size should need, which happens when it contains no primes.
+pub fn new_prime_in_range(lo: &BigUint, hi: &BigUint, rng: &mut (impl RngCore + CryptoRng)) -> Result<BigUint,String> {
+    new_prime_in_range_cancellable(lo, hi, rng, &|| false, &|_| ()).map(|prime| prime.expect("the search is never cancelled"))
+}
+
+/// A strong prime `p` together with the large primes that make it strong: `r` divides `p - 1`,
+/// `s` divides `p + 1` and `t` divides `r - 1`. See [`new_strong_prime_in_range`].
+#[derive(Clone, Debug)]
+pub struct StrongPrime {
+    pub p: BigUint,
+    pub r: BigUint,
+    pub s: BigUint,
+    pub t: BigUint,
+}
+
+/// Bits below half the size of `p` that the auxiliary primes `s,t` of [`new_strong_prime_in_range`] are made with,
+/// which leaves room for the multiples that fit `p` into its range.
+const STRONG_PRIME_MARGIN: u32 = 32;
+
+/// Finds a strong prime in `[lo, hi)` with Gordon's algorithm: random primes `s,t` of about half the size of `hi`,
+/// the first prime `r = 2it + 1` from a random `i`, and then a random prime `p = p0 + 2jrs`, where
+/// `p0 = 2 * (s^(r - 2) mod r) * s - 1`, so that `r | p - 1` and `s | p + 1`.
+///
+/// # Errors
+/// An error is returned if `hi` is too small to have half-size auxiliary primes, if there is no room for
+/// `p` in the range, or if one of the prime searches gives up as in [`new_prime_in_range`].
+pub fn new_strong_prime_in_range(lo: &BigUint, hi: &BigUint, rng: &mut (impl RngCore + CryptoRng)) -> Result<StrongPrime,String> {
+    new_strong_prime_in_range_cancellable(lo, hi, rng, &|| false, &|_| ()).map(|prime| prime.expect("the search is never cancelled"))
+}
+
+/// [`new_strong_prime_in_range`] that gives up with `Ok(None)` once `cancelled` returns true,
+/// and calls `tested` as in [`new_prime_in_range_cancellable`] for the candidates of `s`, `t`, `r` and `p`.
+pub(crate) fn new_strong_prime_in_range_cancellable(lo: &BigUint, hi: &BigUint, rng: &mut (impl RngCore + CryptoRng), cancelled: &impl Fn() -> bool, tested: &impl Fn(bool)) -> Result<Option<StrongPrime>,String> {
+    let aux_bits = (hi.bit_size() / 2).checked_sub(STRONG_PRIME_MARGIN).filter(|&bits| bits >= 16)
+        .ok_or(format!("[{lo}, {hi}) is too small for strong primes"))?;
+    let aux_range = (BigUint::two_pow(aux_bits - 1), BigUint::two_pow(aux_bits));
+    let one = BigUint::one();
+    let two = BigUint::from(2u32);
+
+    let Some(s) = new_prime_in_range_cancellable(&aux_range.0, &aux_range.1, rng, cancelled, tested)? else { return Ok(None) };
+    let Some(t) = new_prime_in_range_cancellable(&aux_range.0, &aux_range.1, rng, cancelled, tested)? else { return Ok(None) };
+
+    // r = 2it + 1 for the first prime from a random i below 2^16.
+    let mut i = BigUint::random_below(&BigUint::two_pow(16), rng) + 1u32;
+    let r = loop {
+        if cancelled() {
+            return Ok(None)
+        }
+        let r = &(&two * &i) * &t + &one;
+        let is_prime = r.prime();
+        tested(is_prime);
+        if is_prime {
+            break r
+        }
+        i += &one;
+    };
+
+    // p0 = 1 mod r and -1 mod s, by Fermat's little theorem s^(r - 2) is the inverse of s modulo r.
+    let r_minus_two = r.saturating_sub(&two);
+    let p0 = (&(&two * &s.modpow(&r_minus_two, &r)) * &s).saturating_sub(&one);
+    let step = &(&two * &r) * &s;
+
+    // p = p0 + j * step for j in [j_lo, j_hi), which are all in [lo, hi).
+    let j_lo = lo.saturating_sub(&p0).div_rem(&step).0 + &one;
+    let j_hi = hi.saturating_sub(&p0).saturating_sub(&one).div_rem(&step).0;
+    let width = j_hi.checked_sub(&j_lo).filter(|width| !width.is_zero()).ok_or(format!("no room for a strong prime in [{lo}, {hi})"))?;
+
+    let attempts = 1000 + 100 * hi.bit_size();
+    for _ in 0..attempts {
+        if cancelled() {
+            return Ok(None)
+        }
+        let j = &j_lo + &BigUint::random_below(&width, rng);
+        let p = &p0 + &(&j * &step);
+        let is_prime = p.prime();
+        tested(is_prime);
+        if is_prime {
+            return Ok(Some(StrongPrime { p, r, s, t }))
+        }
+    }
+    Err(format!("no strong prime found in [{lo}, {hi}) after {attempts} candidates"))
+}
+
+/// [`new_prime_in_range`] that gives up with `Ok(None)` once `cancelled` returns true, which is asked before each candidate,
+/// and calls `tested` with whether each candidate is prime.
+pub(crate) fn new_prime_in_range_cancellable(lo: &BigUint, hi: &BigUint, rng: &mut (impl RngCore + CryptoRng), cancelled: &impl Fn() -> bool, tested: &impl Fn(bool)) -> Result<Option<BigUint>,String> {
+    let width = hi.checked_sub(lo).filter(|width| width.bit_size() > 0).ok_or(format!("the range [{lo}, {hi}) is empty"))?;
+
+    let attempts = 1000 + 100 * hi.bit_size();
+    for _ in 0..attempts {
+        if cancelled() {
+            return Ok(None)
+        }
+        let candidate = lo + &BigUint::random_below(&width, rng);
+        let is_prime = candidate.prime();
+        tested(is_prime);
+        if is_prime {
+            return Ok(Some(candidate))
+        }
+    }
+    Err(format!("no prime found in [{lo}, {hi}) after {attempts} candidates"))
 }
\ No newline at end of file
@@ -0,0 +1,162 @@
+//! Montgomery multiplication on 64-bit limbs.
+//!
+//! Numbers are kept in Montgomery form `x * R mod n`, where `R = 2^(64 * limbs)`. In this form
+//! a modular multiplication needs no division, only multiplications and shifts by whole limbs,
+//! which is what makes [`MontgomeryCtx::pow`] faster than exponentiating with `%`.
+//!
+//! For more see `https://en.wikipedia.org/wiki/Montgomery_modular_multiplication`
+
+use super::BigUint;
+
+/// Width of the exponent windows used by [`MontgomeryCtx::pow`].
+const WINDOW_BITS: u32 = 4;
+
+#[derive(Clone, Debug)]
+pub(crate) struct MontgomeryCtx {
+    modulus: Vec<u64>,
+    /// `-modulus^(-1) mod 2^64`
+    n0_inv: u64,
+    /// `R^2 mod modulus`, used to move numbers into Montgomery form.
+    r2: Vec<u64>,
+}
+
+impl MontgomeryCtx {
+    /// Creates a context for an odd `modulus`, returning [`None`] for even moduli (including zero),
+    /// which Montgomery multiplication cannot handle.
+    pub(crate) fn new(modulus: &BigUint) -> Option<Self> {
+        let limbs = to_limbs(modulus, 0);
+        if limbs.first().is_none_or(|n0| n0 & 1 == 0) {
+            return None
+        }
+
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inv)));
+        }
+
+        let r2 = BigUint::two_pow(128 * limbs.len() as u32) % modulus.clone();
+        Some(MontgomeryCtx {
+            r2: to_limbs(&r2, limbs.len()),
+            n0_inv: inv.wrapping_neg(),
+            modulus: limbs,
+        })
+    }
+
+    pub(crate) fn to_mont(&self, x: &BigUint) -> Vec<u64> {
+        let reduced = x % &self.modulus();
+        self.mul(&to_limbs(&reduced, self.modulus.len()), &self.r2)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn from_mont(&self, x: &[u64]) -> BigUint {
+        let mut one = vec![0; self.modulus.len()];
+        one[0] = 1;
+        from_limbs(&self.mul(x, &one))
+    }
+
+    pub(crate) fn modulus(&self) -> BigUint {
+        from_limbs(&self.modulus)
+    }
+
+    /// Multiplies two numbers in Montgomery form, giving `a * b * R^(-1) mod n`.
+    /// This is the coarsely integrated operand scanning (CIOS) variant.
+    pub(crate) fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = &self.modulus;
+        let s = n.len();
+        let mut t = vec![0u64; s + 2];
+
+        for &b_i in b.iter().take(s) {
+            let mut carry = 0u64;
+            for j in 0..s {
+                let uv = t[j] as u128 + a[j] as u128 * b_i as u128 + carry as u128;
+                t[j] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[s] as u128 + carry as u128;
+            t[s] = uv as u64;
+            t[s + 1] = (uv >> 64) as u64;
+
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let uv = t[0] as u128 + m as u128 * n[0] as u128;
+            let mut carry = (uv >> 64) as u64;
+            for j in 1..s {
+                let uv = t[j] as u128 + m as u128 * n[j] as u128 + carry as u128;
+                t[j - 1] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[s] as u128 + carry as u128;
+            t[s - 1] = uv as u64;
+            t[s] = t[s + 1] + (uv >> 64) as u64;
+        }
+
+        let mut result = t[..s].to_vec();
+        if t[s] != 0 || !less_than(&result, n) {
+            sub_in_place(&mut result, n);
+        }
+        result
+    }
+
+    /// Computes `base^exponent mod n` with fixed windows of [`WINDOW_BITS`] bits.
+    pub(crate) fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let mut table = vec![self.to_mont(&BigUint::from(1u32)), self.to_mont(base)];
+        for i in 2..1 << WINDOW_BITS {
+            let next = self.mul(&table[i - 1], &table[1]);
+            table.push(next);
+        }
+
+        let exponent = exponent.to_bytes_le();
+        let windows = exponent.len() as u32 * 8 / WINDOW_BITS;
+        let mut result = table[0].clone();
+        for w in (0..windows).rev() {
+            for _ in 0..WINDOW_BITS {
+                result = self.mul(&result, &result);
+            }
+            let bit = w * WINDOW_BITS;
+            let window = (exponent[(bit / 8) as usize] >> (bit % 8)) & ((1 << WINDOW_BITS) - 1);
+            if window != 0 {
+                result = self.mul(&result, &table[window as usize]);
+            }
+        }
+        self.from_mont(&result)
+    }
+}
+
+/// Little-endian 64-bit limbs of `x`, zero-extended to at least `len` limbs.
+fn to_limbs(x: &BigUint, len: usize) -> Vec<u64> {
+    let mut limbs: Vec<u64> = x.to_bytes_le()
+        .chunks(8)
+        .map(|chunk| chunk.iter().rev().fold(0, |limb, &byte| limb << 8 | byte as u64))
+        .collect();
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    if limbs.len() < len {
+        limbs.resize(len, 0);
+    }
+    limbs
+}
+
+fn from_limbs(limbs: &[u64]) -> BigUint {
+    let bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+fn less_than(a: &[u64], b: &[u64]) -> bool {
+    for (x, y) in a.iter().zip(b).rev() {
+        if x != y {
+            return x < y
+        }
+    }
+    false
+}
+
+/// Subtracts `b` from `a`, wrapping around on borrow out of the top limb.
+fn sub_in_place(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (diff, borrow_1) = x.overflowing_sub(y);
+        let (diff, borrow_2) = diff.overflowing_sub(borrow as u64);
+        *x = diff;
+        borrow = borrow_1 || borrow_2;
+    }
+}
@@ -0,0 +1,71 @@
+//! Property testing support for [`BigUint`] and [`BigInt`], enabled by the `proptest` feature.
+//!
+//! Besides the [`Arbitrary`] impls, which give values of up to [`DEFAULT_BITS`] bits, this module has
+//! strategies for picking the bit size yourself and for generating probable primes.
+//!
+//! # Examples
+//! ```rust
+//! use proptest::prelude::*;
+//! use rustnetworking::big_num::strategy::up_to_bits;
+//!
+//! proptest! {
+//!     fn value_fits_in_bits(x in up_to_bits(100)) {
+//!         prop_assert!(x.bit_size() <= 100);
+//!     }
+//! }
+//! # value_fits_in_bits();
+//! ```
+
+use proptest::{prelude::*, collection::vec};
+use rand::{SeedableRng, rngs::StdRng};
+
+use super::{BigUint, BigInt, new_prime_in_range};
+
+/// Bit size bound of the values given by the [`Arbitrary`] impls.
+pub const DEFAULT_BITS: u32 = 256;
+
+/// Any value in `[0, 2^bits)`. Shrinks towards zero.
+pub fn up_to_bits(bits: u32) -> impl Strategy<Value = BigUint> {
+    let bytes = bits.div_ceil(8);
+    let excess = bytes * 8 - bits;
+    vec(any::<u8>(), bytes as usize).prop_map(move |bytes| BigUint::from_bytes_le(&bytes) >> excess)
+}
+
+/// A probable prime of exactly `bits` bits, for `bits` of at least 2.
+///
+/// The prime is found by [`new_prime_in_range`] with a generator seeded from proptest,
+/// so a failing case is reproducible, but it does not shrink.
+pub fn probable_prime(bits: u32) -> impl Strategy<Value = BigUint> {
+    assert!(bits >= 2, "there are no primes of fewer than 2 bits");
+    any::<[u8; 32]>().no_shrink().prop_map(move |seed| {
+        let mut rng = StdRng::from_seed(seed);
+        new_prime_in_range(&BigUint::two_pow(bits - 1), &BigUint::two_pow(bits), &mut rng)
+            .expect("every bit size from 2 up has a prime")
+    })
+}
+
+/// Any value in `(-2^bits, 2^bits)`. Shrinks towards zero.
+pub fn signed_up_to_bits(bits: u32) -> impl Strategy<Value = BigInt> {
+    (any::<bool>(), up_to_bits(bits)).prop_map(|(negative, magnitude)| {
+        let x = BigInt::from(magnitude);
+        if negative { -x } else { x }
+    })
+}
+
+impl Arbitrary for BigUint {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<BigUint>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        up_to_bits(DEFAULT_BITS).boxed()
+    }
+}
+
+impl Arbitrary for BigInt {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<BigInt>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        signed_up_to_bits(DEFAULT_BITS).boxed()
+    }
+}
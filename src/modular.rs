@@ -3,7 +3,57 @@
 //! For more about modular arithmetic see
 //! `https://en.wikipedia.org/wiki/Modular_arithmetic`
 
-use crate::big_num::{BigInt,BigUint, Digit};
+use std::{fmt::Display, collections::HashMap};
+
+use crate::big_num::{self, BigInt,BigUint};
+
+/// Errors from the functions in [`crate::modular`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// `x` has no inverse because it shares a factor with `modulus`.
+    NotInvertible { x: BigUint, modulus: BigUint },
+    /// The modulus is zero, so there is no ring to work in.
+    ZeroModulus,
+    /// The modulus at `index` shares a factor with an earlier one, so the moduli are not pairwise coprime.
+    NotCoprime { index: usize },
+    /// The modulus is even, but the function needs an odd one.
+    EvenModulus,
+    /// The modulus is not prime, but the function needs a prime.
+    NotPrime,
+    /// `a * x = b (mod modulus)` has no solution because `gcd(a, modulus)` doesn't divide `b`.
+    NoSolution { gcd: BigUint, b: BigUint },
+    /// The search bound of [`discrete_log`] is above [`MAX_DISCRETE_LOG_BOUND`].
+    BoundTooLarge { bound: u64 },
+    /// The window size of [`FixedBaseExp`] is outside `1..=MAX_WINDOW_BITS`.
+    InvalidWindowBits { bits: u32 },
+    /// A system of congruences was given a different number of residues and moduli.
+    LengthMismatch { residues: usize, moduli: usize },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotInvertible { x, modulus } => write!(f, "{x} is not invertible modulo {modulus}"),
+            Error::ZeroModulus => write!(f, "modulus is zero"),
+            Error::NoSolution { gcd, b } => write!(f, "no solution since gcd {gcd} does not divide {b}"),
+            Error::BoundTooLarge { bound } => write!(f, "bound {bound} is above the maximum of {MAX_DISCRETE_LOG_BOUND}"),
+            Error::InvalidWindowBits { bits } => write!(f, "window of {bits} bits is not in 1 to {MAX_WINDOW_BITS}"),
+            Error::EvenModulus => write!(f, "modulus is even"),
+            Error::NotPrime => write!(f, "modulus is not prime"),
+            Error::NotCoprime { index } => write!(f, "modulus {index} is not coprime to the moduli before it"),
+            Error::LengthMismatch { residues, moduli } => write!(f, "got {residues} residues but {moduli} moduli"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Lets functions that still report errors as [`String`] propagate [`Error`] with `?`.
+impl From<Error> for String {
+    fn from(value: Error) -> Self {
+        value.to_string()
+    }
+}
 
 /// [`inverse`] calculates the modular inverse `x^(-1) (mod modulus)`.
 /// 
@@ -14,53 +64,583 @@ use crate::big_num::{BigInt,BigUint, Digit};
 /// 
 /// This implementation is based on the algorithm found at 
 /// `https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm#Computing_multiplicative_inverses_in_modular_structures`
+/// and runs as a loop in [`BigInt::extended_gcd`], so stack usage doesn't grow with the size of the modulus.
+/// 
+/// # Errors
+/// [`Error::NotInvertible`] is returned if `x` and `modulus` are not coprime,
+/// and [`Error::ZeroModulus`] if `modulus` is zero.
+/// 
+pub fn inverse(x: &BigUint, modulus: &BigUint) -> Result<BigUint,Error> {
+    if modulus.is_zero() {
+        return Err(Error::ZeroModulus)
+    }
+
+    let (gcd, t, _) = BigInt::from(x).extended_gcd(&BigInt::from(modulus));
+
+    if gcd != 1u32 {
+        return Err(Error::NotInvertible { x: x.clone(), modulus: modulus.clone() })
+    }
+
+    Ok(t.mod_floor(modulus))
+}
+
+/// [`mod_add`] calculates `a + b (mod n)`, in `[0, n)` even if `a` or `b` is not.
+/// 
+/// # Panics
+/// Panics if `n` is zero, like `%`.
+pub fn mod_add(a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+    &(&(a % n) + &(b % n)) % n
+}
+
+/// [`mod_sub`] calculates `a - b (mod n)`, wrapping around to `n - (b - a)` when `a < b` instead
+/// of underflowing like `Sub` does.
+/// 
+/// # Panics
+/// Panics if `n` is zero, like `%`.
+pub fn mod_sub(a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+    mod_add(a, &mod_neg(b, n), n)
+}
+
+/// [`mod_mul`] calculates `a * b (mod n)`, in `[0, n)`.
+/// 
+/// # Panics
+/// Panics if `n` is zero, like `%`.
+pub fn mod_mul(a: &BigUint, b: &BigUint, n: &BigUint) -> BigUint {
+    &(&(a % n) * &(b % n)) % n
+}
+
+/// [`mod_neg`] calculates `-a (mod n)`, which is `n - (a mod n)`, or `0` when `n` divides `a`.
+/// 
+/// # Panics
+/// Panics if `n` is zero, like `%`.
+pub fn mod_neg(a: &BigUint, n: &BigUint) -> BigUint {
+    let a = a % n;
+    if a.is_zero() {
+        return a
+    }
+    // a was reduced below n, so this never saturates.
+    n.saturating_sub(&a)
+}
+
+/// [`inverse_signed`] calculates the modular inverse of a possibly negative `x`, by first reducing it
+/// into `[0, modulus)` with [`BigInt::mod_floor`] and then calling [`inverse`].
+/// 
+/// # Errors
+/// See [`inverse`], with `x` in [`Error::NotInvertible`] being the reduced value.
+/// 
+pub fn inverse_signed(x: &BigInt, modulus: &BigUint) -> Result<BigUint,Error> {
+    if modulus.is_zero() {
+        return Err(Error::ZeroModulus)
+    }
+    inverse(&x.mod_floor(modulus), modulus)
+}
+
+/// [`crt`] solves the system of congruences `x = residues[i] (mod moduli[i])` using the Chinese Remainder Theorem,
+/// giving the unique solution in `[0, m)` where `m` is the product of the moduli.
+/// 
+/// The solution is built up one congruence at a time: given `x` solving the first congruences with product `m`,
+/// the next one `r (mod n)` is solved by `x + m * ((r - x) * m^(-1) mod n)`.
+/// An empty system is solved by `0`.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Chinese_remainder_theorem`
+/// 
+/// # Errors
+/// [`Error::LengthMismatch`] is returned if the slices have different lengths,
+/// [`Error::ZeroModulus`] if any modulus is zero, and [`Error::NotCoprime`] if the moduli are not pairwise coprime.
+/// 
+pub fn crt(residues: &[BigUint], moduli: &[BigUint]) -> Result<BigUint,Error> {
+    if residues.len() != moduli.len() {
+        return Err(Error::LengthMismatch { residues: residues.len(), moduli: moduli.len() })
+    }
+
+    let mut x = BigUint::zero();
+    let mut product = BigUint::one();
+    for (index, (residue, modulus)) in residues.iter().zip(moduli).enumerate() {
+        if modulus.is_zero() {
+            return Err(Error::ZeroModulus)
+        }
+        let inv = (&product % modulus).modinv(modulus).map_err(|_| Error::NotCoprime { index })?;
+
+        // (r mod n) + n is always at least x mod n, so this never saturates.
+        let difference = (residue % modulus + modulus).saturating_sub(&(&x % modulus)) % modulus;
+        x += &product * &(&(difference * inv) % modulus);
+        product *= modulus;
+    }
+    Ok(x)
+}
+
+/// [`jacobi`] calculates the Jacobi symbol `(a/n)` for an odd `n`, which is `-1`, `0` or `1`.
+/// 
+/// For a prime `n` it's the Legendre symbol, telling whether `a` is a square modulo `n`, see [`legendre`].
+/// The symbol is computed without factoring `n`, by pulling out factors of 2 and flipping the symbol
+/// with the law of quadratic reciprocity until `a` is reduced to zero.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Jacobi_symbol`
 /// 
 /// # Errors
-/// An error is returned if the input is invertable.
+/// [`Error::EvenModulus`] is returned if `n` is even, which includes zero.
 /// 
-pub fn inverse(x: BigUint, modulus: BigUint) -> Result<BigUint,String> {
-    struct FData {
-        t: BigInt,
-        new_t: BigInt,
-        r: BigInt,
-        new_r: BigInt,
+pub fn jacobi(a: &BigUint, n: &BigUint) -> Result<i8,Error> {
+    if n.is_even() {
+        return Err(Error::EvenModulus)
     }
 
-    fn f(data: FData) -> FData {
-        if data.new_r == Digit::_0.into() {
-            return data
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut symbol = 1;
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos as usize;
+        let n_mod_8 = &n % 8u32;
+        if twos % 2 == 1 && (n_mod_8 == 3u32 || n_mod_8 == 5u32) {
+            symbol = -symbol;
+        }
+
+        std::mem::swap(&mut a, &mut n);
+        if &a % 4u32 == 3u32 && &n % 4u32 == 3u32 {
+            symbol = -symbol;
         }
+        a %= &n;
+    }
+
+    if n.is_one() {
+        Ok(symbol)
+    } else {
+        Ok(0)
+    }
+}
+
+/// [`legendre`] calculates the Legendre symbol `(a/p)` for an odd prime `p`: `1` if `a` is a non-zero square
+/// modulo `p`, `-1` if it isn't a square, and `0` if `p` divides `a`.
+/// 
+/// This is [`jacobi`], so for a composite `p` the result is the Jacobi symbol instead.
+/// 
+/// # Errors
+/// [`Error::EvenModulus`] is returned if `p` is even.
+/// 
+pub fn legendre(a: &BigUint, p: &BigUint) -> Result<i8,Error> {
+    jacobi(a, p)
+}
+
+/// [`is_quadratic_residue`] tells whether `a` is a square modulo an odd prime `p`, using Euler's criterion
+/// that `a^((p - 1) / 2) mod p` is `1` for non-zero squares and `p - 1` otherwise.
+/// 
+/// As `0 = 0^2`, an `a` divisible by `p` counts as a square, matching [`sqrt`] giving `Some(0)` for it,
+/// even though its [`legendre`] symbol is `0` rather than `1`.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Euler%27s_criterion`
+/// 
+/// # Errors
+/// [`Error::EvenModulus`] is returned if `p` is even.
+/// 
+pub fn is_quadratic_residue(a: &BigUint, p: &BigUint) -> Result<bool,Error> {
+    if p.is_even() {
+        return Err(Error::EvenModulus)
+    }
 
-        let quotient = data.r.clone() / data.new_r.clone();
-        let (t, new_t) = g(data.t, data.new_t, quotient.clone());
-        let (r, new_r) = g(data.r, data.new_r, quotient);
+    let a = a % p;
+    if a.is_zero() {
+        return Ok(true)
+    }
+    let exponent = &p.saturating_sub(&BigUint::one()) >> 1u32;
+    Ok(a.modpow(&exponent, p).is_one())
+}
 
-        f(FData{
-            t,
-            new_t,
-            r,
-            new_r
+/// [`sqrt`] calculates a square root of `a` modulo an odd prime `p`, that is an `r` with `r^2 = a (mod p)`,
+/// or [`None`] if `a` is not a square modulo `p`. The other root is `p - r`.
+/// 
+/// For `p = 3 (mod 4)` the root is simply `a^((p + 1) / 4)`. Other primes use the Tonelli–Shanks algorithm,
+/// which writes `p - 1 = q * 2^s` and corrects a first guess using a non-residue in at most `s` steps.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm`
+/// 
+/// # Errors
+/// [`Error::EvenModulus`] is returned if `p` is even, and [`Error::NotPrime`] if the search for a non-residue
+/// finds that `p` isn't prime, which it always does for `p = 1` and odd prime powers like `9`. For other odd `p`
+/// that aren't prime, the result is meaningless.
+/// 
+pub fn sqrt(a: &BigUint, p: &BigUint) -> Result<Option<BigUint>,Error> {
+    if p.is_one() {
+        return Err(Error::NotPrime)
+    }
+    match jacobi(a, p)? {
+        0 => return Ok(Some(BigUint::zero())),
+        -1 => return Ok(None),
+        _ => {}
+    }
+    let a = a % p;
+
+    if p % 4u32 == 3u32 {
+        return Ok(Some(a.modpow(&(&(p + 1u32) / 4u32), p)))
+    }
+
+    let p_minus_one = p.saturating_sub(&BigUint::one());
+    let s = p_minus_one.trailing_zeros().unwrap_or(0);
+    let q = &p_minus_one >> s as usize;
+
+    // Half of all z below a prime p are non-residues. A z sharing a factor with p, or no non-residue
+    // below p at all, means p isn't prime, and searching on would never end.
+    let mut z = BigUint::from(2u32);
+    loop {
+        if &z >= p {
+            return Err(Error::NotPrime)
+        }
+        match jacobi(&z, p)? {
+            -1 => break,
+            0 => return Err(Error::NotPrime),
+            _ => z += BigUint::one(),
+        }
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&(&(&q + 1u32) / 2u32), p);
+    while !t.is_one() {
+        let mut i = 0;
+        let mut t_pow = t.clone();
+        while !t_pow.is_one() {
+            t_pow = &(&t_pow * &t_pow) % p;
+            i += 1;
+            if i == m {
+                return Ok(None)
+            }
+        }
+
+        let b = c.modpow(&BigUint::two_pow((m - i - 1) as u32), p);
+        m = i;
+        c = &(&b * &b) % p;
+        t = &(&t * &c) % p;
+        r = &(&r * &b) % p;
+    }
+    Ok(Some(r))
+}
+
+/// [`phi_from_primes`] calculates Euler's totient `φ(n)` of `n`, the product of the distinct `primes`,
+/// as the product of `p - 1` over the primes. No primes gives `φ(1) = 1`.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Euler%27s_totient_function`
+pub fn phi_from_primes(primes: &[BigUint]) -> BigUint {
+    primes.iter().map(|p| p.saturating_sub(&BigUint::one())).product()
+}
+
+/// [`carmichael_from_primes`] calculates the Carmichael function `λ(n)` of `n`, the product of the distinct `primes`,
+/// as the least common multiple of `p - 1` over the primes. It's the smallest `m` such that `a^m = 1 (mod n)`
+/// for every `a` coprime to `n`, and divides [`phi_from_primes`]. No primes gives `λ(1) = 1`.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Carmichael_function`
+pub fn carmichael_from_primes(primes: &[BigUint]) -> BigUint {
+    primes.iter()
+        .map(|p| p.saturating_sub(&BigUint::one()))
+        .fold(BigUint::one(), |lambda, p_minus_one| lambda.lcm(&p_minus_one))
+}
+
+/// Largest `order_bound` accepted by [`discrete_log`], `2^48`. The search takes around `sqrt(order_bound)`
+/// time and memory, so this keeps it to small groups and out of cryptographic sizes.
+pub const MAX_DISCRETE_LOG_BOUND: u64 = 1 << 48;
+
+/// [`discrete_log`] finds the smallest `x < order_bound` such that `base^x = target (mod modulus)`,
+/// or [`None`] if there is no such `x`.
+/// 
+/// This is the baby-step giant-step algorithm. With `m = ceil(sqrt(order_bound))` it stores the baby steps
+/// `base^j` for `j < m`, then takes giant steps `target * base^(-m * i)` until one of them is a baby step,
+/// giving `x = i * m + j`.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Baby-step_giant-step`
+/// 
+/// # Errors
+/// [`Error::BoundTooLarge`] is returned if `order_bound` is above [`MAX_DISCRETE_LOG_BOUND`],
+/// [`Error::ZeroModulus`] if `modulus` is zero, and [`Error::NotInvertible`] if `base` and `modulus` aren't coprime.
+/// 
+pub fn discrete_log(base: &BigUint, target: &BigUint, modulus: &BigUint, order_bound: u64) -> Result<Option<BigUint>,Error> {
+    if order_bound > MAX_DISCRETE_LOG_BOUND {
+        return Err(Error::BoundTooLarge { bound: order_bound })
+    }
+    if modulus.is_zero() {
+        return Err(Error::ZeroModulus)
+    }
+    let base = base % modulus;
+    let m = BigUint::from(order_bound).sqrt().to_u64().unwrap_or(0);
+    let m = if m * m < order_bound { m + 1 } else { m };
+
+    let mut baby_steps = HashMap::with_capacity(m as usize);
+    let mut step = BigUint::one() % modulus;
+    for j in 0..m {
+        baby_steps.entry(step.clone()).or_insert(j);
+        step = &(&step * &base) % modulus;
+    }
+
+    let giant_step = base.modpow(&BigUint::from(m), modulus).modinv(modulus)?;
+    let mut gamma = target % modulus;
+    for i in 0..m {
+        if let Some(j) = baby_steps.get(&gamma) {
+            let x = i * m + j;
+            return Ok((x < order_bound).then(|| BigUint::from(x)))
+        }
+        gamma = &(&gamma * &giant_step) % modulus;
+    }
+    Ok(None)
+}
+
+/// The solutions of a linear congruence found by [`solve_linear`]: the `count` numbers `first + k * step`
+/// for `k` below `count`, which are all below the modulus, since `step * count` is the modulus.
+/// 
+/// There are as many solutions as `gcd(a, modulus)`, which for `a = b = 0` is every residue of the modulus,
+/// so they are kept as these three numbers and only listed by [`LinearSolutions::iter`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearSolutions {
+    pub first: BigUint,
+    pub step: BigUint,
+    pub count: BigUint,
+}
+
+impl LinearSolutions {
+    /// The solutions in increasing order, each computed as it is taken.
+    pub fn iter(&self) -> impl Iterator<Item = BigUint> + '_ {
+        let end = &self.step * &self.count;
+        let mut x = self.first.clone();
+        std::iter::from_fn(move || {
+            (x < end).then(|| {
+                let next = &x + &self.step;
+                std::mem::replace(&mut x, next)
+            })
         })
     }
 
-    fn g(x: BigInt, new_x: BigInt, quotient: BigInt) -> (BigInt, BigInt) {
-        (new_x.clone(), x - quotient * new_x)
+    /// Whether `x` is one of the solutions.
+    pub fn contains(&self, x: &BigUint) -> bool {
+        x >= &self.first && x < &(&self.step * &self.count) && (x.saturating_sub(&self.first) % &self.step).is_zero()
     }
+}
 
-    let data = f(FData {
-        t: Digit::_0.into(),
-        new_t: Digit::_1.into(),
-        r: modulus.clone().into(),
-        new_r: x.into(),
-    });
+/// [`solve_linear`] solves the linear congruence `a * x = b (mod modulus)`, giving all `gcd(a, modulus)` solutions
+/// in `[0, modulus)` as [`LinearSolutions`].
+/// 
+/// With `g = gcd(a, modulus)` and `a * t + modulus * y = g` from the extended Euclidean algorithm, the smallest
+/// solution is `x = t * (b / g) mod (modulus / g)`, and the others are spaced out by `modulus / g`.
+/// Remark that `a = 0 (mod modulus)` has every residue as a solution when `b = 0 (mod modulus)`.
+/// 
+/// # Errors
+/// [`Error::NoSolution`] is returned if `g` doesn't divide `b`, and [`Error::ZeroModulus`] if `modulus` is zero.
+/// 
+pub fn solve_linear(a: &BigUint, b: &BigUint, modulus: &BigUint) -> Result<LinearSolutions,Error> {
+    if modulus.is_zero() {
+        return Err(Error::ZeroModulus)
+    }
+
+    let a = a % modulus;
+    let b = b % modulus;
+    let (gcd, t, _) = BigInt::from(a).extended_gcd(&BigInt::from(modulus));
+    let gcd = BigUint::from(gcd);
+    if !(&b % &gcd).is_zero() {
+        return Err(Error::NoSolution { gcd, b })
+    }
+
+    let step = modulus / &gcd;
+    let first = (t * BigInt::from(&b / &gcd)).mod_floor(&step);
+    Ok(LinearSolutions { first, step, count: gcd })
+}
+
+/// A number in Montgomery form `x * R mod n` for the modulus `n` of the [`MontgomeryCtx`] that made it.
+/// Numbers from different contexts must not be mixed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MontgomeryForm {
+    limbs: Vec<u64>
+}
 
-    if data.r > Digit::_1.into() {
-        return Err("x is not invertable".into())
+/// [`MontgomeryCtx`] does repeated modular multiplication against a fixed odd modulus without any division,
+/// by doing the setup of Montgomery multiplication once and keeping numbers in [`MontgomeryForm`].
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Montgomery_modular_multiplication`
+/// 
+/// # Examples
+/// ```rust
+/// use rustnetworking::{modular::MontgomeryCtx, big_num::BigUint};
+/// #
+/// # fn main() -> Result<(),String> {
+/// let ctx = MontgomeryCtx::new(&BigUint::from(101u32))?;
+/// let a = ctx.to_mont(&BigUint::from(20u32));
+/// let b = ctx.to_mont(&BigUint::from(30u32));
+///
+/// assert_eq!(BigUint::from(600u32 % 101), ctx.from_mont(&ctx.mul(&a, &b)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MontgomeryCtx {
+    inner: big_num::MontgomeryCtx
+}
+
+impl MontgomeryCtx {
+    /// Sets up a context for `modulus`.
+    /// 
+    /// # Errors
+    /// [`Error::ZeroModulus`] is returned if `modulus` is zero and [`Error::EvenModulus`] if it's even,
+    /// since Montgomery multiplication needs an odd modulus.
+    pub fn new(modulus: &BigUint) -> Result<Self,Error> {
+        if modulus.is_zero() {
+            return Err(Error::ZeroModulus)
+        }
+        let inner = big_num::MontgomeryCtx::new(modulus).ok_or(Error::EvenModulus)?;
+        Ok(MontgomeryCtx{inner})
     }
 
-    if data.t < Digit::_0.into() {
-        return Ok((data.t + modulus.into()).into())
+    pub fn modulus(&self) -> BigUint {
+        self.inner.modulus()
     }
 
-    Ok(data.t.into())
-}
\ No newline at end of file
+    /// Converts `x`, reduced modulo the modulus first, into Montgomery form.
+    pub fn to_mont(&self, x: &BigUint) -> MontgomeryForm {
+        MontgomeryForm{limbs: self.inner.to_mont(x)}
+    }
+
+    /// Converts `x` out of Montgomery form, giving a number in `[0, modulus)`.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_mont(&self, x: &MontgomeryForm) -> BigUint {
+        self.inner.from_mont(&x.limbs)
+    }
+
+    /// Multiplies two numbers in Montgomery form, keeping the product in Montgomery form.
+    pub fn mul(&self, a: &MontgomeryForm, b: &MontgomeryForm) -> MontgomeryForm {
+        MontgomeryForm{limbs: self.inner.mul(&a.limbs, &b.limbs)}
+    }
+
+    /// Computes `base^exponent mod modulus` on ordinary numbers, like [`BigUint::modpow`]
+    /// but without setting up a new context each time.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        self.inner.pow(base, exponent)
+    }
+}
+
+/// [`BarrettCtx`] reduces modulo a fixed modulus, which may be even, by multiplying with a precomputed
+/// reciprocal `mu = floor(4^k / n)`, where `k` is the bit size of `n`, instead of dividing.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Barrett_reduction`
+/// 
+/// # Examples
+/// ```rust
+/// use rustnetworking::{modular::BarrettCtx, big_num::BigUint};
+/// #
+/// # fn main() -> Result<(),String> {
+/// let ctx = BarrettCtx::new(&BigUint::from(100u32))?;
+///
+/// assert_eq!(BigUint::from(34u32), ctx.reduce(&BigUint::from(1234u32)));
+/// assert_eq!(BigUint::from(0u32), ctx.mulmod(&BigUint::from(20u32), &BigUint::from(30u32)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BarrettCtx {
+    modulus: BigUint,
+    mu: BigUint,
+    k: u32,
+}
+
+impl BarrettCtx {
+    /// Sets up a context for `modulus`.
+    /// 
+    /// # Errors
+    /// [`Error::ZeroModulus`] is returned if `modulus` is zero.
+    pub fn new(modulus: &BigUint) -> Result<Self,Error> {
+        if modulus.is_zero() {
+            return Err(Error::ZeroModulus)
+        }
+        let k = modulus.bit_size();
+        let mu = &BigUint::two_pow(2 * k) / modulus;
+        Ok(BarrettCtx{modulus: modulus.clone(), mu, k})
+    }
+
+    pub fn modulus(&self) -> BigUint {
+        self.modulus.clone()
+    }
+
+    /// Gives `x mod modulus`. The quotient estimate is only good for `x < 4^k`, which includes
+    /// every `x < modulus^2`, so larger `x` fall back to `%`.
+    pub fn reduce(&self, x: &BigUint) -> BigUint {
+        if x.bit_size() > 2 * self.k {
+            return x % &self.modulus
+        }
+
+        let q = &(&(x >> (self.k - 1)) * &self.mu) >> (self.k + 1);
+        // q never exceeds the true quotient, so this never saturates, and it's short by at most 2.
+        let mut r = x.saturating_sub(&(&q * &self.modulus));
+        while r >= self.modulus {
+            r -= &self.modulus;
+        }
+        r
+    }
+
+    /// Gives `a * b mod modulus`, for `a` and `b` below the modulus.
+    pub fn mulmod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(&(a * b))
+    }
+}
+
+/// Largest window accepted by [`FixedBaseExp::new`]. The table has `2^window_bits` entries per window.
+pub const MAX_WINDOW_BITS: u32 = 8;
+
+/// [`FixedBaseExp`] raises one base to many exponents modulo the same odd modulus.
+/// 
+/// Setting up precomputes `base^(j * 2^(window_bits * i))` for every window `i` of an exponent as long as the modulus,
+/// and every digit `j`. An exponentiation is then one [`MontgomeryCtx::mul`] per non-zero digit of the exponent
+/// with no squarings, in return for a table of `2^window_bits` numbers per window.
+/// 
+/// For more see `https://en.wikipedia.org/wiki/Exponentiation_by_squaring#Fixed-base_exponent`
+#[derive(Clone, Debug)]
+pub struct FixedBaseExp {
+    ctx: MontgomeryCtx,
+    base: BigUint,
+    window_bits: u32,
+    table: Vec<Vec<MontgomeryForm>>,
+}
+
+impl FixedBaseExp {
+    /// Precomputes the table for `base` modulo `modulus`.
+    /// 
+    /// # Errors
+    /// [`Error::InvalidWindowBits`] is returned if `window_bits` is not in `1..=MAX_WINDOW_BITS`,
+    /// otherwise see [`MontgomeryCtx::new`].
+    pub fn new(base: &BigUint, modulus: &BigUint, window_bits: u32) -> Result<Self,Error> {
+        if !(1..=MAX_WINDOW_BITS).contains(&window_bits) {
+            return Err(Error::InvalidWindowBits { bits: window_bits })
+        }
+        let ctx = MontgomeryCtx::new(modulus)?;
+
+        let one = ctx.to_mont(&BigUint::one());
+        let windows = modulus.bit_size().div_ceil(window_bits);
+        let mut power = ctx.to_mont(base);
+        let mut table = Vec::with_capacity(windows as usize);
+        for _ in 0..windows {
+            let mut row = vec![one.clone()];
+            for j in 1..1 << window_bits {
+                row.push(ctx.mul(&row[j - 1], &power));
+            }
+            power = ctx.mul(&row[row.len() - 1], &power);
+            table.push(row);
+        }
+
+        Ok(FixedBaseExp{ctx, base: base.clone(), window_bits, table})
+    }
+
+    /// Computes `base^exponent mod modulus`. Exponents longer than the modulus are beyond the table,
+    /// and fall back to [`MontgomeryCtx::pow`].
+    pub fn pow(&self, exponent: &BigUint) -> BigUint {
+        if exponent.bit_size() > self.table.len() as u32 * self.window_bits {
+            return self.ctx.pow(&self.base, exponent)
+        }
+
+        let bytes = exponent.to_bytes_le();
+        let mut result = self.table[0][0].clone();
+        for (i, row) in self.table.iter().enumerate() {
+            let digit = (0..self.window_bits).fold(0, |digit, b| {
+                let bit = i as u32 * self.window_bits + b;
+                let set = bytes.get((bit / 8) as usize).is_some_and(|byte| byte >> (bit % 8) & 1 == 1);
+                digit | (set as usize) << b
+            });
+            if digit != 0 {
+                result = self.ctx.mul(&result, &row[digit]);
+            }
+        }
+        self.ctx.from_mont(&result)
+    }
+}
@@ -1,47 +1,823 @@
 extern crate bincode;
+use std::{fmt::Display, sync::{Arc, mpsc, atomic::{AtomicBool, Ordering}}, thread::JoinHandle, time::SystemTime};
 use bincode::{serialize, deserialize};
 use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+use rand::{rngs::{OsRng, StdRng}, CryptoRng, RngCore, SeedableRng};
 
-use crate::big_num::{BigUint, new_prime};
+use crate::{big_num::{BigUint, new_prime_in_range_cancellable, new_strong_prime_in_range_cancellable}, modular::{self, carmichael_from_primes, phi_from_primes}};
 
-use self::{confidentiality::{Message, encrypt, Ciphertext, decrypt, Plaintext}, authenticity::{Signature, sign, verify, Verification}};
+use self::{confidentiality::{Message, encrypt, Ciphertext, decrypt, Plaintext}, authenticity::{Signature, verify, Verification}};
 
 pub mod confidentiality;
 pub mod authenticity;
+pub mod pem;
+pub mod jwk;
+pub mod openssh;
+pub mod binary;
+pub mod attestation;
+pub mod revocation;
+pub mod seal;
+pub mod keystore;
+pub mod agent;
+mod encoding;
 
+pub use self::attestation::attest;
+
+/// Public keys are equal, and hash the same, when both `n` and `e` are, so they can be kept in a
+/// [`HashSet`](std::collections::HashSet) of trusted senders.
 #[derive(Clone)]
+#[derive(PartialEq, Eq, Hash)]
 #[derive(Serialize,Deserialize,Debug)]
 pub struct PublicKey {
-    n: BigUint
+    n: BigUint,
+    /// Keys serialized before `e` was stored all used `e = 3`.
+    #[serde(default = "legacy_exponent")]
+    e: u64,
 }
 
 impl PublicKey {
+    /// The public key of modulus `n` and exponent `e`, as given by another system. Nothing is checked,
+    /// see [`PublicKey::validate`] for that.
+    pub fn new(n: BigUint, e: u64) -> Self {
+        PublicKey { n, e }
+    }
+
+    /// The same as [`PublicKey::new`].
+    pub fn from_parts(n: BigUint, e: u64) -> Self {
+        PublicKey::new(n, e)
+    }
+
+    /// The modulus `n`, the product of the primes of the secret key.
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    /// The public exponent `e`.
+    pub fn e(&self) -> u64 {
+        self.e
+    }
+
+    /// The same as [`PublicKey::e`].
+    pub fn exponent(&self) -> u64 {
+        self.e
+    }
+
     pub fn bit_size(&self) -> u32 {
         self.n.bit_size()
     }
+
+    /// The length of the modulus `n` in bytes.
+    pub fn byte_size(&self) -> usize {
+        self.n.byte_size()
+    }
+
+    /// A SHA-256 digest identifying the key, for example as a peer id.
+    ///
+    /// The digest is over `e` as 8 big endian bytes followed by `n` as [`PublicKey::byte_size`]
+    /// big endian bytes, so it doesn't depend on any serialization format. This encoding will not
+    /// change, since fingerprints are kept by others.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.e.to_be_bytes());
+        hasher.update(self.n.to_bytes_be_padded(self.byte_size()).expect("n fits in its own byte size"));
+        hasher.finalize().into()
+    }
+
+    /// [`PublicKey::fingerprint`] as 64 lowercase hex digits.
+    pub fn fingerprint_hex(&self) -> String {
+        self.fingerprint().iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Basic sanity checks of a key from elsewhere: `n` is odd and has at least [`MIN_KEY_BITS`] bits,
+    /// and `e` is odd, at least 3 and less than `n`. Whether `n` is really a product of two primes
+    /// can't be checked without the secret key, see [`validate_keypair`].
+    /// 
+    /// # Errors
+    /// Gives [`Error::InvalidKey`] naming the first check that failed.
+    pub fn validate(&self) -> Result<(),Error> {
+        let invalid = |reason: &str| Err(Error::InvalidKey(reason.into()));
+        if self.n.is_even() {
+            return invalid("n is even")
+        }
+        if self.n.bit_size() < MIN_KEY_BITS {
+            return Err(Error::InvalidKey(format!("n has {} bits, fewer than the minimum of {MIN_KEY_BITS}", self.n.bit_size())))
+        }
+        if self.e < 3 || self.e.is_multiple_of(2) {
+            return invalid("e is even or less than 3")
+        }
+        if self.n <= self.e {
+            return invalid("e is not less than n")
+        }
+        Ok(())
+    }
 }
 
+/// The primes of `n`, usually just `p,q`, together with the private exponent `d` and modulus `n`,
+/// which are computed once in [`keygen`] rather than on every [`decrypt`].
+///
+/// Keys serialized before `d` and `n` were stored only have `p,q`. Such keys still
+/// deserialize from self-describing formats like JSON, recomputing `d` and `n`, but
+/// their bincode encoding is too short and is rejected, as is the bincode encoding of keys
+/// serialized before multi-prime keys. Keys without `e` used `e = 3`.
+///
+/// With the `zeroize` feature the primes and `d` are wiped when the key is dropped, since every
+/// [`BigUint`] wipes itself, see [`BigUint::zeroize`]. Each clone is wiped when it is dropped in turn.
+///
+/// [`Debug`] shows only the size of `n` and the start of the public key's fingerprint, so a stray
+/// `dbg!` or log line does not leak the key. See [`SecretKey::debug_unsafe_full`] for everything.
 #[derive(Clone)]
-#[derive(Serialize,Deserialize,Debug)]
+#[derive(Serialize,Deserialize)]
+#[serde(into = "SecretKeyFields", try_from = "SecretKeyFields")]
 pub struct SecretKey {
+    /// At least two distinct primes, starting with `p,q`. More make a multi-prime key, see [`keygen_multiprime`].
+    /// Keys decoded from outside this crate are built with [`SecretKey::from_parts_checked`], so these are
+    /// always coprime and at least 2, which decrypting relies on.
+    primes: Vec<BigUint>,
+    e: u64,
+    d: BigUint,
+    n: BigUint,
+}
+
+impl SecretKey {
+    /// Builds the key of the primes `p,q` of another system or a known-answer test, with `e` = [`DEFAULT_E`].
+    /// See [`SecretKey::from_primes_with_exponent`] for another `e`.
+    /// 
+    /// # Errors
+    /// As for [`SecretKey::from_primes_with_exponent`].
+    pub fn from_primes(p: BigUint, q: BigUint) -> Result<Self,Error> {
+        SecretKey::from_primes_with_exponent(p, q, DEFAULT_E)
+    }
+
+    /// Builds the key of the primes `p,q` and public exponent `e`, computing `n` and `d`.
+    /// 
+    /// There is no minimum size, so known-answer tests can use tiny keys, see [`PublicKey::validate`] for that.
+    /// 
+    /// # Errors
+    /// Gives [`Error::InvalidKey`] when `p` or `q` is not prime, `p = q`, `p,q` are too close for
+    /// [`check_prime_distance`], or `e` is even, less than 3, or not coprime to `(p - 1)(q - 1)`.
+    pub fn from_primes_with_exponent(p: BigUint, q: BigUint, e: u64) -> Result<Self,Error> {
+        let invalid = |reason: &str| Err(Error::InvalidKey(reason.into()));
+        if !p.prime() {
+            return invalid("p is not prime")
+        }
+        if !q.prime() {
+            return invalid("q is not prime")
+        }
+        if p == q {
+            return invalid("q is repeated")
+        }
+        check_prime_distance(&p, &q, (&p * &q).bit_size())?;
+        if e < 3 || e.is_multiple_of(2) {
+            return invalid("e is even or less than 3")
+        }
+        let primes = vec![p, q];
+        if !BigUint::from(e).co_prime(&phi_from_primes(&primes)) {
+            return invalid("e is not coprime to φ(n)")
+        }
+        SecretKey::from_prime_list(primes, e)
+    }
+
+    /// [`SecretKey::from_components`] makes a two-prime key from `n`, `e` and `d` alone, as given by published
+    /// test vectors and some hardware security modules, by recovering `p,q` with the method of NIST SP 800-56B,
+    /// appendix C. `d e - 1` is a multiple of `λ(n)`, so for `g` coprime to `n`, repeatedly squaring
+    /// `g^r`, where `r` is the odd part of `d e - 1`, reaches a square root of 1 other than `±1` for about
+    /// half of all `g`, and such a root `x` gives `p = gcd(x - 1, n)`.
+    /// 
+    /// `d` is kept as it is, whether it is modulo `λ(n)` or `φ(n)`, and the larger prime becomes `p`, as OpenSSL has it.
+    /// 
+    /// # Errors
+    /// Gives [`Error::InvalidKey`] when `e` is even or less than 3, `d` is not below `n`, `n` can't be factored with `d`,
+    /// which happens when `d` is not the private exponent of `n,e`, or the primes fail the checks of
+    /// [`SecretKey::from_primes_with_exponent`], for example because `n` has more than two.
+    pub fn from_components(n: BigUint, e: u64, d: BigUint) -> Result<Self,Error> {
+        let invalid = |reason: &str| Err(Error::InvalidKey(reason.into()));
+        if e < 3 || e.is_multiple_of(2) {
+            return invalid("e is even or less than 3")
+        }
+        if d.is_zero() || d >= n {
+            return invalid("d is not below n")
+        }
+
+        let Some(p) = recover_prime(&n, e, &d) else {
+            return invalid("n can't be factored with d, so d is not the private exponent of n and e")
+        };
+        let (q, rest) = n.div_rem(&p);
+        if !rest.is_zero() {
+            return invalid("n can't be factored with d, so d is not the private exponent of n and e")
+        }
+        let (p, q) = if p > q { (p, q) } else { (q, p) };
+        let key = SecretKey::from_primes_with_exponent(p, q, e)?;
+        if !(&d * &BigUint::from(e) % &carmichael_from_primes(&key.primes)).is_one() {
+            return invalid("d e is not 1 modulo λ(n)")
+        }
+        Ok(SecretKey { d, ..key })
+    }
+
+    fn from_prime_list(primes: Vec<BigUint>, e: u64) -> Result<Self,Error> {
+        let d = private_exponent(&primes, e)?;
+        let n = primes.iter().product();
+        Ok(SecretKey { primes, e, d, n })
+    }
+
+    /// Builds a key from decoded parts, for every decoder of untrusted input. The CRT arithmetic of
+    /// decrypting and exporting divides by `r - 1` and inverts each prime `r` modulo the others,
+    /// so a key only gets this far with at least two primes, each at least 2 and all coprime. Encoders
+    /// write `d` at the width of `n`, so it must be below `n`.
+    /// 
+    /// The primes are not tested for primality, which is slow for large keys, see [`validate_keypair`] for that.
+    /// 
+    /// # Errors
+    /// Gives [`Error::InvalidKey`] when the primes fail [`check_prime_list`], `n` is not their product,
+    /// `e` is less than 3, `d` is not below `n`, or `d e` is not 1 modulo `λ(n)`.
+    pub(crate) fn from_parts_checked(primes: Vec<BigUint>, e: u64, d: BigUint, n: BigUint) -> Result<Self,Error> {
+        check_prime_list(&primes)?;
+        if n != primes.iter().product::<BigUint>() {
+            return Err(Error::InvalidKey("n is not the product of the primes".into()))
+        }
+        if e < 3 {
+            return Err(Error::InvalidKey("e is less than 3".into()))
+        }
+        if d >= n {
+            return Err(Error::InvalidKey("d is not below n".into()))
+        }
+        if !(&d * &BigUint::from(e) % &carmichael_from_primes(&primes)).is_one() {
+            return Err(Error::InvalidKey("d e is not 1 modulo λ(n)".into()))
+        }
+        Ok(SecretKey { primes, e, d, n })
+    }
+
+    /// The public key of the pair, which is `n` and `e`.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey { n: self.n.clone(), e: self.e }
+    }
+
+    /// The number of primes `n` is the product of, which is 2 unless the key is multi-prime.
+    pub fn prime_count(&self) -> usize {
+        self.primes.len()
+    }
+
+    /// Whether `d` was computed modulo `λ(n)` or `φ(n)`. Key formats like PKCS#1 store `d` as is, so this
+    /// tells which of the two an exported key has.
+    pub fn exponent_convention(&self) -> ExponentConvention {
+        let e = BigUint::from(self.e);
+        let inverse_modulo = |modulus: BigUint| e.modinv(&modulus).is_ok_and(|d| d == self.d);
+        if inverse_modulo(carmichael_from_primes(&self.primes)) {
+            ExponentConvention::Carmichael
+        } else if inverse_modulo(phi_from_primes(&self.primes)) {
+            ExponentConvention::Euler
+        } else {
+            ExponentConvention::Other
+        }
+    }
+
+    /// Everything in the key, including the primes and `d`, in the form a derived [`Debug`] would have.
+    /// 
+    /// # Security
+    /// Anyone who sees this string can decrypt and sign as the key's owner, so don't log it.
+    pub fn debug_unsafe_full(&self) -> String {
+        format!("SecretKey {{ primes: {:?}, e: {:?}, d: {:?}, n: {:?} }}", self.primes, self.e, self.d, self.n)
+    }
+
+    /// `d mod (p - 1)`, `d mod (q - 1)` and `q^(-1) mod p`, which key formats store for decrypting
+    /// with the Chinese remainder theorem.
+    pub(crate) fn crt_values(&self) -> (BigUint, BigUint, BigUint) {
+        let (p, q) = (&self.primes[0], &self.primes[1]);
+        let qi = q.modinv(p).expect("q is invertible modulo the distinct prime p");
+        (self.crt_exponent(p), self.crt_exponent(q), qi)
+    }
+
+    /// For each prime `r` after `p,q`, `r`, `d mod (r - 1)` and the inverse modulo `r` of the product of the
+    /// primes before it. This is the `otherPrimeInfos` of PKCS#1.
+    pub(crate) fn other_prime_infos(&self) -> Vec<(BigUint, BigUint, BigUint)> {
+        let mut product = &self.primes[0] * &self.primes[1];
+        self.primes[2..].iter().map(|r| {
+            let coefficient = (&product % r).modinv(r).expect("the primes are distinct");
+            product *= r;
+            (r.clone(), self.crt_exponent(r), coefficient)
+        }).collect()
+    }
+
+    /// `d mod (r - 1)` for one of the primes `r`.
+    pub(crate) fn crt_exponent(&self, r: &BigUint) -> BigUint {
+        &self.d % &(r - 1u32).expect("the primes of a key are at least 2, see check_prime_list")
+    }
+}
+
+/// Compares the primes and `d` with [`BigUint::ct_eq`], so the time taken doesn't tell how much of them matched.
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        let primes_eq = self.primes.len() == other.primes.len()
+            && self.primes.iter().zip(&other.primes).fold(true, |eq, (a, b)| a.ct_eq(b) & eq);
+        primes_eq & self.d.ct_eq(&other.d) & (self.e == other.e) & (self.n == other.n)
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fingerprint = self.public_key().fingerprint_hex();
+        f.debug_struct("SecretKey")
+            .field("bits", &self.n.bit_size())
+            .field("primes", &self.prime_count())
+            .field("fingerprint", &format_args!("{}..", &fingerprint[..8]))
+            .finish_non_exhaustive()
+    }
+}
+
+/// The secret numbers of the key, for encodings and conversions outside this crate.
+/// Only built with the `expose-secrets` feature, so that reading them is a deliberate choice.
+#[cfg(feature = "expose-secrets")]
+impl SecretKey {
+    /// The first prime of `n`.
+    pub fn p(&self) -> &BigUint {
+        &self.primes[0]
+    }
+
+    /// The second prime of `n`.
+    pub fn q(&self) -> &BigUint {
+        &self.primes[1]
+    }
+
+    /// All primes of `n`, starting with `p,q`, which is more than two for a multi-prime key.
+    pub fn primes(&self) -> &[BigUint] {
+        &self.primes
+    }
+
+    /// The private exponent, see [`SecretKey::exponent_convention`].
+    pub fn d(&self) -> &BigUint {
+        &self.d
+    }
+}
+
+impl From<&SecretKey> for PublicKey {
+    fn from(sk: &SecretKey) -> Self {
+        sk.public_key()
+    }
+}
+
+/// Serialized form of [`SecretKey`], where `d` and `n` may be missing.
+#[derive(Serialize,Deserialize)]
+struct SecretKeyFields {
     p: BigUint,
-    q: BigUint
+    q: BigUint,
+    #[serde(default = "legacy_exponent")]
+    e: u64,
+    d: Option<BigUint>,
+    n: Option<BigUint>,
+    /// The primes after `p,q` of a multi-prime key.
+    #[serde(default)]
+    other_primes: Vec<BigUint>,
+}
+
+impl From<SecretKey> for SecretKeyFields {
+    fn from(sk: SecretKey) -> Self {
+        let mut primes = sk.primes.into_iter();
+        let (p, q) = (primes.next().expect("a key has p"), primes.next().expect("a key has q"));
+        SecretKeyFields { p, q, e: sk.e, d: Some(sk.d), n: Some(sk.n), other_primes: primes.collect() }
+    }
+}
+
+impl TryFrom<SecretKeyFields> for SecretKey {
+    type Error = Error;
+
+    fn try_from(fields: SecretKeyFields) -> Result<Self,Error> {
+        let mut primes = vec![fields.p, fields.q];
+        primes.extend(fields.other_primes);
+        match (fields.d, fields.n) {
+            (Some(d), Some(n)) => SecretKey::from_parts_checked(primes, fields.e, d, n),
+            _ => {
+                check_prime_list(&primes)?;
+                if fields.e < 3 {
+                    return Err(Error::InvalidKey("e is less than 3".into()))
+                }
+                SecretKey::from_prime_list(primes, fields.e)
+            },
+        }
+    }
+}
+
+/// Fails unless there are at least two primes, each at least 2 and coprime to the others, which is
+/// what [`SecretKey::crt_exponent`] and the CRT values rely on.
+fn check_prime_list(primes: &[BigUint]) -> Result<(),Error> {
+    if primes.len() < 2 {
+        return Err(Error::InvalidKey(format!("{} primes, a key needs at least 2", primes.len())))
+    }
+    let two = BigUint::from(2u32);
+    for (i, r) in primes.iter().enumerate() {
+        if r < &two {
+            return Err(Error::InvalidKey(format!("prime {} is below 2", i + 1)))
+        }
+        if primes[..i].iter().any(|other| !other.co_prime(r)) {
+            return Err(Error::InvalidKey(format!("prime {} is repeated or shares a factor with another prime", i + 1)))
+        }
+    }
+    Ok(())
+}
+
+/// `d = e^(-1) mod λ(n)`, which is `lcm(p - 1, q - 1)` for two primes, see [`ExponentConvention::Carmichael`].
+fn private_exponent(primes: &[BigUint], e: u64) -> Result<BigUint,Error> {
+    let modulus = carmichael_from_primes(primes);
+    let d = BigUint::from(e).modinv(&modulus)?;
+    Ok(d)
+}
+
+/// Which modulus the private exponent `d` of a [`SecretKey`] is the inverse of `e` modulo,
+/// see [`SecretKey::exponent_convention`]. Both decrypt the same, since `λ(n)` divides `φ(n)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExponentConvention {
+    /// `d = e^(-1) mod λ(n)`, the smallest `d`, as made by [`keygen`] and required by FIPS 186.
+    Carmichael,
+    /// `d = e^(-1) mod φ(n)`, as made by [`keygen`] before it used `λ(n)`, and by `openssl genrsa`.
+    Euler,
+    /// Neither of the above, like a `d` with a multiple of `λ(n)` added, or a wrong `d`.
+    Other,
+}
+
+/// A [`PublicKey`] together with its [`SecretKey`], as made by [`keygen`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyPair {
+    public: PublicKey,
+    secret: SecretKey,
+}
+
+impl KeyPair {
+    pub fn public(&self) -> &PublicKey {
+        &self.public
+    }
+
+    pub fn secret(&self) -> &SecretKey {
+        &self.secret
+    }
+
+    /// Gives the public and secret key, in that order.
+    pub fn split(self) -> (PublicKey, SecretKey) {
+        (self.public, self.secret)
+    }
+
+    /// Signs `message` with the secret key, see [`authenticity::sign`].
+    pub fn sign<T: Into<Message>>(&self, message: T) -> Result<Signature,Error> {
+        authenticity::sign_with(message, &self.secret)
+    }
+
+    /// Decrypts `ciphertext` with the secret key, see [`decrypt`].
+    pub fn decrypt<T: Into<Ciphertext>>(&self, ciphertext: T) -> Result<Plaintext,Error> {
+        confidentiality::decrypt_with(ciphertext, &self.secret)
+    }
+}
+
+impl From<SecretKey> for KeyPair {
+    fn from(secret: SecretKey) -> Self {
+        KeyPair { public: secret.public_key(), secret }
+    }
 }
 
-pub type KeyPair = (PublicKey, SecretKey);
+/// The tuple that [`KeyPair`] used to be.
+#[deprecated(note = "use `KeyPair` and `KeyPair::split`")]
+pub type KeyTuple = (PublicKey, SecretKey);
+/// Either kind of key, for code that stores both, like a keystore or a list of known peers.
+/// 
+/// Serialized with the variant as a tag, like `{"Public": {"n": .., "e": ..}}` in JSON.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Serialize,Deserialize)]
 pub enum Key {
-    PublicKey,
-    SecretKey
+    Public(PublicKey),
+    Secret(SecretKey),
+}
+
+impl Key {
+    /// The public key itself, or the one of the secret key.
+    pub fn public_key(&self) -> PublicKey {
+        match self {
+            Key::Public(pk) => pk.clone(),
+            Key::Secret(sk) => sk.public_key(),
+        }
+    }
+
+    /// The size of `n` in bits, see [`PublicKey::bit_size`].
+    pub fn bit_size(&self) -> u32 {
+        match self {
+            Key::Public(pk) => pk.bit_size(),
+            Key::Secret(sk) => sk.n.bit_size(),
+        }
+    }
+
+    /// The fingerprint of the public key, the same for a secret key and its public key, see [`PublicKey::fingerprint`].
+    pub fn fingerprint(&self) -> [u8; 32] {
+        self.public_key().fingerprint()
+    }
+
+    /// Whether this is a [`Key::Secret`], which must be kept private.
+    pub fn is_secret(&self) -> bool {
+        matches!(self, Key::Secret(_))
+    }
+}
+
+impl From<PublicKey> for Key {
+    fn from(pk: PublicKey) -> Self {
+        Key::Public(pk)
+    }
+}
+
+impl From<SecretKey> for Key {
+    fn from(sk: SecretKey) -> Self {
+        Key::Secret(sk)
+    }
+}
+
+/// Gives back the key when it is secret, see [`Key::public_key`] for the public key of any key.
+impl TryFrom<Key> for PublicKey {
+    type Error = Key;
+
+    fn try_from(key: Key) -> Result<Self,Key> {
+        match key {
+            Key::Public(pk) => Ok(pk),
+            key => Err(key),
+        }
+    }
+}
+
+/// Gives back the key when it is public.
+impl TryFrom<Key> for SecretKey {
+    type Error = Key;
+
+    fn try_from(key: Key) -> Result<Self,Key> {
+        match key {
+            Key::Secret(sk) => Ok(sk),
+            key => Err(key),
+        }
+    }
+}
+
+/// A short name for a key, the first 16 bytes of its [`PublicKey::fingerprint`], so it stays the same for
+/// as long as the key does, whichever encoding the key was read from.
+/// 
+/// Shown as 32 lowercase hex digits.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Serialize,Deserialize)]
+pub struct KeyId([u8; 16]);
+
+impl KeyId {
+    pub fn of(pk: &PublicKey) -> KeyId {
+        let fingerprint = pk.fingerprint();
+        KeyId(fingerprint[..16].try_into().expect("fingerprints have 32 bytes"))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl Display for KeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// When a key was made and when it should stop being used, for rotating keys.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Serialize,Deserialize)]
+pub struct KeyInfo {
+    pub id: KeyId,
+    pub created_at: SystemTime,
+    /// [`None`] for keys that don't expire.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl KeyInfo {
+    /// Whether the key has expired at `now`, which it has from its `expires_at` on.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+/// A [`KeyPair`] together with its [`KeyInfo`], whose id is always that of the pair.
+/// 
+/// Serialized as the secret key and the info, like `{"secret": {..}, "info": {..}}` in JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize,Deserialize)]
+#[serde(into = "IdentifiedKeyPairFields", try_from = "IdentifiedKeyPairFields")]
+pub struct IdentifiedKeyPair {
+    key_pair: KeyPair,
+    info: KeyInfo,
+}
+
+impl IdentifiedKeyPair {
+    /// Bundles `key_pair` with info made now, expiring at `expires_at`.
+    pub fn new(key_pair: KeyPair, expires_at: Option<SystemTime>) -> IdentifiedKeyPair {
+        let info = KeyInfo { id: KeyId::of(key_pair.public()), created_at: SystemTime::now(), expires_at };
+        IdentifiedKeyPair { key_pair, info }
+    }
+
+    pub fn key_pair(&self) -> &KeyPair {
+        &self.key_pair
+    }
+
+    pub fn info(&self) -> &KeyInfo {
+        &self.info
+    }
+
+    pub fn id(&self) -> KeyId {
+        self.info.id
+    }
+
+    /// See [`KeyInfo::is_expired`].
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.info.is_expired(now)
+    }
+
+    pub fn into_key_pair(self) -> KeyPair {
+        self.key_pair
+    }
+}
+
+#[derive(Serialize,Deserialize)]
+struct IdentifiedKeyPairFields {
+    secret: SecretKey,
+    info: KeyInfo,
+}
+
+impl From<IdentifiedKeyPair> for IdentifiedKeyPairFields {
+    fn from(identified: IdentifiedKeyPair) -> Self {
+        IdentifiedKeyPairFields { secret: identified.key_pair.secret, info: identified.info }
+    }
+}
+
+/// Rejects info whose id is not that of the key, since lookups by id would then find the wrong key.
+impl TryFrom<IdentifiedKeyPairFields> for IdentifiedKeyPair {
+    type Error = Error;
+
+    fn try_from(fields: IdentifiedKeyPairFields) -> Result<Self,Error> {
+        let key_pair = KeyPair::from(fields.secret);
+        if KeyId::of(key_pair.public()) != fields.info.id {
+            return Err(Error::InvalidKey(format!("key id {} is not that of the key", fields.info.id)))
+        }
+        Ok(IdentifiedKeyPair { key_pair, info: fields.info })
+    }
+}
+
+/// The public exponent used by [`keygen`].
+pub const DEFAULT_E: u64 = 65537;
+
+/// The smallest modulus, in bits, that [`keygen`] makes and [`PublicKey::validate`] accepts.
+pub const MIN_KEY_BITS: u32 = 512;
+
+/// The smallest primes, in bits, that [`keygen_multiprime`] makes for keys of more than two primes.
+pub const MIN_MULTI_PRIME_BITS: u32 = 512;
+
+/// How many bits below half the key size [`check_prime_distance`] allows `|p - q|` to be.
+const PRIME_DISTANCE_MARGIN: u32 = 100;
+
+/// How many bases [`SecretKey::from_components`] tries. Each fails with probability at most one half, so when `d`
+/// is right it is all but certain that one of them factors `n`.
+const RECOVERY_BASES: u32 = 100;
+
+/// The exponent of keys from before `e` was configurable.
+const LEGACY_E: u64 = 3;
+
+fn legacy_exponent() -> u64 {
+    LEGACY_E
+}
+
+
+/// Number of pairs of primes [`keygen`] tries, and of primes for each of `p,q`, before giving up with [`KeygenError::TooManyAttempts`].
+pub const DEFAULT_MAX_KEYGEN_ATTEMPTS: u32 = 100;
+
+/// Errors from [`keygen`] and [`keygen_with_max_attempts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeygenError {
+    /// No usable pair of primes was found within `attempts` tries.
+    TooManyAttempts { attempts: u32 },
+    /// The public exponent is even or less than 3.
+    InvalidExponent { e: u64 },
+    /// [`KeygenHandle::cancel`] was called, or the handle dropped, before a key was found.
+    Cancelled,
+    /// A key can't be made of `prime_count` primes with `bit_size` bits in all, see [`keygen_multiprime`].
+    InvalidPrimeCount { prime_count: u32, bit_size: u32 },
+    /// Generating a prime or deriving the secret key failed.
+    Prime(String),
+}
+
+impl Display for KeygenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeygenError::TooManyAttempts { attempts } => write!(f, "no usable primes found in {attempts} attempts"),
+            KeygenError::InvalidExponent { e } => write!(f, "{e} is not a valid public exponent, it must be odd and at least 3"),
+            KeygenError::Cancelled => write!(f, "key generation was cancelled"),
+            KeygenError::InvalidPrimeCount { prime_count, bit_size } => write!(f,
+                "a {bit_size} bit key can't have {prime_count} primes, it needs at least 2 and more only if each has {MIN_MULTI_PRIME_BITS} bits"),
+            KeygenError::Prime(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for KeygenError {}
+
+impl From<KeygenError> for String {
+    fn from(err: KeygenError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Errors from the functions of [`rsa`](self) and its submodules.
+#[derive(Debug)]
+pub enum Error {
+    /// Generating a key pair failed, see [`KeygenError`].
+    KeyGeneration(KeygenError),
+    /// A key of `requested` bits was asked for, but keys must have at least `minimum` bits, see [`MIN_KEY_BITS`].
+    KeyTooSmall { requested: u32, minimum: u32 },
+    /// The key's components don't fit together.
+    InvalidKey(String),
+    /// An encoded key, like a PEM document, is malformed.
+    Encoding(String),
+    /// The message, read as a number, is not less than the modulus `n`, so it can't be
+    /// encrypted or decrypted without losing information.
+    MessageTooLong { message_bits: u32, modulus_bits: u32 },
+    /// Encoding or decoding the [`Data`] of [`pack`] or [`unpack`] failed.
+    Serialization(bincode::Error),
+    /// The signature of unpacked [`Data`] is not valid for its message and sender.
+    VerificationFailed,
+    /// A freshly generated key pair failed [`pairwise_consistency_test`], naming the round trip that broke.
+    SelfTestFailed(String),
+    /// `e` has no inverse modulo `λ(n)`, so there is no private exponent.
+    NotInvertible(modular::Error),
+    /// A key sealed with [`SecretKey::seal`] was opened with another passphrase.
+    WrongPassphrase,
+    /// Bytes given to [`PublicKey::from_bytes`] or [`SecretKey::from_bytes`] are not a key of that type and version.
+    Format(binary::FormatError),
+    /// Unpacked [`Data`] was sent by a key on the [`RevocationList`](revocation::RevocationList) given to [`unpack_unless_revoked`].
+    Revoked(KeyId),
+    /// A [`confidentiality::decrypt_pkcs1v15`] ciphertext is the wrong length or badly padded. It doesn't say which,
+    /// as telling them apart helps an attacker.
+    DecryptionFailed,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::KeyGeneration(err) => write!(f, "key generation failed: {err}"),
+            Error::KeyTooSmall { requested, minimum } => write!(f, "a {requested} bit key is too small, the minimum is {minimum} bits"),
+            Error::InvalidKey(reason) => write!(f, "bad key: {reason}"),
+            Error::Encoding(reason) => write!(f, "bad key encoding: {reason}"),
+            Error::MessageTooLong { message_bits, modulus_bits } =>
+                write!(f, "message of {message_bits} bits does not fit below a modulus of {modulus_bits} bits"),
+            Error::Serialization(err) => write!(f, "serialization failed: {err}"),
+            Error::VerificationFailed => write!(f, "verification rejected"),
+            Error::SelfTestFailed(reason) => write!(f, "key pair failed its self-test: {reason}"),
+            Error::NotInvertible(err) => write!(f, "bad key: {err}"),
+            Error::WrongPassphrase => write!(f, "wrong passphrase"),
+            Error::Format(err) => write!(f, "bad key bytes: {err}"),
+            Error::Revoked(id) => write!(f, "the sender {id} is revoked"),
+            Error::DecryptionFailed => write!(f, "decryption failed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::KeyGeneration(err) => Some(err),
+            Error::Serialization(err) => Some(err),
+            Error::NotInvertible(err) => Some(err),
+            Error::Format(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<KeygenError> for Error {
+    fn from(err: KeygenError) -> Self {
+        Error::KeyGeneration(err)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(err: bincode::Error) -> Self {
+        Error::Serialization(err)
+    }
 }
 
-pub const E: i32 = 3;
+impl From<modular::Error> for Error {
+    fn from(err: modular::Error) -> Self {
+        Error::NotInvertible(err)
+    }
+}
 
+impl From<Error> for String {
+    fn from(err: Error) -> Self {
+        err.to_string()
+    }
+}
 
 /// [`keygen`] generates an RSA [`KeyPair`] with a given `bit_size`.
-/// The `bit_size` is the size of `n = p * q`, where `p,q` are large prime numbers.
-/// [`keygen`] returns a [`Result<KeyPair,String>`].
+/// The `bit_size` is the size of `n = p * q`, where `p,q` are large prime numbers, and `n` always has
+/// exactly `bit_size` bits, so ciphertexts and signatures of the key all have [`PublicKey::byte_size`] bytes.
+/// The public exponent is [`DEFAULT_E`], see [`keygen_with_exponent`] for picking another.
+/// `p` and `q` are searched for at the same time on two threads.
+/// [`keygen`] returns a [`Result<KeyPair,Error>`].
 /// 
-/// The [`KeyPair`] contains two keys
+/// The [`KeyPair`] contains two keys, given by [`KeyPair::split`]
 /// - A [`PublicKey`] that can be used in [`encrypt`] to encrypt a message.
 /// - A [`SecretKey`] that can be used in [`decrypt`] to decrypt a message.
 /// 
@@ -50,57 +826,524 @@ pub const E: i32 = 3;
 /// use rustnetworking::rsa::{confidentiality::{Plaintext,Ciphertext,encrypt,decrypt},keygen};
 /// #
 /// # fn main() -> Result<(),String> {
-/// let (pk,sk) = keygen(2048)?;
+/// let (pk,sk) = keygen(2048)?.split();
 /// 
 /// let m: Plaintext = "Very secret message ;p".as_bytes().into();
-/// let c: Ciphertext = encrypt(m, &pk).into();
+/// let c: Ciphertext = encrypt(m, &pk)?;
 /// let decrypted = decrypt(c, sk)?;
 /// # Ok(())
 /// # }
 /// ```
 /// 
 /// # Security
-/// For security, it's recommended to use `bit_size` 2048 or larger.
+/// For security, it's recommended to use `bit_size` 2048 or larger. Keys of fewer than [`MIN_KEY_BITS`] bits
+/// are refused, since they are factored in minutes; tests that need them can use `keygen_insecure_for_tests`
+/// with the `insecure-small-keys` feature.
 /// 
 /// # Errors
-/// Keygen handles errors by propagating them. Errors should not occur unless a very small `bit_size` is chosen.
+/// Gives [`Error::KeyTooSmall`] when `bit_size` is less than [`MIN_KEY_BITS`]. Other errors should not occur,
+/// though keygen gives up after [`DEFAULT_MAX_KEYGEN_ATTEMPTS`] tries without usable primes.
 /// 
 /// # Panics
 /// Keygen should not panic under normal circumstances.
-pub fn keygen(bit_size: u32) -> Result<KeyPair,String> {
-    let p_size = (bit_size as f32 / 2.).floor() as usize;
-    let q_size = (bit_size as f32 / 2.).ceil() as usize;
+pub fn keygen(bit_size: u32) -> Result<KeyPair,Error> {
+    generate(bit_size, &KeygenOptions::default(), &mut OsRng)
+}
+
+/// Settings for [`keygen_with_options`], with [`Default`] giving those of [`keygen`].
+/// 
+/// # Examples
+/// ```rust
+/// use rustnetworking::rsa::{keygen_with_options, KeygenOptions};
+/// 
+/// # fn main() -> Result<(),String> {
+/// let pair = keygen_with_options(512, &KeygenOptions { strong_primes: true, ..Default::default() })?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeygenOptions {
+    /// The public exponent, see [`keygen_with_exponent`].
+    pub e: u64,
+    /// How many primes and sets of primes are tried, see [`keygen_with_max_attempts`].
+    pub max_attempts: u32,
+    /// Whether `p,q` are strong primes, for which `p - 1` and `p + 1` each have a large prime factor `r,s`,
+    /// and `r - 1` has a large prime factor `t`, made with [`new_strong_prime_in_range`](crate::big_num::new_strong_prime_in_range).
+    /// 
+    /// This was meant to stop Pollard's `p - 1`, Williams' `p + 1` and cycling attacks, but for random primes
+    /// of today's sizes those already fail, and ECM and the number field sieve don't care. Some compliance
+    /// checklists still ask for strong primes, which make key generation about one and a half times as slow.
+    pub strong_primes: bool,
+}
+
+impl Default for KeygenOptions {
+    fn default() -> Self {
+        KeygenOptions { e: DEFAULT_E, max_attempts: DEFAULT_MAX_KEYGEN_ATTEMPTS, strong_primes: false }
+    }
+}
+
+/// [`keygen_with_options`] is [`keygen`] with the settings of `options`, which can combine those of
+/// [`keygen_with_exponent`] and [`keygen_with_max_attempts`] and ask for strong primes.
+/// 
+/// # Errors
+/// As for [`keygen_with_exponent`] and [`keygen_with_max_attempts`].
+pub fn keygen_with_options(bit_size: u32, options: &KeygenOptions) -> Result<KeyPair,Error> {
+    generate(bit_size, options, &mut OsRng)
+}
+
+/// [`keygen_with_exponent`] is [`keygen`] with the public exponent `e` instead of [`DEFAULT_E`].
+/// 
+/// # Security
+/// Small exponents like `e = 3` make unpadded RSA easier to attack, see [`confidentiality`].
+/// 
+/// # Errors
+/// Gives [`Error::KeyGeneration`] with [`KeygenError::InvalidExponent`] when `e` is even or less than 3, since such an `e`
+/// is never invertible modulo `λ(n)` or gives no encryption at all.
+pub fn keygen_with_exponent(bit_size: u32, e: u64) -> Result<KeyPair,Error> {
+    generate(bit_size, &KeygenOptions { e, ..Default::default() }, &mut OsRng)
+}
+
+/// [`keygen_with_max_attempts`] is [`keygen`] with a bound on how many primes and pairs of primes are tried.
+/// A prime is thrown away, and another one searched for in its place, when `e` is not [`exponent_coprime_to`] it.
+/// A pair is rejected when `p,q` are equal or too close or `n` has the wrong size, and then only `q` is searched for again.
+/// 
+/// # Errors
+/// Gives [`Error::KeyGeneration`] with [`KeygenError::TooManyAttempts`] when none of the first `max_attempts` primes of
+/// `p` or `q`, or none of the first `max_attempts` pairs, are usable.
+pub fn keygen_with_max_attempts(bit_size: u32, max_attempts: u32) -> Result<KeyPair,Error> {
+    generate(bit_size, &KeygenOptions { max_attempts, ..Default::default() }, &mut OsRng)
+}
+
+/// [`keygen_with_rng`] is [`keygen`] with all randomness taken from `rng` instead of the operating system.
+/// The same `rng` state always gives the same key pair, which makes key generation reproducible,
+/// for example from a recorded source during an audit. This holds even though the primes are searched
+/// for on separate threads, since each thread's generator is seeded from `rng` in a fixed order.
+/// 
+/// # Security
+/// The key is only as unpredictable as `rng`, so it must be seeded from a secure source to be used for real.
+/// 
+/// # Errors
+/// As for [`keygen`].
+pub fn keygen_with_rng<R: CryptoRng + RngCore>(bit_size: u32, rng: &mut R) -> Result<KeyPair,Error> {
+    generate(bit_size, &KeygenOptions::default(), rng)
+}
+
+/// What [`keygen_with_progress`] reports while it runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeygenEvent {
+    /// A candidate for the prime at index `prime` of the key, `0` for `p` and `1` for `q`, was tested for primality.
+    /// Strong primes have candidates for their auxiliary primes too, which count for the prime they are part of.
+    CandidateTested { prime: usize },
+    /// A candidate prime, or a whole set of primes, was thrown away.
+    Rejected { reason: RejectionReason },
+    /// The key is done, after trying `attempts` sets of primes, each but the first differing from the one before in its last prime.
+    Completed { attempts: u32 },
+}
+
+/// Why [`keygen_with_progress`] threw something away, see [`KeygenEvent::Rejected`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// A candidate prime was composite. Most candidates are, so this is by far the most common event.
+    Composite,
+    /// The product of the primes has more or fewer bits than the key should. The ranges the primes are picked
+    /// from rule this out, but it is checked so that a key never has the wrong size.
+    TooLarge,
+    /// `e` shares a factor with `p - 1` for one of the primes `p`, so it has no inverse modulo `λ(n)`.
+    GcdFailure,
+    /// Two primes are equal, or too close together for [`check_prime_distance`].
+    TooClose,
+}
+
+/// [`keygen_with_progress`] is [`keygen`] that calls `progress` with a [`KeygenEvent`] for each candidate prime
+/// tested, each rejection and the completion, for showing progress on long key generations.
+/// 
+/// The primes are searched for on other threads, but `progress` is only ever called on the thread that called
+/// this, while it waits for them. Events are passed by value, so `progress` can't change the generation.
+/// 
+/// # Examples
+/// ```rust
+/// use rustnetworking::rsa::{keygen_with_progress, KeygenEvent};
+/// 
+/// # fn main() -> Result<(),String> {
+/// let mut tested = 0;
+/// let pair = keygen_with_progress(512, |event| if let KeygenEvent::CandidateTested { .. } = event {
+///     tested += 1;
+/// })?;
+/// assert!(tested >= 2);
+/// # Ok(())
+/// # }
+/// ```
+/// 
+/// # Errors
+/// As for [`keygen`].
+pub fn keygen_with_progress(bit_size: u32, mut progress: impl FnMut(KeygenEvent)) -> Result<KeyPair,Error> {
+    check_key_size(bit_size)?;
+    generate_primes(bit_size, 2, &KeygenOptions::default(), &mut OsRng, &AtomicBool::new(false), &mut progress)
+}
+
+/// [`keygen_multiprime`] is [`keygen`] for a modulus `n` of `prime_count` primes instead of two,
+/// which makes decrypting faster since each prime is smaller. See RFC 8017, section 3.
+/// 
+/// # Security
+/// Factoring methods like ECM get faster as the primes get smaller, whatever the size of `n`, so every prime
+/// must have at least [`MIN_MULTI_PRIME_BITS`] bits. For 2048 bit keys this allows up to four primes.
+/// 
+/// # Errors
+/// Gives [`Error::KeyGeneration`] with [`KeygenError::InvalidPrimeCount`] when `prime_count` is less than 2,
+/// or when it is more than 2 and the primes would have fewer than [`MIN_MULTI_PRIME_BITS`] bits.
+/// Otherwise as for [`keygen`].
+pub fn keygen_multiprime(bit_size: u32, prime_count: u32) -> Result<KeyPair,Error> {
+    check_key_size(bit_size)?;
+    if prime_count < 2 || (prime_count > 2 && bit_size / prime_count < MIN_MULTI_PRIME_BITS) {
+        return Err(KeygenError::InvalidPrimeCount { prime_count, bit_size }.into())
+    }
+    generate_primes(bit_size, prime_count, &KeygenOptions::default(), &mut OsRng, &AtomicBool::new(false), &mut |_| ())
+}
+
+/// [`keygen_checked`] is [`keygen`] followed by [`pairwise_consistency_test`], so a key pair that
+/// can't decrypt or sign correctly is never handed out, as FIPS 140 asks of key generation.
+/// It costs one encryption, decryption, signature and verification on top of [`keygen`].
+/// 
+/// # Errors
+/// Gives [`Error::SelfTestFailed`] when the self-test fails, otherwise as for [`keygen`].
+pub fn keygen_checked(bit_size: u32) -> Result<KeyPair,Error> {
+    let pair = keygen(bit_size)?;
+    pairwise_consistency_test(&pair)?;
+    Ok(pair)
+}
+
+/// [`pairwise_consistency_test`] checks that `pair` works as a whole: a fixed probe decrypts back to itself
+/// after encryption, and a signature of a fixed message verifies. Unlike [`validate_keypair`] it goes
+/// through [`decrypt`] and [`sign`](authenticity::sign) themselves, so it also catches bugs in them.
+/// 
+/// # Errors
+/// Gives [`Error::SelfTestFailed`] naming the round trip that broke.
+pub fn pairwise_consistency_test(pair: &KeyPair) -> Result<(),Error> {
+    let failed = |reason: &str| Error::SelfTestFailed(reason.into());
+
+    let probe: Message = vec![0x5c, 0xa1, 0xab, 0x1e];
+    let ciphertext = encrypt(probe.clone(), pair.public()).map_err(|_| failed("the probe could not be encrypted"))?;
+    match pair.decrypt(ciphertext) {
+        Ok(decrypted) if decrypted == probe => (),
+        _ => return Err(failed("decrypting the encrypted probe does not give it back")),
+    }
+
+    let message: Message = b"pairwise consistency test".to_vec();
+    let signature = pair.sign(message.clone()).map_err(|_| failed("the probe message could not be signed"))?;
+    match verify(message, signature, pair.public().clone()) {
+        Verification::Accept => Ok(()),
+        Verification::Reject => Err(failed("the signature of the probe message does not verify")),
+    }
+}
+
+/// [`keygen_background`] runs [`keygen`] on a new thread, giving a [`KeygenHandle`] to collect the
+/// [`KeyPair`] with, or to cancel the search for primes when it's no longer needed.
+/// 
+/// # Examples
+/// ```rust
+/// use rustnetworking::rsa::keygen_background;
+/// 
+/// # fn main() -> Result<(),String> {
+/// let handle = keygen_background(512);
+/// // ... do something else in the meantime ...
+/// let (pk,sk) = handle.wait()?.split();
+/// # Ok(())
+/// # }
+/// ```
+/// 
+/// # Errors
+/// The handle gives the errors of [`keygen`], and [`KeygenError::Cancelled`] after [`KeygenHandle::cancel`].
+pub fn keygen_background(bit_size: u32) -> KeygenHandle {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancelled);
+    let worker = std::thread::spawn(move || {
+        check_key_size(bit_size)?;
+        generate_primes(bit_size, 2, &KeygenOptions::default(), &mut OsRng, &flag, &mut |_| ())
+    });
+    KeygenHandle { worker: Some(worker), cancelled }
+}
 
-    fn f(p_size: usize,q_size: usize) -> Result<(BigUint,BigUint), String> {
-        let p_candidate = new_prime(p_size);
-        let q_candidate = new_prime(q_size);
+/// A key generation running on another thread, see [`keygen_background`].
+/// 
+/// Dropping the handle cancels the generation without waiting for the thread to stop.
+#[derive(Debug)]
+pub struct KeygenHandle {
+    /// [`None`] once the result has been taken by [`KeygenHandle::try_recv`].
+    worker: Option<JoinHandle<Result<KeyPair,Error>>>,
+    cancelled: Arc<AtomicBool>,
+}
 
-        if p_candidate == q_candidate {
-            return f(p_size,q_size)
+impl KeygenHandle {
+    /// The result if the generation has finished, and [`None`] while it is still running.
+    /// The result is only given once, after which this is always [`None`].
+    pub fn try_recv(&mut self) -> Option<Result<KeyPair,Error>> {
+        if self.worker.as_ref().is_some_and(JoinHandle::is_finished) {
+            return Some(self.join())
         }
+        None
+    }
+
+    /// Blocks until the generation has finished, and gives its result.
+    /// 
+    /// # Panics
+    /// Panics if [`KeygenHandle::try_recv`] already gave the result.
+    pub fn wait(mut self) -> Result<KeyPair,Error> {
+        self.join()
+    }
+
+    /// Stops the generation before its next prime candidate, after which it gives [`KeygenError::Cancelled`].
+    /// Cancelling after a key was found does nothing.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn join(&mut self) -> Result<KeyPair,Error> {
+        let worker = self.worker.take().expect("the result of a key generation is only taken once");
+        worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
 
-        if p_candidate.bit_size() + q_candidate.bit_size() > p_size as u32 + q_size as u32 {
-            return f(p_size, q_size)
+impl Drop for KeygenHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// [`validate_keypair`] checks that `pk` and `sk` make up a usable key pair, for keys imported or
+/// deserialized from untrusted sources.
+/// 
+/// Besides [`PublicKey::validate`], it checks that
+/// - `pk` and `sk` have the same `n` and `e`
+/// - the primes `p,q`, and any more of a multi-prime key, are distinct probable primes with product `n`
+/// - `gcd(e, φ(n)) = 1`, where `φ(n) = (p - 1)(q - 1)` for two primes
+/// - `p,q` are far enough apart for [`check_prime_distance`], unless the key is multi-prime
+/// - decrypting an encrypted test message gives the message back, so `d` fits `e`
+/// 
+/// # Errors
+/// Gives [`Error::InvalidKey`] naming the first check that failed.
+pub fn validate_keypair(pk: &PublicKey, sk: &SecretKey) -> Result<(),Error> {
+    let invalid = |reason: &str| Err(Error::InvalidKey(reason.into()));
+    pk.validate()?;
+    if pk.n != sk.n || pk.e != sk.e {
+        return invalid("the public key is not the one of the secret key")
+    }
+    for (i, r) in sk.primes.iter().enumerate() {
+        let name = match i {
+            0 => "p".to_string(),
+            1 => "q".to_string(),
+            _ => format!("prime {}", i + 1),
+        };
+        if !r.prime() {
+            return Err(Error::InvalidKey(format!("{name} is not prime")))
+        }
+        if sk.primes[..i].contains(r) {
+            return Err(Error::InvalidKey(format!("{name} is repeated")))
         }
+    }
+    if sk.primes.iter().product::<BigUint>() != sk.n {
+        return invalid("n is not the product of the primes")
+    }
+    if !BigUint::from(sk.e).co_prime(&phi_from_primes(&sk.primes)) {
+        return invalid("e is not coprime to φ(n)")
+    }
+    if sk.prime_count() == 2 {
+        check_prime_distance(&sk.primes[0], &sk.primes[1], sk.n.bit_size())?;
+    }
 
-        let one = BigUint::from_i32(1)?;
-        let modulus = (p_candidate.clone() - one.clone())? * (q_candidate.clone() - one)?;
-        if !BigUint::from_i32(E)?.co_prime(&modulus) {
-            return f(p_size, q_size)
+    let message = BigUint::from(0x5ca1ab1eu32);
+    let ciphertext = message.modpow(&BigUint::from(sk.e), &sk.n);
+    if ciphertext.modpow(&sk.d, &sk.n) != message {
+        return invalid("decryption does not undo encryption, so d is wrong")
+    }
+    Ok(())
+}
+
+/// A prime factor of `n` from its exponents, see [`SecretKey::from_components`], trying the bases `g = 2, 3, ..`
+/// so that the same components always give the same key. [`None`] when none of the first bases find one.
+fn recover_prime(n: &BigUint, e: u64, d: &BigUint) -> Option<BigUint> {
+    let one = BigUint::one();
+    let n_minus_one = n.saturating_sub(&one);
+    let k = (d * &BigUint::from(e)).saturating_sub(&one);
+    let t = k.trailing_zeros()?;
+    let r = &k >> t as usize;
+
+    for g in 2..RECOVERY_BASES + 2 {
+        let mut x = BigUint::from(g).modpow(&r, n);
+        if x.is_one() || x == n_minus_one {
+            continue
+        }
+        for _ in 0..t {
+            let y = &x * &x % n;
+            if y.is_one() {
+                let p = x.saturating_sub(&one).gcd(n);
+                return (!p.is_one() && &p != n).then_some(p)
+            }
+            if y == n_minus_one {
+                break
+            }
+            x = y;
         }
+    }
+    None
+}
+
+/// [`exponent_coprime_to`] is whether `gcd(e, p - 1) = 1`, which `e` needs for every prime `p` of a key to have an inverse
+/// modulo `λ(n)`. [`keygen`] searches for each prime until this holds, instead of checking `e` against `φ(n)` once
+/// all primes are there, so that a prime which is fine is kept. For `e = 3` about half of all primes fail.
+pub fn exponent_coprime_to(e: u64, p: &BigUint) -> bool {
+    BigUint::from(e).co_prime(&p.saturating_sub(&BigUint::one()))
+}
 
-        Ok((p_candidate,q_candidate))
+/// [`check_prime_distance`] checks that the primes `p,q` of a `bit_size` bit key are not so close together
+/// that Fermat's method factors `n` by searching from `sqrt(n)`, which finds primes that share about their
+/// top half of bits. Following FIPS 186-5, `|p - q|` must be more than `2^(bit_size / 2 - 100)`.
+/// 
+/// Random primes of [`keygen`] all but never fail this, but it is checked anyway, and keys from elsewhere
+/// are checked by [`validate_keypair`].
+/// 
+/// # Errors
+/// Gives [`Error::InvalidKey`] when `p` and `q` are too close.
+pub fn check_prime_distance(p: &BigUint, q: &BigUint, bit_size: u32) -> Result<(),Error> {
+    let bound = BigUint::two_pow((bit_size / 2).saturating_sub(PRIME_DISTANCE_MARGIN));
+    let distance = if p > q { p.saturating_sub(q) } else { q.saturating_sub(p) };
+    if distance <= bound {
+        return Err(Error::InvalidKey(format!("p and q are too close, |p - q| is not above 2^{}", bound.bit_size() - 1)))
     }
+    Ok(())
+}
 
-    let (p,q) = f(p_size,q_size)?;
+/// [`keygen_insecure_for_tests`] is [`keygen`] without the [`MIN_KEY_BITS`] floor, for tests that want
+/// keys quickly or need a small modulus. Only built with the `insecure-small-keys` feature.
+/// 
+/// # Security
+/// Keys this small are easily factored. Never use them outside of tests.
+/// 
+/// # Errors
+/// As for [`keygen`], except that [`Error::KeyTooSmall`] is never given, and for tiny `bit_size`
+/// keygen may give up for lack of usable primes.
+#[cfg(feature = "insecure-small-keys")]
+pub fn keygen_insecure_for_tests(bit_size: u32) -> Result<KeyPair,Error> {
+    generate_primes(bit_size, 2, &KeygenOptions::default(), &mut OsRng, &AtomicBool::new(false), &mut |_| ())
+}
 
-    let n = p.clone() * q.clone();
+fn check_key_size(bit_size: u32) -> Result<(),Error> {
+    if bit_size < MIN_KEY_BITS {
+        return Err(Error::KeyTooSmall { requested: bit_size, minimum: MIN_KEY_BITS })
+    }
+    Ok(())
+}
 
-    let public_key = PublicKey{n};
-    let secret_key = SecretKey{p,q};
-    Ok((public_key, secret_key))
+fn generate(bit_size: u32, options: &KeygenOptions, rng: &mut (impl RngCore + CryptoRng)) -> Result<KeyPair,Error> {
+    check_key_size(bit_size)?;
+    generate_primes(bit_size, 2, options, rng, &AtomicBool::new(false), &mut |_| ())
 }
 
+/// Generates a key of `prime_count` primes, giving up with [`KeygenError::Cancelled`] once `cancelled` is set,
+/// and telling `progress` about it on this thread, see [`keygen_with_progress`].
+fn generate_primes(bit_size: u32, prime_count: u32, options: &KeygenOptions, rng: &mut (impl RngCore + CryptoRng), cancelled: &AtomicBool, progress: &mut impl FnMut(KeygenEvent)) -> Result<KeyPair,Error> {
+    let KeygenOptions { e, max_attempts, .. } = *options;
+    if e < 3 || e.is_multiple_of(2) {
+        return Err(KeygenError::InvalidExponent { e }.into())
+    }
+
+    // The smaller primes come first, so for two primes p has bit_size / 2 bits and q the rest.
+    let larger = bit_size % prime_count;
+    let sizes = (0..prime_count).map(|i| bit_size / prime_count + u32::from(i >= prime_count - larger));
+
+    // A prime of at least 2^(size - 1/k) and below 2^size has its top bit set, and the product
+    // of k such primes always has exactly the sum of their sizes in bits. For two primes the bound
+    // is sqrt(2) * 2^(size - 1).
+    let ranges: Vec<(BigUint, BigUint)> = sizes.map(|size| {
+        let lo = BigUint::two_pow(prime_count * size - 1).nth_root(prime_count) + 1u32;
+        (lo, BigUint::two_pow(size))
+    }).collect();
+
+    // Each prime is searched for on its own thread until e is coprime to it minus one, and when a set of
+    // primes doesn't fit together, only the last one is searched for again.
+    let mut primes = Vec::with_capacity(ranges.len());
+    for attempt in 1..=max_attempts {
+        primes.extend(search_primes(&ranges, primes.len(), options, rng, cancelled, progress)?);
+
+        let repeated = primes.iter().enumerate().any(|(i, r)| primes[..i].contains(r));
+        let rejection = if repeated || (prime_count == 2 && check_prime_distance(&primes[0], &primes[1], bit_size).is_err()) {
+            Some(RejectionReason::TooClose)
+        } else if primes.iter().product::<BigUint>().bit_size() != bit_size {
+            Some(RejectionReason::TooLarge)
+        } else {
+            None
+        };
+        if let Some(reason) = rejection {
+            progress(KeygenEvent::Rejected { reason });
+            primes.pop();
+            continue
+        }
+
+        let key_pair = SecretKey::from_prime_list(primes, e)?.into();
+        progress(KeygenEvent::Completed { attempts: attempt });
+        return Ok(key_pair)
+    }
+
+    Err(KeygenError::TooManyAttempts { attempts: max_attempts }.into())
+}
+
+/// Searches for a prime in each of `ranges` from index `first` on at once, each on its own thread with its own generator
+/// seeded from `rng`, so a seeded `rng` still gives the same primes. They are strong primes if the `options` ask for them,
+/// and a prime `p` is searched for again, up to `max_attempts` times, when `e` is not [`exponent_coprime_to`] it.
+/// 
+/// The threads send their [`KeygenEvent`]s here, where `progress` is called with them until the threads finish.
+fn search_primes(ranges: &[(BigUint, BigUint)], first: usize, options: &KeygenOptions, rng: &mut (impl RngCore + CryptoRng), cancelled: &AtomicBool, progress: &mut impl FnMut(KeygenEvent)) -> Result<Vec<BigUint>,Error> {
+    let KeygenOptions { e, max_attempts, strong_primes: strong } = *options;
+    let ranges = &ranges[first..];
+    let rngs = ranges.iter()
+        .map(|_| StdRng::from_rng(&mut *rng))
+        .collect::<Result<Vec<StdRng>,rand::Error>>()
+        .map_err(|err| KeygenError::Prime(err.to_string()))?;
+
+    let searches: Vec<Result<Option<BigUint>,KeygenError>> = std::thread::scope(|scope| {
+        let (sender, events) = mpsc::channel();
+        let workers: Vec<_> = ranges.iter().zip(rngs).zip(first..).map(|(((lo, hi), mut prime_rng), prime)| {
+            let sender = sender.clone();
+            scope.spawn(move || {
+                let cancelled = || cancelled.load(Ordering::Relaxed);
+                // Sending only fails once the receiver is gone, and then nobody is listening.
+                let tested = |is_prime: bool| {
+                    let _ = sender.send(KeygenEvent::CandidateTested { prime });
+                    if !is_prime {
+                        let _ = sender.send(KeygenEvent::Rejected { reason: RejectionReason::Composite });
+                    }
+                };
+                for _ in 0..max_attempts {
+                    let search = if strong {
+                        new_strong_prime_in_range_cancellable(lo, hi, &mut prime_rng, &cancelled, &tested).map(|prime| prime.map(|prime| prime.p))
+                    } else {
+                        new_prime_in_range_cancellable(lo, hi, &mut prime_rng, &cancelled, &tested)
+                    };
+                    match search.map_err(KeygenError::Prime)? {
+                        Some(p) if !exponent_coprime_to(e, &p) => {
+                            let _ = sender.send(KeygenEvent::Rejected { reason: RejectionReason::GcdFailure });
+                        },
+                        found => return Ok(found),
+                    }
+                }
+                Err(KeygenError::TooManyAttempts { attempts: max_attempts })
+            })
+        }).collect();
+        drop(sender);
+        events.iter().for_each(&mut *progress);
+        workers.into_iter()
+            .map(|worker| worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect()
+    });
+
+    let mut primes = Vec::with_capacity(ranges.len());
+    for search in searches {
+        match search? {
+            Some(prime) => primes.push(prime),
+            None => return Err(KeygenError::Cancelled.into()),
+        }
+    }
+    Ok(primes)
+}
 
 #[derive(Serialize,Deserialize,Debug)]
 pub struct Data {
@@ -109,28 +1352,51 @@ pub struct Data {
     pub sender: PublicKey,
 }
 
-pub fn pack<T: Into<Plaintext>>(message: T, sender: KeyPair, receiver: &PublicKey) -> Result<Ciphertext,String> {
-    let (sender_pk, sender_sk) = sender;
+pub fn pack<T: Into<Plaintext>>(message: T, sender: &KeyPair, receiver: &PublicKey) -> Result<Ciphertext,Error> {
     let plaintext = message.into();
     let data = Data {
         message: plaintext.clone(),
-        signature: sign(plaintext, sender_sk)?,
-        sender: sender_pk
+        signature: sender.sign(plaintext)?,
+        sender: sender.public().clone()
     };
 
-    let data_bytes = serialize(&data).map_err(|err| err.to_string())?;
-    let encrypted = encrypt(data_bytes, receiver);
-    Ok(encrypted)
+    let data_bytes = serialize(&data)?;
+    encrypt(data_bytes, receiver)
+}
+
+pub fn unpack<T: Into<Ciphertext>>(ciphertext: T, receiver: SecretKey) -> Result<Plaintext,Error> {
+    unpack_with_sender(ciphertext, receiver).map(|(message, _)| message)
 }
 
-pub fn unpack<T: Into<Ciphertext>>(ciphertext: T, receiver: SecretKey) -> Result<Plaintext,String> {
+/// [`unpack_with_sender`] is [`unpack`] that also gives the [`KeyId`] of the sender, for looking up whether
+/// to trust it. [`pack`] already embeds the sender's public key, which the signature is checked against,
+/// so the id is that of the key that really signed and takes no room in the ciphertext.
+/// 
+/// # Errors
+/// As for [`unpack`].
+pub fn unpack_with_sender<T: Into<Ciphertext>>(ciphertext: T, receiver: SecretKey) -> Result<(Plaintext, KeyId),Error> {
     let decrypted = decrypt(ciphertext, receiver)?;
-    let data: Data = deserialize(&decrypted).map_err(|err| err.to_string())?;
+    let data: Data = deserialize(&decrypted)?;
+    let sender = KeyId::of(&data.sender);
     
     let verification = verify(data.message.clone(), data.signature, data.sender);
 
     match verification {
-        Verification::Reject => Err("verification rejected".into()),
-        Verification::Accept => Ok(data.message)
+        Verification::Reject => Err(Error::VerificationFailed),
+        Verification::Accept => Ok((data.message, sender))
     }
-}
\ No newline at end of file
+}
+
+/// [`unpack_unless_revoked`] is [`unpack_with_sender`] that refuses senders on `revocations`. The list is
+/// taken as it is, so check it with [`RevocationList::verify`](revocation::RevocationList::verify) when it arrives.
+/// 
+/// # Errors
+/// As for [`unpack`], and [`Error::Revoked`] when the sender is revoked, which is only reported for messages
+/// whose signature checks out.
+pub fn unpack_unless_revoked<T: Into<Ciphertext>>(ciphertext: T, receiver: SecretKey, revocations: &revocation::RevocationList) -> Result<(Plaintext, KeyId),Error> {
+    let (message, sender) = unpack_with_sender(ciphertext, receiver)?;
+    if revocations.is_revoked(sender) {
+        return Err(Error::Revoked(sender))
+    }
+    Ok((message, sender))
+}
@@ -0,0 +1,176 @@
+//! A key agent that signs and decrypts for code that must never see the keys it uses.
+//!
+//! A [`KeyAgent`] moves its [`SecretKey`]s to a thread of their own, and [`AgentHandle`]s send it
+//! requests over a channel, naming the key by its [`KeyId`]. A handle can only ask for signatures
+//! and decryptions, so it can be given to untrusted code, cloned, and shared between threads.
+//! Dropping the agent stops the thread and drops the keys, after which every request of its handles,
+//! including those already waiting for an answer, fails with [`Error::Stopped`].
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, KeyId, agent::KeyAgent, authenticity::{verify, Verification}};
+//!
+//! # fn main() -> Result<(),String> {
+//! let (pk,sk) = keygen(512)?.split();
+//! let agent = KeyAgent::new([sk]);
+//! let handle = agent.handle();
+//!
+//! let signature = handle.sign(KeyId::of(&pk), "signed by the agent")?;
+//! assert!(matches!(verify("signed by the agent", signature, pk), Verification::Accept));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashMap, fmt::Display, sync::{Arc, mpsc, atomic::{AtomicBool, Ordering}}, thread::JoinHandle};
+
+use super::{KeyId, SecretKey, authenticity::{self, Signature}, confidentiality::{self, Ciphertext, Message, Plaintext}};
+
+/// Errors from the requests of an [`AgentHandle`].
+#[derive(Debug)]
+pub enum Error {
+    /// The [`KeyAgent`] has been dropped.
+    Stopped,
+    /// The agent has no key with this id.
+    UnknownKey(KeyId),
+    /// The agent has the key, but signing or decrypting with it failed.
+    Key(super::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Stopped => write!(f, "the key agent has stopped"),
+            Error::UnknownKey(id) => write!(f, "the key agent has no key {id}"),
+            Error::Key(err) => write!(f, "the key agent's operation failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Key(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<super::Error> for Error {
+    fn from(err: super::Error) -> Self {
+        Error::Key(err)
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> Self {
+        err.to_string()
+    }
+}
+
+type Reply<T> = mpsc::Sender<Result<T,Error>>;
+
+enum Request {
+    Add(SecretKey),
+    Sign { key: KeyId, message: Message, reply: Reply<Signature> },
+    Decrypt { key: KeyId, ciphertext: Ciphertext, reply: Reply<Plaintext> },
+    Stop,
+}
+
+/// Owns secret keys on a thread of its own, see the [module](self) docs.
+pub struct KeyAgent {
+    requests: mpsc::Sender<Request>,
+    stopped: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl KeyAgent {
+    /// Starts an agent holding `keys`.
+    pub fn new(keys: impl IntoIterator<Item = SecretKey>) -> KeyAgent {
+        let mut keys: HashMap<KeyId, SecretKey> = keys.into_iter().map(|sk| (KeyId::of(&sk.public_key()), sk)).collect();
+        let (requests, received) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let worker_stopped = Arc::clone(&stopped);
+        let worker = std::thread::spawn(move || {
+            // Requests still queued when the agent is dropped are dropped with their reply senders, which fails them.
+            for request in received.iter().take_while(|_| !worker_stopped.load(Ordering::Acquire)) {
+                match request {
+                    Request::Add(sk) => { keys.insert(KeyId::of(&sk.public_key()), sk); },
+                    Request::Sign { key, message, reply } =>
+                        { let _ = reply.send(with_key(&keys, key, |sk| authenticity::sign_with(message, sk))); },
+                    Request::Decrypt { key, ciphertext, reply } =>
+                        { let _ = reply.send(with_key(&keys, key, |sk| confidentiality::decrypt_with(ciphertext, sk))); },
+                    Request::Stop => break,
+                }
+            }
+        });
+
+        KeyAgent { requests, stopped, worker: Some(worker) }
+    }
+
+    /// Gives the agent another key, replacing any it has with the same id, and gives that id.
+    pub fn add(&self, sk: SecretKey) -> KeyId {
+        let id = KeyId::of(&sk.public_key());
+        // The worker only stops when the agent is dropped, so this can't fail.
+        let _ = self.requests.send(Request::Add(sk));
+        id
+    }
+
+    /// A new handle for requests to this agent.
+    pub fn handle(&self) -> AgentHandle {
+        AgentHandle { requests: self.requests.clone() }
+    }
+}
+
+impl std::fmt::Debug for KeyAgent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyAgent").finish_non_exhaustive()
+    }
+}
+
+/// Stops the worker, failing the requests it hasn't answered yet, and waits for it to drop the keys.
+impl Drop for KeyAgent {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+        let _ = self.requests.send(Request::Stop);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn with_key<T>(keys: &HashMap<KeyId, SecretKey>, key: KeyId, operation: impl FnOnce(&SecretKey) -> Result<T,super::Error>) -> Result<T,Error> {
+    let sk = keys.get(&key).ok_or(Error::UnknownKey(key))?;
+    Ok(operation(sk)?)
+}
+
+/// Asks a [`KeyAgent`] for signatures and decryptions. Cheap to clone, and [`Send`] and [`Sync`].
+#[derive(Clone, Debug)]
+pub struct AgentHandle {
+    requests: mpsc::Sender<Request>,
+}
+
+impl AgentHandle {
+    /// Signs `message` with the key with id `key`, as [`authenticity::sign`] does.
+    ///
+    /// # Errors
+    /// Gives [`Error::UnknownKey`] when the agent has no such key, [`Error::Key`] when signing fails,
+    /// and [`Error::Stopped`] when the agent has been dropped.
+    pub fn sign<T: Into<Message>>(&self, key: KeyId, message: T) -> Result<Signature,Error> {
+        self.request(|reply| Request::Sign { key, message: message.into(), reply })
+    }
+
+    /// Decrypts `ciphertext` with the key with id `key`, as [`confidentiality::decrypt`] does.
+    ///
+    /// # Errors
+    /// As for [`AgentHandle::sign`].
+    pub fn decrypt<T: Into<Ciphertext>>(&self, key: KeyId, ciphertext: T) -> Result<Plaintext,Error> {
+        self.request(|reply| Request::Decrypt { key, ciphertext: ciphertext.into(), reply })
+    }
+
+    fn request<T>(&self, request: impl FnOnce(Reply<T>) -> Request) -> Result<T,Error> {
+        let (reply, answer) = mpsc::channel();
+        self.requests.send(request(reply)).map_err(|_| Error::Stopped)?;
+        answer.recv().map_err(|_| Error::Stopped)?
+    }
+}
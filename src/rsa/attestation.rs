@@ -0,0 +1,170 @@
+//! Signed statements that a key belongs to a name, for a small web of trust between peers.
+//!
+//! An [`Attestation`] is made by an issuer with [`attest`], and binds a subject key to [`Claims`]
+//! of a name and an optional expiry. What is signed is a canonical encoding, so that the same
+//! attestation always verifies the same way, whichever way it was serialized in between:
+//!
+//! | bytes | field |
+//! | --- | --- |
+//! | 4 | the magic tag `RNAT` |
+//! | 1 | version, currently 1 |
+//! | 16 | the [`KeyId`] of the issuer |
+//! | 4 + .. | the length of the subject's [`PublicKey::to_bytes`] as a big endian [`u32`], and those bytes |
+//! | 4 + .. | the length of the name in bytes as a big endian [`u32`], and its UTF-8 |
+//! | 1 | 1 if there is an expiry, else 0 |
+//! | 8 + 4 | the expiry as seconds and nanoseconds since the Unix epoch, big endian, or zeros |
+//!
+//! # Examples
+//! ```rust
+//! use std::time::SystemTime;
+//! use rustnetworking::rsa::{attest, keygen, attestation::Claims};
+//!
+//! # fn main() -> Result<(),String> {
+//! let issuer = keygen(512)?;
+//! let (subject,_) = keygen(512)?.split();
+//! let attestation = attest(&subject, Claims { name: "bob".into(), expires_at: None }, &issuer)?;
+//! attestation.verify(issuer.public(), SystemTime::now())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt::Display, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::{Serialize, Deserialize};
+
+use super::{authenticity::{self, Signature, Verification}, Error, KeyId, KeyPair, PublicKey};
+
+const MAGIC: &[u8; 4] = b"RNAT";
+const VERSION: u8 = 1;
+
+/// The longest chain [`verify_chain`] follows.
+pub const MAX_CHAIN_LENGTH: usize = 8;
+
+/// What an issuer says about the subject of an [`Attestation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize,Deserialize)]
+pub struct Claims {
+    pub name: String,
+    /// [`None`] for attestations that don't expire. Times before the Unix epoch are signed as the epoch.
+    pub expires_at: Option<SystemTime>,
+}
+
+/// A subject key and [`Claims`] about it, signed by the key with id `issuer`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize,Deserialize)]
+pub struct Attestation {
+    pub subject: PublicKey,
+    pub claims: Claims,
+    pub issuer: KeyId,
+    pub signature: Signature,
+}
+
+/// Errors from [`Attestation::verify`] and [`verify_chain`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttestationError {
+    /// The attestation at `index` of the chain, `0` for a single one, is not signed by the key that is meant to have
+    /// issued it, or has been changed since.
+    BadSignature { index: usize },
+    /// The attestation at `index` of the chain expired at `expires_at`.
+    Expired { index: usize, expires_at: SystemTime },
+    /// A chain must have at least one attestation.
+    EmptyChain,
+    /// A chain of `length` attestations is longer than [`MAX_CHAIN_LENGTH`].
+    ChainTooLong { length: usize },
+}
+
+impl Display for AttestationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttestationError::BadSignature { index } => write!(f, "attestation {index} has a bad signature"),
+            AttestationError::Expired { index, expires_at } => write!(f, "attestation {index} expired at {expires_at:?}"),
+            AttestationError::EmptyChain => write!(f, "the chain of attestations is empty"),
+            AttestationError::ChainTooLong { length } =>
+                write!(f, "a chain of {length} attestations is longer than the maximum of {MAX_CHAIN_LENGTH}"),
+        }
+    }
+}
+
+impl std::error::Error for AttestationError {}
+
+impl From<AttestationError> for String {
+    fn from(err: AttestationError) -> Self {
+        err.to_string()
+    }
+}
+
+/// [`attest`] signs `claims` about `subject` with the secret key of `issuer`.
+///
+/// # Errors
+/// Gives [`Error::MessageTooLong`] when the issuer's key is too small for a SHA-256 digest, see [`authenticity::sign`].
+pub fn attest(subject: &PublicKey, claims: Claims, issuer: &KeyPair) -> Result<Attestation,Error> {
+    let issuer_id = KeyId::of(issuer.public());
+    let signature = issuer.sign(signed_bytes(subject, &claims, issuer_id))?;
+    Ok(Attestation { subject: subject.clone(), claims, issuer: issuer_id, signature })
+}
+
+impl Attestation {
+    /// Checks that the attestation was signed by `issuer` and has not expired at `now`.
+    ///
+    /// # Errors
+    /// Gives [`AttestationError::BadSignature`] when `issuer` is not the issuer or the signature doesn't match,
+    /// and [`AttestationError::Expired`] when the claims expired at or before `now`, both with index 0.
+    pub fn verify(&self, issuer: &PublicKey, now: SystemTime) -> Result<(),AttestationError> {
+        self.verify_at(0, issuer, now)
+    }
+
+    fn verify_at(&self, index: usize, issuer: &PublicKey, now: SystemTime) -> Result<(),AttestationError> {
+        if KeyId::of(issuer) != self.issuer {
+            return Err(AttestationError::BadSignature { index })
+        }
+        let message = signed_bytes(&self.subject, &self.claims, self.issuer);
+        if let Verification::Reject = authenticity::verify(message, self.signature.clone(), issuer.clone()) {
+            return Err(AttestationError::BadSignature { index })
+        }
+        match self.claims.expires_at {
+            Some(expires_at) if now >= expires_at => Err(AttestationError::Expired { index, expires_at }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// [`verify_chain`] checks a chain of attestations from `root`: the first is issued by `root`, and each
+/// after it by the subject of the one before. Gives the subject of the last one, the key the chain vouches for.
+///
+/// # Errors
+/// Gives the error of the first attestation that fails [`Attestation::verify`], with its index in `chain`,
+/// and [`AttestationError::EmptyChain`] or [`AttestationError::ChainTooLong`] when the chain has no attestations
+/// or more than [`MAX_CHAIN_LENGTH`].
+pub fn verify_chain<'a>(chain: &'a [Attestation], root: &'a PublicKey, now: SystemTime) -> Result<&'a PublicKey,AttestationError> {
+    if chain.is_empty() {
+        return Err(AttestationError::EmptyChain)
+    }
+    if chain.len() > MAX_CHAIN_LENGTH {
+        return Err(AttestationError::ChainTooLong { length: chain.len() })
+    }
+    chain.iter().enumerate().try_fold(root, |issuer, (index, attestation)| {
+        attestation.verify_at(index, issuer, now)?;
+        Ok(&attestation.subject)
+    })
+}
+
+/// The canonical encoding of an attestation that is signed, see the [module](self) docs.
+fn signed_bytes(subject: &PublicKey, claims: &Claims, issuer: KeyId) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    out.extend_from_slice(issuer.as_bytes());
+    put_bytes(&mut out, &subject.to_bytes());
+    put_bytes(&mut out, claims.name.as_bytes());
+
+    let expiry = claims.expires_at.map(|expires_at| expires_at.duration_since(UNIX_EPOCH).unwrap_or_default());
+    out.push(u8::from(expiry.is_some()));
+    let expiry = expiry.unwrap_or_default();
+    out.extend_from_slice(&expiry.as_secs().to_be_bytes());
+    out.extend_from_slice(&expiry.subsec_nanos().to_be_bytes());
+    out
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&u32::try_from(bytes.len()).expect("attested fields are below 4 GiB").to_be_bytes());
+    out.extend_from_slice(bytes);
+}
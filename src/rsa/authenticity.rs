@@ -19,7 +19,8 @@
 //! [^note]: https://en.wikipedia.org/wiki/SHA-2
 
 use sha2::{Sha256, Digest};
-use super::{confidentiality::{Message, decrypt, encrypt}, SecretKey, PublicKey};
+use crate::big_num::BigUint;
+use super::{confidentiality::{Message, decrypt_with, encrypt}, SecretKey, PublicKey, Error};
 
 pub type Signature = Vec<u8>;
 
@@ -40,10 +41,16 @@ pub enum Verification {
 /// When verfying the signature, the digest of a hashing on the message is compared to the signature.
 /// 
 /// # Errors
-/// Signing can possible fail and so [`sign`] returns a result.
-pub fn sign<T: Into<Message>>(message: T, sk: SecretKey) -> Result<Signature,String> {
+/// Signing can possible fail and so [`sign`] returns a result. It gives [`Error::MessageTooLong`]
+/// when the modulus is too small for a Sha256 digest.
+pub fn sign<T: Into<Message>>(message: T, sk: SecretKey) -> Result<Signature,Error> {
+    sign_with(message, &sk)
+}
+
+/// [`sign`] without taking the key, for [`KeyPair::sign`](super::KeyPair::sign).
+pub(crate) fn sign_with<T: Into<Message>>(message: T, sk: &SecretKey) -> Result<Signature,Error> {
     let digest: Message = hash(message);
-    decrypt(digest, sk)
+    decrypt_with(digest, sk)
 }
 
 /// [`verify`] verifies a signature against a message and [`PublicKey`].
@@ -53,9 +60,14 @@ pub fn sign<T: Into<Message>>(message: T, sk: SecretKey) -> Result<Signature,Str
 /// # Security
 /// To prevent forgery attacks, [`verify`] assumes the signing is done on a hash of the message
 /// and so it verifies the signature against not the message, but a hashing of it.
+/// The comparison is done in constant time, so the time taken doesn't reveal how much of the hash matched.
 pub fn verify<T: Into<Message>>(message: T, signature: Signature, pk: PublicKey) -> Verification {
-    let unsign: Message = encrypt(signature, &pk);
-    if hash(message) == unsign {
+    let Ok(unsign) = encrypt(signature, &pk) else {
+        return Verification::Reject
+    };
+    let unsign: BigUint = unsign.into();
+    let digest: BigUint = hash(message).into();
+    if digest.ct_eq(&unsign) {
         Verification::Accept
     } else {
         Verification::Reject
@@ -0,0 +1,217 @@
+//! A compact binary encoding of keys with a type tag and version, for files that must still be
+//! readable after this crate changes.
+//!
+//! Every encoding starts with a header of a magic tag, `RNPK` for public keys and `RNSK` for secret
+//! keys, a version byte, currently 1, and the bit size of `n` as a big endian [`u32`]. The numbers
+//! that follow are big endian and of fixed width, so nothing in them needs a length of its own:
+//!
+//! | field | public key | secret key |
+//! | --- | --- | --- |
+//! | | | the prime count as a [`u8`] and the prime width `w` as a big endian [`u16`] |
+//! | `e` | 8 bytes | 8 bytes |
+//! | `n` | [`PublicKey::byte_size`] bytes | [`PublicKey::byte_size`] bytes |
+//! | `d` | | [`PublicKey::byte_size`] bytes |
+//! | primes | | `w` bytes each, in the order of `p,q` and then the other primes |
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, PublicKey};
+//!
+//! # fn main() -> Result<(),String> {
+//! let (pk,_) = keygen(512)?.split();
+//! let bytes = pk.to_bytes();
+//! assert_eq!(b"RNPK", &bytes[..4]);
+//! assert_eq!(pk, PublicKey::from_bytes(&bytes)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::Display;
+
+use crate::big_num::BigUint;
+
+use super::{Error, PublicKey, SecretKey};
+
+const PUBLIC_MAGIC: &[u8; 4] = b"RNPK";
+const SECRET_MAGIC: &[u8; 4] = b"RNSK";
+const VERSION: u8 = 1;
+
+/// Errors from [`PublicKey::from_bytes`] and [`SecretKey::from_bytes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The bytes don't start with the magic tag of the key type, which includes the other key type's tag.
+    WrongMagic { expected: [u8; 4], found: Vec<u8> },
+    /// The version is not one this crate reads.
+    UnknownVersion { version: u8 },
+    /// The bytes end after `found` bytes, but the header says there are `expected`.
+    Truncated { expected: usize, found: usize },
+    /// `count` bytes follow the key.
+    TrailingData { count: usize },
+    /// The header doesn't fit the numbers, like a bit size other than that of `n`.
+    Inconsistent(String),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::WrongMagic { expected, found } =>
+                write!(f, "starts with {}, not {}", String::from_utf8_lossy(found), String::from_utf8_lossy(expected)),
+            FormatError::UnknownVersion { version } => write!(f, "unknown version {version}"),
+            FormatError::Truncated { expected, found } => write!(f, "truncated to {found} of {expected} bytes"),
+            FormatError::TrailingData { count } => write!(f, "{count} bytes after the key"),
+            FormatError::Inconsistent(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<FormatError> for Error {
+    fn from(err: FormatError) -> Self {
+        Error::Format(err)
+    }
+}
+
+impl PublicKey {
+    /// Encodes the key as `RNPK`, see the [module](self) docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = header(PUBLIC_MAGIC, self.bit_size());
+        out.extend_from_slice(&self.e.to_be_bytes());
+        out.extend(fixed(&self.n, self.byte_size()));
+        out
+    }
+
+    /// Decodes a key written by [`PublicKey::to_bytes`].
+    ///
+    /// # Errors
+    /// Gives [`Error::Format`] when `bytes` has the wrong magic tag or an unknown version, is truncated
+    /// or has bytes after the key, or its bit size is not that of `n`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey,Error> {
+        let mut reader = Reader::new(PUBLIC_MAGIC, bytes)?;
+        let width = reader.modulus_width();
+        reader.need(8 + width)?;
+        let e = reader.read_u64();
+        let n = reader.read(width);
+        reader.finish(&n)?;
+        Ok(PublicKey { n, e })
+    }
+}
+
+impl SecretKey {
+    /// Encodes the key as `RNSK`, see the [module](self) docs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let byte_size = self.n.byte_size();
+        let prime_width = self.primes.iter().map(BigUint::byte_size).max().unwrap_or(0);
+
+        let mut out = header(SECRET_MAGIC, self.n.bit_size());
+        out.push(u8::try_from(self.primes.len()).expect("keys have far fewer than 256 primes"));
+        out.extend_from_slice(&u16::try_from(prime_width).expect("primes are below 2^(8 * 65535)").to_be_bytes());
+        out.extend_from_slice(&self.e.to_be_bytes());
+        out.extend(fixed(&self.n, byte_size));
+        out.extend(fixed(&self.d, byte_size));
+        for prime in &self.primes {
+            out.extend(fixed(prime, prime_width));
+        }
+        out
+    }
+
+    /// Decodes a key written by [`SecretKey::to_bytes`].
+    ///
+    /// # Errors
+    /// Gives [`Error::Format`] as for [`PublicKey::from_bytes`], and also when there are fewer than
+    /// two primes, and [`Error::InvalidKey`] when the primes are below 2 or not coprime, `n` is not their product,
+    /// `e` is less than 3, or `d` is not a private exponent of `e` below `n`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey,Error> {
+        let mut reader = Reader::new(SECRET_MAGIC, bytes)?;
+        let width = reader.modulus_width();
+        reader.need(3)?;
+        let prime_count = reader.read_u8() as usize;
+        let prime_width = u16::from_be_bytes([reader.read_u8(), reader.read_u8()]) as usize;
+        if prime_count < 2 {
+            return Err(FormatError::Inconsistent(format!("{prime_count} primes, a key needs at least 2")).into())
+        }
+
+        reader.need(8 + 2 * width + prime_count * prime_width)?;
+        let e = reader.read_u64();
+        let n = reader.read(width);
+        let d = reader.read(width);
+        let primes: Vec<BigUint> = (0..prime_count).map(|_| reader.read(prime_width)).collect();
+        reader.finish(&n)?;
+
+        SecretKey::from_parts_checked(primes, e, d, n)
+    }
+}
+
+fn header(magic: &[u8; 4], bit_size: u32) -> Vec<u8> {
+    let mut out = magic.to_vec();
+    out.push(VERSION);
+    out.extend_from_slice(&bit_size.to_be_bytes());
+    out
+}
+
+fn fixed(x: &BigUint, width: usize) -> Vec<u8> {
+    x.to_bytes_be_padded(width).expect("the width is at least the byte size of the number")
+}
+
+/// Reads the fields after a checked header, where every read has been made safe by [`Reader::need`].
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    bit_size: u32,
+}
+
+impl<'a> Reader<'a> {
+    /// Checks the magic tag and version and reads the bit size.
+    fn new(magic: &[u8; 4], bytes: &'a [u8]) -> Result<Reader<'a>,FormatError> {
+        if !bytes.starts_with(magic) {
+            return Err(FormatError::WrongMagic { expected: *magic, found: bytes[..bytes.len().min(4)].to_vec() })
+        }
+        let mut reader = Reader { bytes, position: 4, bit_size: 0 };
+        reader.need(1)?;
+        let version = reader.read_u8();
+        if version != VERSION {
+            return Err(FormatError::UnknownVersion { version })
+        }
+        reader.need(4)?;
+        reader.bit_size = u32::from_be_bytes(std::array::from_fn(|_| reader.read_u8()));
+        Ok(reader)
+    }
+
+    fn modulus_width(&self) -> usize {
+        self.bit_size.div_ceil(8) as usize
+    }
+
+    /// Fails unless `length` more bytes can be read.
+    fn need(&self, length: usize) -> Result<(),FormatError> {
+        let expected = self.position + length;
+        if self.bytes.len() < expected {
+            return Err(FormatError::Truncated { expected, found: self.bytes.len() })
+        }
+        Ok(())
+    }
+
+    fn read_u8(&mut self) -> u8 {
+        self.position += 1;
+        self.bytes[self.position - 1]
+    }
+
+    fn read_u64(&mut self) -> u64 {
+        u64::from_be_bytes(std::array::from_fn(|_| self.read_u8()))
+    }
+
+    fn read(&mut self, width: usize) -> BigUint {
+        self.position += width;
+        BigUint::from_bytes_be(&self.bytes[self.position - width..self.position])
+    }
+
+    /// Fails if bytes are left over, or if the header's bit size is not that of `n`.
+    fn finish(&self, n: &BigUint) -> Result<(),FormatError> {
+        if self.position != self.bytes.len() {
+            return Err(FormatError::TrailingData { count: self.bytes.len() - self.position })
+        }
+        if n.bit_size() != self.bit_size {
+            return Err(FormatError::Inconsistent(format!("header says {} bits, but n has {}", self.bit_size, n.bit_size())))
+        }
+        Ok(())
+    }
+}
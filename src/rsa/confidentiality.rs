@@ -13,12 +13,16 @@
 //! 
 //! # Security
 //! RSA has various security issues when used alone. It's therefore recommended to use OAEP[^note].
+//! For example, with a small exponent like `e = 3` (see [`keygen_with_exponent`](super::keygen_with_exponent)) any message `m` with `m^3 < n` is never reduced
+//! modulo `n`, so anyone can recover it from the ciphertext with a cube root (see `BigUint::nth_root`).
 //! 
 //! [^note]: `https://en.wikipedia.org/wiki/Optimal_asymmetric_encryption_padding`
 
-use crate::{big_num::{BigUint}, modular};
+use rand::{CryptoRng, RngCore};
 
-use super::{PublicKey, SecretKey, E};
+use crate::{big_num::BigUint, modular::crt};
+
+use super::{PublicKey, SecretKey, Error};
 
 pub type Message = Vec<u8>;
 pub type Plaintext = Message;
@@ -26,24 +30,28 @@ pub type Ciphertext = Message;
 
 
 /// [`encrypt`] encrypts a [`Plaintext`] message `m` into a [`Ciphertext`] message `c` using a given [`PublicKey`] pk.
-/// The underlying algorithm is `c = m^e mod n`, where `e = 3` is constant and `n` is given by `pk`.
+/// The underlying algorithm is `c = m^e mod n`, where `e,n` are given by `pk`.
 /// 
 /// # Examples
 /// ```rust
 /// use rustnetworking::rsa::{confidentiality::{Plaintext,Ciphertext,encrypt,decrypt},keygen};
 /// #
 /// # fn main() -> Result<(),String> {
-/// let (pk,sk) = keygen(2048)?;
+/// let (pk,sk) = keygen(2048)?.split();
 /// 
 /// let m: Plaintext = "Very secret message ;p".as_bytes().into();
-/// let c: Ciphertext = encrypt(m, &pk).into();
+/// let c: Ciphertext = encrypt(m, &pk)?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn encrypt<T: Into<Plaintext>>(plaintext: T, pk: &PublicKey) -> Ciphertext {
+/// 
+/// # Errors
+/// [`encrypt`] gives [`Error::MessageTooLong`] when `m` is not less than `n`.
+pub fn encrypt<T: Into<Plaintext>>(plaintext: T, pk: &PublicKey) -> Result<Ciphertext,Error> {
     let plaintext_as_number: BigUint = plaintext.into().into();
-    let cipher = plaintext_as_number.modpow(&BigUint::from_i32(E).unwrap(), &pk.n);
-    cipher.into()
+    fits_modulus(&plaintext_as_number, &pk.n)?;
+    let cipher = plaintext_as_number.modpow(&BigUint::from(pk.e), &pk.n);
+    Ok(cipher.into())
 }
 
 
@@ -51,11 +59,13 @@ pub fn encrypt<T: Into<Plaintext>>(plaintext: T, pk: &PublicKey) -> Ciphertext {
 /// using a [`SecretKey`] sk.
 /// The underlying algorithm is `m = c^d mod n`, where `d` is the modular inverse 
 /// 
-/// `d = e^(-1) mod (p - 1)(q - 1)`. 
+/// `d = e^(-1) mod λ(n)`, where `λ(n) = lcm(p - 1, q - 1)`. 
 /// 
-/// `p,q` are given by `pk`.
+/// Both `d` and `n` are stored in `sk`, so they are not recomputed on each call. The exponentiation is
+/// done modulo each prime of `n` and combined with the Chinese remainder theorem, which also works for
+/// multi-prime keys.
 /// 
-/// [`decrypt`] returns a [`Result<Plaintext,String>`] because the 
+/// [`decrypt`] returns a [`Result<Plaintext,Error>`] because the 
 /// function might fail if given a wrong key [`SecretKey`].
 /// 
 /// # Examples
@@ -63,9 +73,9 @@ pub fn encrypt<T: Into<Plaintext>>(plaintext: T, pk: &PublicKey) -> Ciphertext {
 /// use rustnetworking::rsa::{confidentiality::{Plaintext,Ciphertext,encrypt,decrypt},keygen};
 ///
 /// # fn main() -> Result<(),String> {
-/// let (pk,sk) = keygen(2048)?;
+/// let (pk,sk) = keygen(2048)?.split();
 /// # let m: Plaintext = "Very secret message ;p".into();
-/// # let c: Ciphertext = encrypt(m, &pk).into();
+/// # let c: Ciphertext = encrypt(m, &pk)?;
 /// // ...
 /// let decrypted = decrypt(c, sk)?;
 /// # Ok(())
@@ -73,19 +83,113 @@ pub fn encrypt<T: Into<Plaintext>>(plaintext: T, pk: &PublicKey) -> Ciphertext {
 /// ```
 /// 
 /// # Errors
-/// [`decrypt`] gives an error when given a bad or wrong [`SecretKey`],
-pub fn decrypt<T: Into<Ciphertext>>(ciphertext: T, sk: SecretKey) -> Result<Plaintext,String> {
+/// [`decrypt`] gives [`Error::MessageTooLong`] when `c` is not less than the `n` of `sk`, which means it was not
+/// encrypted for this key.
+pub fn decrypt<T: Into<Ciphertext>>(ciphertext: T, sk: SecretKey) -> Result<Plaintext,Error> {
+    decrypt_with(ciphertext, &sk)
+}
+
+/// [`decrypt`] without taking the key, for [`KeyPair::decrypt`](super::KeyPair::decrypt).
+pub(crate) fn decrypt_with<T: Into<Ciphertext>>(ciphertext: T, sk: &SecretKey) -> Result<Plaintext,Error> {
     let ciphertext_number: BigUint = ciphertext.into().into();
-    let d = create_d(&sk.p,&sk.q).map_err(|_| "bad key")?;
+    fits_modulus(&ciphertext_number, &sk.n)?;
 
-    let message = ciphertext_number.modpow(&d, &(sk.p * sk.q));
+    // Decrypting modulo each prime with a reduced exponent and combining the results
+    // is much cheaper than one exponentiation modulo n. The reduced exponents and residues
+    // are BigUints, so with the zeroize feature they are wiped as soon as they are dropped.
+    let residues: Vec<BigUint> = sk.primes.iter()
+        .map(|r| (&ciphertext_number % r).modpow(&sk.crt_exponent(r), r))
+        .collect();
+    let message = crt(&residues, &sk.primes)?;
     Ok(message.into())
 }
 
-fn create_d(p: &BigUint,q: &BigUint) -> Result<BigUint,String> {
-    let big_one = BigUint::from_i32(1).unwrap();
-    let modulus: BigUint = (p.clone() - big_one.clone())? * (q.clone() - big_one)?;
+/// The fewest padding bytes [`encrypt_pkcs1v15`] adds, from RFC 8017.
+const MIN_PKCS1V15_PADDING: usize = 8;
+
+/// [`encrypt_pkcs1v15`] encrypts `plaintext` with the RSAES-PKCS1-v1_5 scheme of RFC 8017, for talking to
+/// systems that can't do better. The message is padded to `0x00 0x02 PS 0x00 M`, the length of `n`, where
+/// `PS` is at least 8 random nonzero bytes from `rng`, and the ciphertext is always [`PublicKey::byte_size`] bytes.
+/// 
+/// Prefer OAEP where the other side has it: decryption that reports bad padding in any way, even by taking
+/// longer, lets an attacker decrypt messages with many queries (Bleichenbacher's attack).
+/// 
+/// # Examples
+/// ```rust
+/// use rustnetworking::rsa::{confidentiality::{encrypt_pkcs1v15,decrypt_pkcs1v15},keygen};
+/// 
+/// # fn main() -> Result<(),String> {
+/// let (pk,sk) = keygen(1024)?.split();
+/// let c = encrypt_pkcs1v15("for a legacy peer", &pk, &mut rand::thread_rng())?;
+/// assert_eq!(pk.byte_size(), c.len());
+/// assert_eq!("for a legacy peer".as_bytes(), &decrypt_pkcs1v15(c, &sk)?[..]);
+/// # Ok(())
+/// # }
+/// ```
+/// 
+/// # Errors
+/// [`encrypt_pkcs1v15`] gives [`Error::MessageTooLong`] when the message is longer than 11 bytes less than `n`.
+pub fn encrypt_pkcs1v15<T: Into<Plaintext>>(plaintext: T, pk: &PublicKey, rng: &mut (impl RngCore + CryptoRng)) -> Result<Ciphertext,Error> {
+    let message = plaintext.into();
+    let k = pk.byte_size();
+    if message.len() + MIN_PKCS1V15_PADDING + 3 > k {
+        return Err(Error::MessageTooLong { message_bits: (message.len() * 8) as u32, modulus_bits: pk.bit_size() })
+    }
+
+    let mut padding = vec![0; k - message.len() - 3];
+    for byte in &mut padding {
+        while *byte == 0 {
+            *byte = (rng.next_u32() & 0xff) as u8;
+        }
+    }
+    let mut encoded = vec![0x00, 0x02];
+    encoded.extend(padding);
+    encoded.push(0x00);
+    encoded.extend(message);
+
+    let cipher = BigUint::from(encoded).modpow(&BigUint::from(pk.e), &pk.n);
+    Ok(cipher.to_bytes_be_padded(k).expect("the ciphertext is below n"))
+}
+
+/// [`decrypt_pkcs1v15`] decrypts a ciphertext of [`encrypt_pkcs1v15`] and removes its padding.
+/// 
+/// The padding is checked in full, without stopping at the first flaw, and every flaw gives the same error,
+/// so that the check itself tells an attacker as little as it can. The exponentiation before it is not
+/// constant time, so this is a mitigation and not a guarantee.
+/// 
+/// # Errors
+/// [`decrypt_pkcs1v15`] gives [`Error::DecryptionFailed`] when the ciphertext is not [`PublicKey::byte_size`] bytes,
+/// not below `n`, or doesn't decrypt to a padded message.
+pub fn decrypt_pkcs1v15<T: Into<Ciphertext>>(ciphertext: T, sk: &SecretKey) -> Result<Plaintext,Error> {
+    let ciphertext = ciphertext.into();
+    let k = sk.n.byte_size();
+    if ciphertext.len() != k || k < MIN_PKCS1V15_PADDING + 3 {
+        return Err(Error::DecryptionFailed)
+    }
+    let encoded = decrypt_with(ciphertext, sk)
+        .map_err(|_| Error::DecryptionFailed)
+        .map(|decrypted| BigUint::from(decrypted).to_bytes_be_padded(k).expect("the message is below n"))?;
+
+    let mut valid = u8::from(encoded[0] == 0x00) & u8::from(encoded[1] == 0x02);
+    let mut separator = 0;
+    let mut searching = 1u8;
+    for (i, &byte) in encoded.iter().enumerate().skip(2) {
+        let zero = u8::from(byte == 0);
+        separator |= i * usize::from(searching & zero);
+        searching &= 1 ^ zero;
+    }
+    valid &= 1 ^ searching;
+    valid &= u8::from(separator >= 2 + MIN_PKCS1V15_PADDING);
 
-    let d = modular::inverse(BigUint::from_i32(E).unwrap(), modulus)?;
-    Ok(d)
-}
\ No newline at end of file
+    if valid == 0 {
+        return Err(Error::DecryptionFailed)
+    }
+    Ok(encoded[separator + 1..].to_vec())
+}
+
+fn fits_modulus(message: &BigUint, n: &BigUint) -> Result<(),Error> {
+    if message >= n {
+        return Err(Error::MessageTooLong { message_bits: message.bit_size(), modulus_bits: n.bit_size() })
+    }
+    Ok(())
+}
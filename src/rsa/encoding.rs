@@ -0,0 +1,154 @@
+//! The small part of DER[^note] needed for encoding RSA keys, and the X.509 `SubjectPublicKeyInfo`
+//! encoding of [`PublicKey`] built on it.
+//!
+//! [^note]: `https://en.wikipedia.org/wiki/X.690#DER_encoding`
+
+use crate::big_num::BigUint;
+
+use super::{Error, PublicKey};
+
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const NULL: u8 = 0x05;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const SEQUENCE: u8 = 0x30;
+
+/// The `rsaEncryption` object identifier `1.2.840.113549.1.1.1`, encoded.
+const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+impl PublicKey {
+    /// Encodes the key as a DER X.509 `SubjectPublicKeyInfo`, the layout used in certificates
+    /// and by `openssl rsa -pubin -inform DER`.
+    pub fn to_der(&self) -> Vec<u8> {
+        let algorithm = encode_sequence(&[encode(OBJECT_IDENTIFIER, RSA_ENCRYPTION), encode(NULL, &[])]);
+        let mut key = vec![0];
+        key.extend(self.to_pkcs1_der());
+        encode_sequence(&[algorithm, encode(BIT_STRING, &key)])
+    }
+
+    /// Decodes a DER X.509 `SubjectPublicKeyInfo` of an RSA key.
+    ///
+    /// # Errors
+    /// Gives [`Error::Encoding`] when `der` is not such a structure, including when anything follows it.
+    pub fn from_der(der: &[u8]) -> Result<PublicKey,Error> {
+        let mut outer = Reader::new(der);
+        let mut info = outer.read_sequence()?;
+        let mut algorithm = info.read_sequence()?;
+        if algorithm.read(OBJECT_IDENTIFIER)? != RSA_ENCRYPTION {
+            return Err(Error::Encoding("not an rsaEncryption key".into()))
+        }
+        if !algorithm.read(NULL)?.is_empty() {
+            return Err(malformed("NULL with contents"))
+        }
+        algorithm.finish()?;
+
+        let key = match info.read(BIT_STRING)? {
+            [0, key @ ..] => key,
+            _ => return Err(malformed("bit string with unused bits")),
+        };
+        info.finish()?;
+        outer.finish()?;
+        PublicKey::from_pkcs1_der(key)
+    }
+}
+
+/// Encodes a tag, the DER length of `contents` and the contents themselves.
+pub(crate) fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if contents.len() < 0x80 {
+        out.push(contents.len() as u8);
+    } else {
+        let length: Vec<u8> = contents.len().to_be_bytes().into_iter().skip_while(|&byte| byte == 0).collect();
+        out.push(0x80 | length.len() as u8);
+        out.extend(length);
+    }
+    out.extend_from_slice(contents);
+    out
+}
+
+pub(crate) fn encode_integer(x: &BigUint) -> Vec<u8> {
+    let mut bytes = x.to_bytes_be();
+    // The top bit is the sign, so a leading zero byte keeps large values positive.
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    encode(INTEGER, &bytes)
+}
+
+pub(crate) fn encode_sequence(elements: &[Vec<u8>]) -> Vec<u8> {
+    encode(SEQUENCE, &elements.concat())
+}
+
+/// Reads DER elements one after another from the front of a byte slice.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    /// Reads an element with the given tag, giving its contents.
+    pub(crate) fn read(&mut self, tag: u8) -> Result<&'a [u8],Error> {
+        let (&found, rest) = self.bytes.split_first().ok_or_else(|| malformed("unexpected end of input"))?;
+        if found != tag {
+            return Err(malformed(&format!("expected tag {tag:#04x}, found {found:#04x}")))
+        }
+        let (&first, rest) = rest.split_first().ok_or_else(|| malformed("missing length"))?;
+        let (length, rest) = if first < 0x80 {
+            (first as usize, rest)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > size_of::<usize>() || rest.len() < count {
+                return Err(malformed("bad length"))
+            }
+            let (length, rest) = rest.split_at(count);
+            let length = length.iter().fold(0, |acc, &byte| acc << 8 | byte as usize);
+            // DER allows only the shortest length encoding.
+            if length < 0x80 || length >> (8 * (count - 1)) == 0 {
+                return Err(malformed("length is not minimally encoded"))
+            }
+            (length, rest)
+        };
+        if rest.len() < length {
+            return Err(malformed("length runs past the end of input"))
+        }
+        let (contents, rest) = rest.split_at(length);
+        self.bytes = rest;
+        Ok(contents)
+    }
+
+    pub(crate) fn read_sequence(&mut self) -> Result<Reader<'a>,Error> {
+        Ok(Reader::new(self.read(SEQUENCE)?))
+    }
+
+    /// Reads an integer, which must not be negative.
+    pub(crate) fn read_integer(&mut self) -> Result<BigUint,Error> {
+        let contents = self.read(INTEGER)?;
+        match contents.first() {
+            None => Err(malformed("empty integer")),
+            Some(byte) if byte & 0x80 != 0 => Err(malformed("negative integer")),
+            Some(&0) if contents.get(1).is_some_and(|byte| byte & 0x80 == 0) => Err(malformed("integer is not minimally encoded")),
+            Some(_) => Ok(BigUint::from_bytes_be(contents)),
+        }
+    }
+
+    /// Whether everything has been read.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Checks that everything has been read.
+    pub(crate) fn finish(self) -> Result<(),Error> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(malformed("trailing data"))
+        }
+    }
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::Encoding(format!("bad DER: {reason}"))
+}
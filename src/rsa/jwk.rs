@@ -0,0 +1,155 @@
+//! JSON Web Key[^note] import and export of keys.
+//!
+//! Keys are JSON objects with `"kty": "RSA"` and the key's numbers as base64url encoded,
+//! big endian bytes. Fields that aren't needed, like `alg` or `kid`, are ignored on import.
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, PublicKey};
+//!
+//! # fn main() -> Result<(),String> {
+//! let (pk,_) = keygen(512)?.split();
+//! let jwk = pk.to_jwk();
+//! assert!(jwk.contains(r#""e":"AQAB""#));
+//! let decoded = PublicKey::from_jwk(&jwk)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [^note]: `https://www.rfc-editor.org/rfc/rfc7518#section-6.3`
+
+use serde::{Serialize, Deserialize};
+
+use crate::big_num::BigUint;
+
+use super::{pem::{base64url_encode, base64url_decode}, Error, PublicKey, SecretKey};
+
+const KEY_TYPE: &str = "RSA";
+
+/// The fields of an RSA JWK that this module reads or writes, all optional so that
+/// a missing field can be reported by name.
+#[derive(Serialize, Deserialize, Default)]
+struct JwkFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    d: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dq: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    qi: Option<String>,
+    /// The primes after `p,q` of a multi-prime key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oth: Option<Vec<OtherPrimeInfo>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OtherPrimeInfo {
+    r: Option<String>,
+    d: Option<String>,
+    t: Option<String>,
+}
+
+impl PublicKey {
+    /// Encodes the key as a JWK with the fields `kty`, `n` and `e`.
+    pub fn to_jwk(&self) -> String {
+        let fields = JwkFields {
+            kty: Some(KEY_TYPE.into()),
+            n: Some(encode(&self.n)),
+            e: Some(encode(&BigUint::from(self.e))),
+            ..Default::default()
+        };
+        serde_json::to_string(&fields).expect("JWK fields are all strings")
+    }
+
+    /// Decodes an RSA JWK, reading `n` and `e`. A secret key's JWK works too.
+    ///
+    /// # Errors
+    /// Gives [`Error::Encoding`] when `jwk` is not a JSON object, its `kty` is not `RSA`,
+    /// or `n` or `e` is missing or not base64url.
+    pub fn from_jwk(jwk: &str) -> Result<PublicKey,Error> {
+        PublicKey::from_jwk_fields(&parse(jwk)?)
+    }
+
+    fn from_jwk_fields(fields: &JwkFields) -> Result<PublicKey,Error> {
+        let n = decode(&fields.n, "n")?;
+        let e = decode(&fields.e, "e")?.to_u64()
+            .ok_or_else(|| Error::Encoding("JWK field `e` does not fit in 64 bits".into()))?;
+        Ok(PublicKey { n, e })
+    }
+}
+
+impl SecretKey {
+    /// Encodes the key as a JWK with the fields `kty`, `n`, `e`, `d`, `p`, `q`, `dp`, `dq` and `qi`,
+    /// and `oth` for multi-prime keys.
+    pub fn to_jwk(&self) -> String {
+        let (dp, dq, qi) = self.crt_values();
+        let oth: Vec<OtherPrimeInfo> = self.other_prime_infos().iter()
+            .map(|(r, d, t)| OtherPrimeInfo { r: Some(encode(r)), d: Some(encode(d)), t: Some(encode(t)) })
+            .collect();
+        let fields = JwkFields {
+            kty: Some(KEY_TYPE.into()),
+            n: Some(encode(&self.n)),
+            e: Some(encode(&BigUint::from(self.e))),
+            d: Some(encode(&self.d)),
+            p: Some(encode(&self.primes[0])),
+            q: Some(encode(&self.primes[1])),
+            dp: Some(encode(&dp)),
+            dq: Some(encode(&dq)),
+            qi: Some(encode(&qi)),
+            oth: (!oth.is_empty()).then_some(oth),
+        };
+        serde_json::to_string(&fields).expect("JWK fields are all strings")
+    }
+
+    /// Decodes an RSA JWK, reading `n`, `e`, `d`, `p` and `q`, and the `r` of each entry in `oth`
+    /// for multi-prime keys.
+    ///
+    /// `dp`, `dq` and `qi`, and `d` and `t` in `oth`, are not needed since they only make decrypting faster,
+    /// so they may be left out.
+    ///
+    /// # Errors
+    /// Gives [`Error::Encoding`] when `jwk` is not a JSON object, its `kty` is not `RSA`, or one of the
+    /// fields it reads is missing or not base64url, and [`Error::InvalidKey`] when the primes are below 2 or
+    /// not coprime, `n` is not their product, `e` is less than 3, or `d` is not a private exponent of `e` below `n`.
+    pub fn from_jwk(jwk: &str) -> Result<SecretKey,Error> {
+        let fields = parse(jwk)?;
+        let PublicKey { n, e } = PublicKey::from_jwk_fields(&fields)?;
+        let d = decode(&fields.d, "d")?;
+        let mut primes = vec![decode(&fields.p, "p")?, decode(&fields.q, "q")?];
+        for info in fields.oth.iter().flatten() {
+            primes.push(decode(&info.r, "r")?);
+        }
+
+        SecretKey::from_parts_checked(primes, e, d, n)
+    }
+}
+
+fn parse(jwk: &str) -> Result<JwkFields,Error> {
+    let fields: JwkFields = serde_json::from_str(jwk).map_err(|err| Error::Encoding(format!("bad JWK: {err}")))?;
+    match fields.kty.as_deref() {
+        Some(KEY_TYPE) => Ok(fields),
+        Some(kty) => Err(Error::Encoding(format!("JWK key type is `{kty}`, not `{KEY_TYPE}`"))),
+        None => Err(Error::Encoding("JWK is missing the required field `kty`".into())),
+    }
+}
+
+fn encode(x: &BigUint) -> String {
+    base64url_encode(&x.to_bytes_be())
+}
+
+fn decode(field: &Option<String>, name: &str) -> Result<BigUint,Error> {
+    let encoded = field.as_deref().ok_or_else(|| Error::Encoding(format!("JWK is missing the required field `{name}`")))?;
+    let bytes = base64url_decode(encoded).map_err(|_| Error::Encoding(format!("JWK field `{name}` is not base64url")))?;
+    Ok(BigUint::from_bytes_be(&bytes))
+}
@@ -0,0 +1,219 @@
+//! A directory of named key pairs on disk.
+//!
+//! Each key is one file, `<name>.key`, holding the JSON serialization of its [`SecretKey`],
+//! or with [`Keystore::open_sealed`] the bytes of [`SecretKey::seal`]. Keys are written to a
+//! hidden temporary file first and then renamed over the old one, so a reader, or another
+//! [`Keystore`] on the same directory, sees either the old key or the new one and never half
+//! of either. A write that is cut short leaves only its temporary file, which [`Keystore::list`]
+//! and [`Keystore::load`] ignore.
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, keystore::Keystore};
+//!
+//! # fn main() -> Result<(),String> {
+//! # let dir = std::env::temp_dir().join(format!("keystore-doctest-{}", std::process::id()));
+//! let keystore = Keystore::open(&dir)?;
+//! let key_pair = keygen(512)?;
+//! keystore.store("server", &key_pair)?;
+//! assert_eq!(vec!["server"], keystore.list()?);
+//! assert_eq!(key_pair.public(), keystore.load("server")?.public());
+//! # std::fs::remove_dir_all(&dir).map_err(|err| err.to_string())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt::Display, fs, io::{self, Write}, path::{Path, PathBuf}, sync::atomic::{AtomicU64, Ordering}};
+
+use super::{KeyPair, SecretKey};
+
+const EXTENSION: &str = "key";
+
+/// Counts the writes of this process, so that concurrent writes get distinct temporary files.
+static WRITES: AtomicU64 = AtomicU64::new(0);
+
+/// Errors from a [`Keystore`].
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing the directory failed.
+    Io(io::Error),
+    /// Names must be non-empty and made of ASCII letters, digits, `-`, `_` and `.`, not starting with `.`.
+    InvalidName(String),
+    /// There is no key with this name.
+    NotFound(String),
+    /// The key file is there but doesn't hold a key, or it was sealed with another passphrase.
+    Key(super::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "keystore i/o failed: {err}"),
+            Error::InvalidName(name) => write!(f, "`{name}` is not a valid key name"),
+            Error::NotFound(name) => write!(f, "no key named `{name}`"),
+            Error::Key(err) => write!(f, "stored key is unreadable: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Key(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<super::Error> for Error {
+    fn from(err: super::Error) -> Self {
+        Error::Key(err)
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> Self {
+        err.to_string()
+    }
+}
+
+/// Named key pairs in a directory, see the [module](self) docs.
+pub struct Keystore {
+    dir: PathBuf,
+    passphrase: Option<String>,
+}
+
+/// Shows the directory and whether keys are sealed, but not the passphrase.
+impl std::fmt::Debug for Keystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keystore")
+            .field("dir", &self.dir)
+            .field("sealed", &self.passphrase.is_some())
+            .finish()
+    }
+}
+
+impl Keystore {
+    /// Opens the keystore in the directory `path`, creating it if it doesn't exist.
+    /// Keys are stored unencrypted, so the directory should only be readable by its owner.
+    ///
+    /// # Errors
+    /// Gives [`Error::Io`] when the directory can't be created.
+    pub fn open(path: impl AsRef<Path>) -> Result<Keystore,Error> {
+        fs::create_dir_all(&path)?;
+        Ok(Keystore { dir: path.as_ref().to_path_buf(), passphrase: None })
+    }
+
+    /// As [`Keystore::open`], but keys are sealed under `passphrase`, see [`SecretKey::seal`].
+    ///
+    /// # Errors
+    /// Gives [`Error::Io`] when the directory can't be created.
+    pub fn open_sealed(path: impl AsRef<Path>, passphrase: &str) -> Result<Keystore,Error> {
+        Ok(Keystore { passphrase: Some(passphrase.into()), ..Keystore::open(path)? })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Stores `key_pair` under `name`, replacing any key of that name.
+    ///
+    /// # Errors
+    /// Gives [`Error::InvalidName`] for a bad `name` and [`Error::Io`] when writing fails,
+    /// in which case the old key, if any, is left as it was.
+    pub fn store(&self, name: &str, key_pair: &KeyPair) -> Result<(),Error> {
+        let path = self.key_path(name)?;
+        let bytes = match &self.passphrase {
+            Some(passphrase) => key_pair.secret().seal(passphrase),
+            None => serde_json::to_vec(key_pair.secret()).expect("secret keys serialize"),
+        };
+
+        let temporary = self.dir.join(format!(".{name}.{}.{}.tmp", std::process::id(), WRITES.fetch_add(1, Ordering::Relaxed)));
+        let written = write_new(&temporary, &bytes).and_then(|()| fs::rename(&temporary, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        Ok(written?)
+    }
+
+    /// Loads the key pair stored under `name`.
+    ///
+    /// # Errors
+    /// Gives [`Error::InvalidName`] for a bad `name`, [`Error::NotFound`] when there is no such key,
+    /// [`Error::Io`] when reading fails, and [`Error::Key`] when the file doesn't hold a key, which for
+    /// a sealed keystore includes [`Error::WrongPassphrase`](super::Error::WrongPassphrase).
+    pub fn load(&self, name: &str) -> Result<KeyPair,Error> {
+        let path = self.key_path(name)?;
+        let bytes = fs::read(&path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => Error::NotFound(name.into()),
+            _ => Error::Io(err),
+        })?;
+        let secret = match &self.passphrase {
+            Some(passphrase) => SecretKey::open(&bytes, passphrase)?,
+            None => serde_json::from_slice(&bytes)
+                .map_err(|err| super::Error::Encoding(format!("bad key file: {err}")))?,
+        };
+        Ok(secret.into())
+    }
+
+    /// The names of the stored keys, sorted.
+    ///
+    /// # Errors
+    /// Gives [`Error::Io`] when the directory can't be read.
+    pub fn list(&self) -> Result<Vec<String>,Error> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == EXTENSION) {
+                if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()).filter(|name| valid_name(name)) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Deletes the key stored under `name`.
+    ///
+    /// # Errors
+    /// Gives [`Error::InvalidName`] for a bad `name`, [`Error::NotFound`] when there is no such key,
+    /// and [`Error::Io`] when removing it fails.
+    pub fn delete(&self, name: &str) -> Result<(),Error> {
+        fs::remove_file(self.key_path(name)?).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => Error::NotFound(name.into()),
+            _ => Error::Io(err),
+        })
+    }
+
+    fn key_path(&self, name: &str) -> Result<PathBuf,Error> {
+        if !valid_name(name) {
+            return Err(Error::InvalidName(name.into()))
+        }
+        Ok(self.dir.join(format!("{name}.{EXTENSION}")))
+    }
+}
+
+/// Names can't hold path separators, and can't start with `.`, which marks temporary files.
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.')
+        && name.bytes().all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'))
+}
+
+/// Writes `bytes` to a new file at `path` that only its owner can read, and flushes it to disk.
+fn write_new(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
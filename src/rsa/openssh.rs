@@ -0,0 +1,112 @@
+//! OpenSSH public key lines, as found in `authorized_keys` and `.pub` files.
+//!
+//! A line is `ssh-rsa <base64> <comment>`, where the base64 is the SSH wire encoding[^note]
+//! of the string `ssh-rsa` followed by the mpints `e` and `n`.
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, PublicKey};
+//!
+//! # fn main() -> Result<(),String> {
+//! let (pk,_) = keygen(512)?.split();
+//! let line = pk.to_openssh("alice@example.com");
+//! assert!(line.starts_with("ssh-rsa AAAAB3NzaC1yc2E"));
+//! let decoded = PublicKey::from_openssh(&line)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [^note]: `https://www.rfc-editor.org/rfc/rfc4251#section-5` and `https://www.rfc-editor.org/rfc/rfc4253#section-6.6`
+
+use crate::big_num::BigUint;
+
+use super::{pem::{base64_encode, base64_decode}, Error, PublicKey};
+
+const KEY_TYPE: &str = "ssh-rsa";
+
+impl PublicKey {
+    /// Encodes the key as an OpenSSH public key line, ending in `comment` unless it is empty.
+    pub fn to_openssh(&self, comment: &str) -> String {
+        let mut blob = Vec::new();
+        put_string(&mut blob, KEY_TYPE.as_bytes());
+        put_mpint(&mut blob, &BigUint::from(self.e));
+        put_mpint(&mut blob, &self.n);
+
+        let line = format!("{KEY_TYPE} {}", base64_encode(&blob));
+        if comment.is_empty() {
+            line
+        } else {
+            format!("{line} {comment}")
+        }
+    }
+
+    /// Decodes an OpenSSH public key line, as written by [`PublicKey::to_openssh`] or `ssh-keygen`.
+    /// The comment is not kept.
+    ///
+    /// # Errors
+    /// Gives [`Error::Encoding`] when `line` is not an `ssh-rsa` key, when the key type in the line and
+    /// blob differ, or when the blob is malformed, including mpints with needless leading zeros.
+    pub fn from_openssh(line: &str) -> Result<PublicKey,Error> {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some(KEY_TYPE) => (),
+            Some(key_type) => return Err(Error::Encoding(format!("key type is `{key_type}`, not `{KEY_TYPE}`"))),
+            None => return Err(malformed("empty line")),
+        }
+        let blob = base64_decode(parts.next().ok_or_else(|| malformed("missing key"))?)?;
+
+        let mut reader = blob.as_slice();
+        if read_string(&mut reader)? != KEY_TYPE.as_bytes() {
+            return Err(malformed("key type of the blob is not `ssh-rsa`"))
+        }
+        let e = read_mpint(&mut reader)?.to_u64().ok_or_else(|| malformed("public exponent does not fit in 64 bits"))?;
+        let n = read_mpint(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(malformed("trailing data"))
+        }
+        Ok(PublicKey { n, e })
+    }
+}
+
+fn put_string(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Writes `x` as a two's complement mpint, which is empty for zero and has a leading zero byte
+/// when the top bit would otherwise make it negative.
+fn put_mpint(out: &mut Vec<u8>, x: &BigUint) {
+    if x.is_zero() {
+        return put_string(out, &[])
+    }
+    let mut bytes = x.to_bytes_be();
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    put_string(out, &bytes)
+}
+
+fn read_string<'a>(reader: &mut &'a [u8]) -> Result<&'a [u8],Error> {
+    let (length, rest) = reader.split_first_chunk::<4>().ok_or_else(|| malformed("missing length"))?;
+    let length = u32::from_be_bytes(*length) as usize;
+    if rest.len() < length {
+        return Err(malformed("length runs past the end of the key"))
+    }
+    let (string, rest) = rest.split_at(length);
+    *reader = rest;
+    Ok(string)
+}
+
+fn read_mpint(reader: &mut &[u8]) -> Result<BigUint,Error> {
+    let bytes = read_string(reader)?;
+    match bytes {
+        [] => Ok(BigUint::zero()),
+        [byte, ..] if byte & 0x80 != 0 => Err(malformed("negative mpint")),
+        [0] | [0, 0..=0x7f, ..] => Err(malformed("mpint has a needless leading zero")),
+        _ => Ok(BigUint::from_bytes_be(bytes)),
+    }
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::Encoding(format!("bad OpenSSH key: {reason}"))
+}
@@ -0,0 +1,187 @@
+//! Lists of revoked keys, signed by the key that issued them, so peers can stop trusting a key before
+//! its [`Attestation`](super::attestation::Attestation) expires.
+//!
+//! A [`RevocationList`] is changed with [`RevocationList::revoke`], which drops its signature, and signed
+//! again with [`RevocationList::sign`]. The signature covers a canonical encoding of the list, in which
+//! the revocations are ordered by [`KeyId`]:
+//!
+//! | bytes | field |
+//! | --- | --- |
+//! | 4 | the magic tag `RNRL` |
+//! | 1 | version, currently 1 |
+//! | 16 | the [`KeyId`] of the issuer |
+//! | 4 | the number of revocations as a big endian [`u32`] |
+//! | 29 each | the revoked [`KeyId`], the [`RevocationReason`] code, and the time of revoking as seconds and nanoseconds since the Unix epoch, big endian |
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, KeyId, authenticity::Verification, revocation::{RevocationList, RevocationReason}};
+//!
+//! # fn main() -> Result<(),String> {
+//! let issuer = keygen(512)?;
+//! let (lost,_) = keygen(512)?.split();
+//! let mut list = RevocationList::new(issuer.public());
+//! list.revoke(KeyId::of(&lost), RevocationReason::KeyCompromise);
+//! list.sign(&issuer)?;
+//!
+//! assert!(matches!(list.verify(issuer.public()), Verification::Accept));
+//! assert!(list.is_revoked(KeyId::of(&lost)));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+use super::{authenticity::{self, Signature, Verification}, Error, KeyId, KeyPair, PublicKey};
+
+const MAGIC: &[u8; 4] = b"RNRL";
+const VERSION: u8 = 1;
+
+/// Why a key was revoked, with the codes of the X.509 `CRLReason`s of the same name.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Serialize,Deserialize)]
+pub enum RevocationReason {
+    Unspecified,
+    /// The secret key has, or may have, been seen by someone else.
+    KeyCompromise,
+    /// The key has been replaced by a new one.
+    Superseded,
+    /// The key is no longer used.
+    CessationOfOperation,
+}
+
+impl RevocationReason {
+    fn code(self) -> u8 {
+        match self {
+            RevocationReason::Unspecified => 0,
+            RevocationReason::KeyCompromise => 1,
+            RevocationReason::Superseded => 4,
+            RevocationReason::CessationOfOperation => 5,
+        }
+    }
+}
+
+/// That the key with id `key` was revoked at `revoked_at` for `reason`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Serialize,Deserialize)]
+pub struct Revocation {
+    pub key: KeyId,
+    pub reason: RevocationReason,
+    /// Times before the Unix epoch are signed as the epoch.
+    pub revoked_at: SystemTime,
+}
+
+/// Revoked keys, ordered by [`KeyId`], and the signature of the key with id `issuer` over them.
+///
+/// Deserializing rejects lists whose revocations are out of order or name a key twice, as [`RevocationList::revoke`]
+/// never makes them, but doesn't check the signature, see [`RevocationList::verify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize,Deserialize)]
+#[serde(try_from = "RevocationListFields")]
+pub struct RevocationList {
+    issuer: KeyId,
+    revocations: Vec<Revocation>,
+    signature: Option<Signature>,
+}
+
+impl RevocationList {
+    /// An empty, unsigned list to be issued by `issuer`.
+    pub fn new(issuer: &PublicKey) -> RevocationList {
+        RevocationList { issuer: KeyId::of(issuer), revocations: Vec::new(), signature: None }
+    }
+
+    pub fn issuer(&self) -> KeyId {
+        self.issuer
+    }
+
+    /// The revocations, ordered by [`KeyId`].
+    pub fn revocations(&self) -> &[Revocation] {
+        &self.revocations
+    }
+
+    /// Revokes the key with id `key` from now on for `reason`, which leaves the list unsigned until the
+    /// next [`RevocationList::sign`]. A key that is already revoked keeps its first revocation.
+    pub fn revoke(&mut self, key: KeyId, reason: RevocationReason) {
+        if let Err(index) = self.search(key) {
+            self.revocations.insert(index, Revocation { key, reason, revoked_at: SystemTime::now() });
+            self.signature = None;
+        }
+    }
+
+    /// Whether the key with id `key` is on the list, whether or not the list is signed.
+    pub fn is_revoked(&self, key: KeyId) -> bool {
+        self.revocation(key).is_some()
+    }
+
+    /// The revocation of the key with id `key`, if it is on the list.
+    pub fn revocation(&self, key: KeyId) -> Option<&Revocation> {
+        self.search(key).ok().map(|index| &self.revocations[index])
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signature.is_some()
+    }
+
+    /// Signs the list with the secret key of `issuer`.
+    ///
+    /// # Errors
+    /// Gives [`Error::InvalidKey`] when `issuer` is not the issuer of the list, and [`Error::MessageTooLong`]
+    /// when its key is too small for a SHA-256 digest, see [`authenticity::sign`].
+    pub fn sign(&mut self, issuer: &KeyPair) -> Result<(),Error> {
+        if KeyId::of(issuer.public()) != self.issuer {
+            return Err(Error::InvalidKey(format!("the list is issued by {}, not {}", self.issuer, KeyId::of(issuer.public()))))
+        }
+        self.signature = Some(issuer.sign(self.signed_bytes())?);
+        Ok(())
+    }
+
+    /// Checks that the whole list is signed by `issuer`, rejecting unsigned lists.
+    pub fn verify(&self, issuer: &PublicKey) -> Verification {
+        match &self.signature {
+            Some(signature) if KeyId::of(issuer) == self.issuer =>
+                authenticity::verify(self.signed_bytes(), signature.clone(), issuer.clone()),
+            _ => Verification::Reject,
+        }
+    }
+
+    fn search(&self, key: KeyId) -> Result<usize,usize> {
+        self.revocations.binary_search_by_key(&key, |revocation| revocation.key)
+    }
+
+    /// The canonical encoding of the list that is signed, see the [module](self) docs.
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        out.extend_from_slice(self.issuer.as_bytes());
+        out.extend_from_slice(&u32::try_from(self.revocations.len()).expect("lists have fewer than 2^32 revocations").to_be_bytes());
+        for revocation in &self.revocations {
+            let revoked_at = revocation.revoked_at.duration_since(UNIX_EPOCH).unwrap_or_default();
+            out.extend_from_slice(revocation.key.as_bytes());
+            out.push(revocation.reason.code());
+            out.extend_from_slice(&revoked_at.as_secs().to_be_bytes());
+            out.extend_from_slice(&revoked_at.subsec_nanos().to_be_bytes());
+        }
+        out
+    }
+}
+
+#[derive(Deserialize)]
+struct RevocationListFields {
+    issuer: KeyId,
+    revocations: Vec<Revocation>,
+    signature: Option<Signature>,
+}
+
+/// Rejects revocations that are out of order or repeated, since lookups search them in order.
+impl TryFrom<RevocationListFields> for RevocationList {
+    type Error = Error;
+
+    fn try_from(fields: RevocationListFields) -> Result<Self,Error> {
+        if !fields.revocations.windows(2).all(|pair| pair[0].key < pair[1].key) {
+            return Err(Error::Encoding("revocations are not in order of key id, or repeat a key".into()))
+        }
+        Ok(RevocationList { issuer: fields.issuer, revocations: fields.revocations, signature: fields.signature })
+    }
+}
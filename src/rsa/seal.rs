@@ -0,0 +1,227 @@
+//! Passphrase-protected export of secret keys, for keeping them on disk.
+//!
+//! [`SecretKey::seal`] derives a key from the passphrase with PBKDF2-HMAC-SHA256[^pbkdf2] and a
+//! random salt, and encrypts the bincode serialized key with HMAC-SHA256 in counter mode, followed
+//! by an HMAC-SHA256 tag over everything before it (encrypt-then-MAC). The sealed bytes are
+//!
+//! | bytes | field |
+//! | --- | --- |
+//! | 1 | version, currently 1 |
+//! | 4 | PBKDF2 iterations, big endian |
+//! | 16 | salt |
+//! | 32 | passphrase check |
+//! | .. | ciphertext |
+//! | 32 | tag |
+//!
+//! The passphrase check is an HMAC of the derived key, which tells a wrong passphrase apart from
+//! corrupted bytes. It gives an attacker nothing the tag doesn't, since both take a full PBKDF2
+//! per guess.
+//!
+//! # Examples
+//! ```rust
+//! use rustnetworking::rsa::{keygen, SecretKey};
+//!
+//! # fn main() -> Result<(),String> {
+//! let (_,sk) = keygen(512)?.split();
+//! let sealed = sk.seal("correct horse battery staple");
+//! let opened = SecretKey::open(&sealed, "correct horse battery staple")?;
+//! assert_eq!(sk, opened);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [^pbkdf2]: `https://www.rfc-editor.org/rfc/rfc8018#section-5.2`
+
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Sha256, Digest};
+
+use super::{Error, SecretKey};
+
+const VERSION: u8 = 1;
+
+/// PBKDF2 iterations of newly sealed keys, as OWASP recommends for PBKDF2-HMAC-SHA256.
+pub const SEAL_ITERATIONS: u32 = 600_000;
+
+/// The most iterations [`SecretKey::open`] will run, so that a crafted header can't make it spin.
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LENGTH: usize = 16;
+const BLOCK_LENGTH: usize = 64;
+const HASH_LENGTH: usize = 32;
+const HEADER_LENGTH: usize = 1 + 4 + SALT_LENGTH + HASH_LENGTH;
+
+impl SecretKey {
+    /// Encrypts the key under `passphrase`, see the [module](self) for the format.
+    pub fn seal(&self, passphrase: &str) -> Vec<u8> {
+        let mut salt = [0; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let keys = Keys::derive(passphrase, &salt, SEAL_ITERATIONS);
+
+        let mut sealed = Vec::with_capacity(HEADER_LENGTH);
+        sealed.push(VERSION);
+        sealed.extend_from_slice(&SEAL_ITERATIONS.to_be_bytes());
+        sealed.extend_from_slice(&salt);
+        sealed.extend_from_slice(&keys.check);
+
+        let mut plaintext = bincode::serialize(self).expect("secret keys serialize");
+        sealed.extend(apply_keystream(&keys.encryption, &plaintext));
+        wipe(&mut plaintext);
+
+        let tag = hmac(&keys.authentication, &[&sealed]);
+        sealed.extend_from_slice(&tag);
+        sealed
+    }
+
+    /// Decrypts a key sealed by [`SecretKey::seal`] with the same `passphrase`.
+    ///
+    /// # Errors
+    /// Gives [`Error::WrongPassphrase`] when `passphrase` is not the one the key was sealed with,
+    /// and [`Error::Encoding`] when `sealed` is truncated, has an unknown version or too many
+    /// iterations, or has been changed since it was sealed.
+    pub fn open(sealed: &[u8], passphrase: &str) -> Result<SecretKey,Error> {
+        let malformed = |reason: &str| Error::Encoding(format!("bad sealed key: {reason}"));
+        if sealed.len() < HEADER_LENGTH + HASH_LENGTH {
+            return Err(malformed("too short"))
+        }
+        let (header, rest) = sealed.split_at(HEADER_LENGTH);
+        let (ciphertext, tag) = rest.split_at(rest.len() - HASH_LENGTH);
+
+        if header[0] != VERSION {
+            return Err(malformed(&format!("unknown version {}", header[0])))
+        }
+        let iterations = u32::from_be_bytes(header[1..5].try_into().expect("four bytes"));
+        if iterations == 0 || iterations > MAX_ITERATIONS {
+            return Err(malformed(&format!("{iterations} iterations is out of range")))
+        }
+        let salt = &header[5..5 + SALT_LENGTH];
+        let check = &header[5 + SALT_LENGTH..];
+
+        let keys = Keys::derive(passphrase, salt, iterations);
+        if !constant_time_eq(&keys.check, check) {
+            return Err(Error::WrongPassphrase)
+        }
+        if !constant_time_eq(&hmac(&keys.authentication, &[header, ciphertext]), tag) {
+            return Err(malformed("tag does not match, the key has been changed"))
+        }
+
+        let mut plaintext = apply_keystream(&keys.encryption, ciphertext);
+        let sk = bincode::deserialize(&plaintext).map_err(|err| malformed(&err.to_string()));
+        wipe(&mut plaintext);
+        sk
+    }
+}
+
+/// The keys derived from a passphrase, each an HMAC of the PBKDF2 output with its own label.
+struct Keys {
+    encryption: [u8; HASH_LENGTH],
+    authentication: [u8; HASH_LENGTH],
+    check: [u8; HASH_LENGTH],
+}
+
+impl Keys {
+    fn derive(passphrase: &str, salt: &[u8], iterations: u32) -> Keys {
+        let mut master = pbkdf2(passphrase.as_bytes(), salt, iterations);
+        let keys = Keys {
+            encryption: hmac(&master, &[b"encryption"]),
+            authentication: hmac(&master, &[b"authentication"]),
+            check: hmac(&master, &[b"passphrase check"]),
+        };
+        wipe(&mut master);
+        keys
+    }
+}
+
+impl Drop for Keys {
+    fn drop(&mut self) {
+        wipe(&mut self.encryption);
+        wipe(&mut self.authentication);
+    }
+}
+
+/// The first block of PBKDF2 with HMAC-SHA256, which is all the key material needed.
+///
+/// Every iteration after the first MACs only the 32 byte output of the one before, which fits
+/// in a single padded block, so those run on the SHA-256 compression function directly. That
+/// keeps sealing fast in unoptimized builds, where `sha2`'s generic hashing is slow.
+fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32) -> [u8; HASH_LENGTH] {
+    let mut key = key_block(password);
+    let inner = compressed(SHA256_IV, key.map(|b| b ^ 0x36));
+    let outer = compressed(SHA256_IV, key.map(|b| b ^ 0x5c));
+    wipe(&mut key);
+
+    // A 32 byte message after the 64 byte padded key, padded to one block.
+    let mut block = [0; BLOCK_LENGTH];
+    block[HASH_LENGTH] = 0x80;
+    block[BLOCK_LENGTH - 8..].copy_from_slice(&((BLOCK_LENGTH + HASH_LENGTH) as u64 * 8).to_be_bytes());
+
+    let mut u = hmac(password, &[salt, &1u32.to_be_bytes()]);
+    let mut out = u;
+    for _ in 1..iterations {
+        block[..HASH_LENGTH].copy_from_slice(&u);
+        let inner_hash = state_bytes(compressed(inner, block));
+        block[..HASH_LENGTH].copy_from_slice(&inner_hash);
+        u = state_bytes(compressed(outer, block));
+        out.iter_mut().zip(u).for_each(|(o, x)| *o ^= x);
+    }
+    wipe(&mut block);
+    out
+}
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+fn compressed(mut state: [u32; 8], block: [u8; BLOCK_LENGTH]) -> [u32; 8] {
+    sha2::compress256(&mut state, &[block.into()]);
+    state
+}
+
+fn state_bytes(state: [u32; 8]) -> [u8; HASH_LENGTH] {
+    let mut bytes = [0; HASH_LENGTH];
+    bytes.chunks_mut(4).zip(state).for_each(|(chunk, word)| chunk.copy_from_slice(&word.to_be_bytes()));
+    bytes
+}
+
+/// HMAC-SHA256[^note] of the concatenation of `parts`.
+///
+/// [^note]: `https://www.rfc-editor.org/rfc/rfc2104`
+fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8; HASH_LENGTH] {
+    let mut key = key_block(key);
+    let mut inner = Sha256::new_with_prefix(key.map(|b| b ^ 0x36));
+    parts.iter().for_each(|part| inner.update(part));
+    let mut outer = Sha256::new_with_prefix(key.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    wipe(&mut key);
+    outer.finalize().into()
+}
+
+/// The HMAC key padded to a block, hashing it first when it is longer than one.
+fn key_block(key: &[u8]) -> [u8; BLOCK_LENGTH] {
+    let mut block = [0; BLOCK_LENGTH];
+    if key.len() > BLOCK_LENGTH {
+        block[..HASH_LENGTH].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    block
+}
+
+/// XORs `bytes` with the keystream `HMAC(key, 0) || HMAC(key, 1) || ..` of 64 bit big endian counters.
+/// Every seal has a fresh salt and so a fresh key, which is why no nonce is needed.
+fn apply_keystream(key: &[u8; HASH_LENGTH], bytes: &[u8]) -> Vec<u8> {
+    bytes.chunks(HASH_LENGTH).zip(0u64..)
+        .flat_map(|(chunk, counter)| {
+            let stream = hmac(key, &[&counter.to_be_bytes()]);
+            chunk.iter().zip(stream).map(|(b, s)| b ^ s).collect::<Vec<u8>>()
+        })
+        .collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn wipe(bytes: &mut [u8]) {
+    bytes.fill(0);
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
@@ -0,0 +1,1433 @@
+use std::{str::FromStr, collections::{BTreeSet, HashMap}};
+
+use rand::Rng;
+use rustnetworking::{modular, big_num::{BigUint, BigInt, Sign, Digit, ct_eq_bytes, new_prime_in_range, new_strong_prime_in_range, new_safe_prime}};
+
+fn random_biguint(bytes: usize) -> BigUint {
+    let mut rng = rand::thread_rng();
+    let bytes: Vec<u8> = (0..bytes).map(|_| rng.gen()).collect();
+    bytes.into()
+}
+
+#[test]
+fn parse_gives_value_of_decimal_string() -> Result<(),String> {
+    let x = BigUint::parse("1234567890123456789")?;
+    let expected = BigUint::from_i32(1234567890)? * BigUint::from_i32(1000000000)? + BigUint::from_i32(123456789)?;
+
+    assert_eq!(expected, x);
+    Ok(())
+}
+
+#[test]
+fn parse_rejects_empty_string() {
+    assert!(BigUint::parse("").is_err());
+}
+
+#[test]
+fn parse_rejects_non_digit_characters() {
+    assert!(BigUint::parse("12a4").is_err());
+    assert!(BigUint::parse("-12").is_err());
+    assert!(BigUint::parse("+12").is_err());
+    assert!(BigUint::parse(" 12").is_err());
+}
+
+#[test]
+fn from_str_round_trips_with_display() -> Result<(),String> {
+    for size in [1, 8, 64, 256] {
+        let x = random_biguint(size);
+        assert_eq!(x, BigUint::from_str(&x.to_string())?);
+    }
+    Ok(())
+}
+
+#[test]
+fn from_hex_accepts_prefix_and_both_cases() -> Result<(),String> {
+    let expected = BigUint::from_i32(0xbeef)?;
+
+    assert_eq!(expected, BigUint::from_hex("beef")?);
+    assert_eq!(expected, BigUint::from_hex("BEEF")?);
+    assert_eq!(expected, BigUint::from_hex("0xBeEf")?);
+    assert_eq!(expected, BigUint::from_hex("0X00beef")?);
+    Ok(())
+}
+
+#[test]
+fn from_hex_rejects_invalid_input() {
+    assert!(BigUint::from_hex("").is_err());
+    assert!(BigUint::from_hex("0x").is_err());
+    assert!(BigUint::from_hex("12g4").is_err());
+    assert!(BigUint::from_hex("0x 12").is_err());
+}
+
+#[test]
+fn hex_formatting_matches_primitive_formatting() -> Result<(),String> {
+    let x = BigUint::from_i32(0x0abc)?;
+
+    assert_eq!("abc", format!("{x:x}"));
+    assert_eq!("ABC", format!("{x:X}"));
+    assert_eq!("0xabc", format!("{x:#x}"));
+    assert_eq!("0abc", x.to_hex());
+    Ok(())
+}
+
+#[test]
+fn to_hex_round_trips_consistently_with_bytes() -> Result<(),String> {
+    for size in [1, 8, 64, 256] {
+        let x = random_biguint(size);
+        let hex = x.to_hex();
+
+        assert_eq!(2 * x.to_bytes_be().len(), hex.len());
+        assert_eq!(x, BigUint::from_hex(&hex)?);
+        assert_eq!(x, BigUint::from_hex(&format!("{x:X}"))?);
+    }
+    Ok(())
+}
+
+#[test]
+fn from_bytes_le_round_trips_with_to_bytes_le() {
+    for size in [1, 8, 64, 256] {
+        let x = random_biguint(size);
+        assert_eq!(x, BigUint::from_bytes_le(&x.to_bytes_le()));
+        assert_eq!(x, BigUint::from_le(x.to_bytes_le()));
+    }
+}
+
+#[test]
+fn little_endian_bytes_are_reversed_big_endian_bytes() {
+    for size in [1, 8, 64, 256] {
+        let x = random_biguint(size);
+        let mut reversed = x.to_bytes_be();
+        reversed.reverse();
+
+        assert_eq!(reversed, x.to_bytes_le());
+        assert_eq!(BigUint::from_bytes_be(&reversed), BigUint::from_bytes_le(&x.to_bytes_be()));
+    }
+}
+
+#[test]
+fn from_bytes_le_ignores_trailing_zeros() {
+    assert_eq!(BigUint::from_bytes_le(&[1, 2]), BigUint::from_bytes_le(&[1, 2, 0, 0]));
+    assert_eq!(vec![1, 2], BigUint::from_bytes_le(&[1, 2, 0, 0]).to_bytes_le());
+}
+
+#[test]
+fn checked_sub_of_value_from_itself_is_zero() -> Result<(),String> {
+    let a = random_biguint(32);
+
+    assert_eq!(Some(BigUint::from_i32(0)?), a.checked_sub(&a));
+    assert_eq!(BigUint::from_i32(0)?, a.saturating_sub(&a));
+    Ok(())
+}
+
+#[test]
+fn checked_sub_from_zero_underflows() -> Result<(),String> {
+    let zero = BigUint::from_i32(0)?;
+    let x = BigUint::from_i32(7)?;
+
+    assert_eq!(None, zero.checked_sub(&x));
+    assert_eq!(zero, zero.saturating_sub(&x));
+    Ok(())
+}
+
+#[test]
+fn checked_sub_agrees_with_sub_on_2048_bit_values() -> Result<(),String> {
+    let a = random_biguint(256);
+    let b = random_biguint(256);
+    let (larger, smaller) = if a > b { (a, b) } else { (b, a) };
+
+    assert_eq!(Some((larger.clone() - smaller.clone())?), larger.checked_sub(&smaller));
+    assert_eq!(None, smaller.checked_sub(&larger));
+    Ok(())
+}
+
+#[test]
+fn sorting_matches_numeric_order_of_decimal_strings() {
+    let mut values: Vec<BigUint> = (1..20).map(|size| random_biguint(size % 7 + 1)).collect();
+    let mut strings: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+
+    values.sort();
+    strings.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+
+    let sorted_strings: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+    assert_eq!(strings, sorted_strings);
+}
+
+#[test]
+fn biguint_can_be_used_in_sets_and_maps() -> Result<(),String> {
+    let a = BigUint::parse("123456789012345678901234567890")?;
+    let b = BigUint::parse("987654321098765432109876543210")?;
+
+    let set: BTreeSet<BigUint> = [b.clone(), a.clone(), a.clone()].into_iter().collect();
+    assert_eq!(vec![&a, &b], set.iter().collect::<Vec<_>>());
+
+    let mut map = HashMap::new();
+    map.insert(a.clone(), "a");
+    map.insert(b.clone(), "b");
+    assert_eq!(Some(&"a"), map.get(&BigUint::parse("123456789012345678901234567890")?));
+
+    assert_eq!(Some(&b), [a.clone(), b.clone()].iter().max());
+    Ok(())
+}
+
+#[test]
+fn reference_operators_agree_with_owned_operators() -> Result<(),String> {
+    let a = random_biguint(64);
+    let b = random_biguint(32) + BigUint::from_i32(1)?;
+
+    assert_eq!(a.clone() + b.clone(), &a + &b);
+    assert_eq!(a.clone() * b.clone(), &a * &b);
+    assert_eq!(a.clone() / b.clone(), &a / &b);
+    assert_eq!(a.clone() % b.clone(), &a % &b);
+    assert_eq!((a.clone() - b.clone())?, (&a - &b)?);
+    assert!((&b - &a).is_err());
+
+    assert_eq!(&a + &b, a.clone() + &b);
+    assert_eq!(&a * &b, &a * b.clone());
+    Ok(())
+}
+
+#[test]
+fn assign_operators_agree_with_binary_operators() -> Result<(),String> {
+    let a = random_biguint(64);
+    let b = random_biguint(32) + BigUint::from_i32(1)?;
+
+    let mut x = a.clone();
+    x += &b;
+    assert_eq!(&a + &b, x);
+    x -= b.clone();
+    assert_eq!(a, x);
+    x *= &b;
+    assert_eq!(&a * &b, x);
+    x /= b.clone();
+    assert_eq!(a, x);
+    x %= &b;
+    assert_eq!(&a % &b, x);
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn sub_assign_panics_on_underflow() {
+    let mut x = BigUint::new(vec![1]);
+    x -= BigUint::new(vec![2]);
+}
+
+#[test]
+fn bigint_assign_operators_agree_with_binary_operators() {
+    let a = BigInt::new(Sign::Minus, vec![123456789, 42]);
+    let b = BigInt::new(Sign::Plus, vec![987654321]);
+
+    let mut x = a.clone();
+    x += &b;
+    assert_eq!(&a + &b, x);
+    x -= b.clone();
+    assert_eq!(a, x);
+    x *= &b;
+    assert_eq!(&a * &b, x);
+    x /= b.clone();
+    assert_eq!(a, x);
+
+    let mut y = BigInt::new(Sign::Plus, vec![17]);
+    y %= BigInt::new(Sign::Plus, vec![5]);
+    assert_eq!(BigInt::new(Sign::Plus, vec![2]), y);
+}
+
+#[test]
+fn shifting_left_grows_bit_size() -> Result<(),String> {
+    let x = random_biguint(64) + BigUint::from_i32(1)?;
+
+    assert_eq!(x.bit_size() + 8, (&x << 8u32).bit_size());
+    assert_eq!(x.bit_size() + 1000, (x.clone() << 1000usize).bit_size());
+    assert_eq!(&x * &BigUint::from_i32(256)?, x << 8u32);
+    Ok(())
+}
+
+#[test]
+fn shifting_right_past_bit_size_gives_zero() -> Result<(),String> {
+    let x = random_biguint(64);
+
+    assert_eq!(BigUint::from_i32(0)?, &x >> (x.bit_size() + 1));
+    assert_eq!(BigUint::from_i32(0)?, x >> 10_000usize);
+    Ok(())
+}
+
+#[test]
+fn shift_assign_agrees_with_shift() {
+    let x = random_biguint(64);
+
+    let mut y = x.clone();
+    y <<= 13u32;
+    assert_eq!(&x << 13u32, y);
+    y >>= 13usize;
+    assert_eq!(x, y);
+}
+
+fn bytewise(a: &BigUint, b: &BigUint, op: fn(u8, u8) -> u8) -> BigUint {
+    let (a, b) = (a.to_bytes_le(), b.to_bytes_le());
+    let bytes: Vec<u8> = (0..a.len().max(b.len()))
+        .map(|i| op(*a.get(i).unwrap_or(&0), *b.get(i).unwrap_or(&0)))
+        .collect();
+    BigUint::from_le(bytes)
+}
+
+#[test]
+fn bitwise_operators_agree_with_bytewise_operations() {
+    for (a_size, b_size) in [(32, 32), (64, 8), (3, 200)] {
+        let a = random_biguint(a_size);
+        let b = random_biguint(b_size);
+
+        assert_eq!(bytewise(&a, &b, |x, y| x & y), &a & &b);
+        assert_eq!(bytewise(&a, &b, |x, y| x | y), &a | &b);
+        assert_eq!(bytewise(&a, &b, |x, y| x ^ y), &a ^ &b);
+        assert_eq!(&a ^ &b, a.clone() ^ b.clone());
+        assert_eq!(&a & &b, a.clone() & &b);
+    }
+}
+
+#[test]
+fn xor_with_itself_is_zero() -> Result<(),String> {
+    let a = random_biguint(128);
+
+    assert_eq!(BigUint::from_i32(0)?, &a ^ &a);
+    Ok(())
+}
+
+#[test]
+fn pow_of_zero_is_one_and_pow_of_one_is_identity() -> Result<(),String> {
+    let x = random_biguint(32);
+
+    assert_eq!(BigUint::from_i32(1)?, x.pow(0));
+    assert_eq!(x, x.pow(1));
+    assert_eq!(BigUint::from_i32(1)?, BigUint::from_i32(0)?.pow(0));
+    Ok(())
+}
+
+#[test]
+fn pow_agrees_with_modpow_under_huge_modulus() -> Result<(),String> {
+    let x = random_biguint(16);
+    let huge = BigUint::two_pow(65536);
+
+    for exp in [2, 3, 17, 100, 255] {
+        assert_eq!(x.modpow(&BigUint::from_i32(exp)?, &huge), x.pow(exp as u32));
+    }
+    Ok(())
+}
+
+#[test]
+fn two_pow_agrees_with_pow() -> Result<(),String> {
+    let two = BigUint::from_i32(2)?;
+
+    for k in [0, 1, 31, 32, 33, 1024] {
+        assert_eq!(two.pow(k), BigUint::two_pow(k));
+        assert_eq!(k + 1, BigUint::two_pow(k).bit_size());
+    }
+    Ok(())
+}
+
+#[test]
+fn sqrt_and_nth_root_handle_zero_and_one() -> Result<(),String> {
+    let zero = BigUint::from_i32(0)?;
+    let one = BigUint::from_i32(1)?;
+
+    assert_eq!(zero, zero.sqrt());
+    assert_eq!(one, one.sqrt());
+    assert_eq!(zero, zero.nth_root(5));
+    assert_eq!(one, one.nth_root(5));
+    Ok(())
+}
+
+#[test]
+fn nth_root_is_floor_root() -> Result<(),String> {
+    let one = BigUint::from_i32(1)?;
+
+    for n in [1, 2, 3, 5, 17] {
+        for size in [1, 8, 64, 256] {
+            let x = random_biguint(size);
+            let r = x.nth_root(n);
+
+            assert!(r.pow(n) <= x);
+            assert!((&r + &one).pow(n) > x);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn nth_root_inverts_pow() -> Result<(),String> {
+    let m = random_biguint(64);
+
+    assert_eq!(m, m.pow(2).sqrt());
+    assert_eq!(m, m.pow(3).nth_root(3));
+    assert_eq!(BigUint::from_i32(3)?, BigUint::from_i32(15)?.sqrt());
+    Ok(())
+}
+
+#[test]
+fn from_unsigned_primitives_gives_expected_bytes() {
+    assert_eq!(vec![1, 0, 1], BigUint::from(65537u32).to_bytes_be());
+    assert_eq!(vec![0xff], BigUint::from(255u8).to_bytes_be());
+    assert_eq!(vec![1, 0], BigUint::from(256u16).to_bytes_be());
+    assert_eq!(vec![0xff; 8], BigUint::from(u64::MAX).to_bytes_be());
+    assert_eq!(vec![0xff; 16], BigUint::from(u128::MAX).to_bytes_be());
+    assert_eq!(BigUint::from(65537u32), BigUint::from(65537usize));
+}
+
+#[test]
+fn try_from_signed_primitives_rejects_only_negatives() -> Result<(),String> {
+    assert_eq!(BigUint::from(3u32), BigUint::try_from(3i32)?);
+    assert_eq!(BigUint::from(0u32), BigUint::try_from(0i64)?);
+    assert_eq!(BigUint::from(i64::MAX as u64), BigUint::try_from(i64::MAX)?);
+    assert!(BigUint::try_from(-1i32).is_err());
+    assert!(BigUint::try_from(i64::MIN).is_err());
+    Ok(())
+}
+
+#[test]
+fn to_u64_fits_exactly_u64_max() -> Result<(),String> {
+    let max = BigUint::from(u64::MAX);
+    let max_plus_one = &max + &BigUint::from(1u32);
+
+    assert_eq!(Some(u64::MAX), max.to_u64());
+    assert_eq!(None, max_plus_one.to_u64());
+    assert_eq!(u64::MAX, u64::try_from(max)?);
+    assert!(u64::try_from(max_plus_one.clone()).is_err());
+    assert_eq!(Some(u64::MAX as u128 + 1), max_plus_one.to_u128());
+    Ok(())
+}
+
+#[test]
+fn to_u32_and_to_u128_respect_bounds() -> Result<(),String> {
+    assert_eq!(Some(65537), BigUint::from(65537u32).to_u32());
+    assert_eq!(None, BigUint::from(u32::MAX as u64 + 1).to_u32());
+    assert_eq!(Some(u128::MAX), BigUint::from(u128::MAX).to_u128());
+    assert_eq!(None, BigUint::two_pow(128).to_u128());
+    assert_eq!(3, u32::try_from(BigUint::from(3u8))?);
+    assert!(u128::try_from(BigUint::two_pow(128)).is_err());
+    Ok(())
+}
+
+#[test]
+fn random_below_never_reaches_the_bound() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+
+    for bound in [BigUint::from(1u32), BigUint::from(2u32), BigUint::from(1000u32), BigUint::two_pow(64), random_biguint(128) + BigUint::from(1u32)] {
+        for _ in 0..200 {
+            assert!(BigUint::random_below(&bound, &mut rng) < bound);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn random_below_covers_small_ranges() {
+    let mut rng = rand::thread_rng();
+    let bound = BigUint::from(5u32);
+
+    let seen: BTreeSet<BigUint> = (0..500).map(|_| BigUint::random_below(&bound, &mut rng)).collect();
+    assert_eq!(5, seen.len());
+}
+
+#[test]
+fn random_bits_sets_the_high_bit() {
+    let mut rng = rand::thread_rng();
+
+    for bits in [1, 7, 8, 9, 64, 1000, 2048] {
+        for _ in 0..20 {
+            assert_eq!(bits, BigUint::random_bits(bits, &mut rng).bit_size());
+        }
+    }
+    assert_eq!(0, BigUint::random_bits(0, &mut rng).bit_size());
+}
+
+fn square_and_multiply_modpow(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+    let mut result = BigUint::from(1u32) % modulus.clone();
+    for bit in (0..exponent.bit_size()).rev() {
+        result = &(&result * &result) % modulus;
+        if (exponent >> bit).is_odd() {
+            result = &(&result * base) % modulus;
+        }
+    }
+    result
+}
+
+#[test]
+fn modpow_agrees_with_square_and_multiply_for_odd_moduli() {
+    for (base_size, exponent_size, modulus_size) in [(1, 1, 1), (8, 8, 8), (64, 32, 64), (300, 64, 256), (3, 256, 256)] {
+        let base = random_biguint(base_size);
+        let exponent = random_biguint(exponent_size);
+        let modulus = random_biguint(modulus_size) | BigUint::from(1u32);
+
+        assert_eq!(square_and_multiply_modpow(&base, &exponent, &modulus), base.modpow(&exponent, &modulus));
+    }
+}
+
+#[test]
+fn modpow_agrees_with_square_and_multiply_for_even_moduli() {
+    let base = random_biguint(64);
+    let exponent = random_biguint(16);
+    let modulus = random_biguint(64) << 1u32;
+
+    assert_eq!(square_and_multiply_modpow(&base, &exponent, &modulus), base.modpow(&exponent, &modulus));
+}
+
+#[test]
+fn modpow_handles_trivial_exponents_and_moduli() {
+    let base = random_biguint(32);
+    let modulus = random_biguint(32) | BigUint::from(1u32);
+    let one = BigUint::from(1u32);
+    let zero = BigUint::from(0u32);
+
+    assert_eq!(one, base.modpow(&zero, &modulus));
+    assert_eq!(&base % &modulus, base.modpow(&one, &modulus));
+    assert_eq!(zero, base.modpow(&random_biguint(8), &one));
+    assert_eq!(zero, zero.modpow(&one, &modulus));
+}
+
+#[test]
+fn lcm_of_small_values() {
+    assert_eq!(BigUint::from(12u32), BigUint::from(4u32).lcm(&BigUint::from(6u32)));
+    assert_eq!(BigUint::from(7u32), BigUint::from(7u32).lcm(&BigUint::from(7u32)));
+    assert_eq!(BigUint::from(21u32), BigUint::from(1u32).lcm(&BigUint::from(21u32)));
+}
+
+#[test]
+fn lcm_with_zero_is_zero() {
+    let zero = BigUint::from(0u32);
+    let x = random_biguint(32);
+
+    assert_eq!(zero, zero.lcm(&x));
+    assert_eq!(zero, x.lcm(&zero));
+    assert_eq!(zero, zero.lcm(&zero));
+}
+
+#[test]
+fn lcm_of_large_coprime_values_is_product() {
+    let a = random_biguint(128);
+    let b = &a + &BigUint::from(1u32);
+
+    assert_eq!(&a * &b, a.lcm(&b));
+}
+
+fn assert_bezout_identity(a: &BigInt, b: &BigInt) {
+    let (gcd, x, y) = a.extended_gcd(b);
+
+    assert_eq!(gcd, &(a * &x) + &(b * &y));
+    assert_eq!(a.gcd(b), gcd);
+}
+
+#[test]
+fn extended_gcd_satisfies_bezout_identity_for_1024_bit_values() {
+    for _ in 0..10 {
+        let a = BigInt::from(random_biguint(128));
+        let b = BigInt::from(random_biguint(128));
+
+        assert_bezout_identity(&a, &b);
+    }
+}
+
+#[test]
+fn extended_gcd_satisfies_bezout_identity_for_negative_values() {
+    let a = BigInt::new(Sign::Minus, vec![240]);
+    let b = BigInt::new(Sign::Plus, vec![46]);
+
+    assert_bezout_identity(&a, &b);
+    assert_bezout_identity(&b, &a);
+    assert_eq!(BigInt::new(Sign::Plus, vec![2]), a.extended_gcd(&b).0);
+}
+
+#[test]
+fn extended_gcd_with_zero_argument() {
+    let zero = BigInt::new(Sign::Plus, vec![0]);
+    let one = BigInt::new(Sign::Plus, vec![1]);
+    let a = BigInt::from(random_biguint(32));
+
+    assert_eq!((a.clone(), one.clone(), zero.clone()), a.extended_gcd(&zero));
+    assert_eq!((a.clone(), zero.clone(), one.clone()), zero.extended_gcd(&a));
+    assert_eq!(zero.clone(), zero.extended_gcd(&zero).0);
+}
+
+#[test]
+fn parity_of_small_values() {
+    assert!(BigUint::from(0u32).is_even());
+    assert!(BigUint::from(1u32).is_odd());
+    assert!(BigUint::from(65537u32).is_odd());
+    assert!(BigUint::two_pow(2048).is_even());
+    assert!(!BigUint::two_pow(2048).is_odd());
+}
+
+#[test]
+fn trailing_zeros_of_known_values() {
+    assert_eq!(None, BigUint::from(0u32).trailing_zeros());
+    assert_eq!(Some(0), BigUint::from(1u32).trailing_zeros());
+    assert_eq!(Some(3), BigUint::from(40u32).trailing_zeros());
+    assert_eq!(Some(64), BigUint::from(1u128 << 64).trailing_zeros());
+    assert_eq!(Some(1000), (BigUint::from(3u32) << 1000u32).trailing_zeros());
+}
+
+#[test]
+fn trailing_zeros_is_bit_size_for_powers_of_two() {
+    for k in [0, 7, 8, 31, 32, 33, 2047] {
+        let x = BigUint::two_pow(k);
+        assert_eq!(Some(x.bit_size() as u64 - 1), x.trailing_zeros());
+    }
+}
+
+#[test]
+fn trailing_zeros_agrees_with_parity() {
+    for _ in 0..50 {
+        let x = random_biguint(16);
+        assert_eq!(x.is_odd(), x.trailing_zeros() == Some(0));
+    }
+}
+
+#[test]
+fn div_rem_agrees_with_div_and_rem() {
+    let a = random_biguint(256);
+    let b = random_biguint(100) | BigUint::from(1u32);
+
+    assert_eq!((&a / &b, &a % &b), a.div_rem(&b));
+    assert_eq!(Some(a.div_rem(&b)), a.checked_div_rem(&b));
+}
+
+#[test]
+fn checked_div_rem_by_zero_is_none() {
+    let a = random_biguint(32);
+
+    assert_eq!(None, a.checked_div_rem(&BigUint::from(0u32)));
+    assert_eq!(None, BigInt::from(a).checked_div_rem(&BigInt::new(Sign::Plus, vec![0])));
+}
+
+#[test]
+fn bigint_div_rem_truncates_towards_zero() {
+    let a = BigInt::new(Sign::Minus, vec![7]);
+    let b = BigInt::new(Sign::Plus, vec![2]);
+
+    assert_eq!((BigInt::new(Sign::Minus, vec![3]), BigInt::new(Sign::Minus, vec![1])), a.div_rem(&b));
+    assert_eq!(&a / &b, a.div_rem(&b).0);
+}
+
+#[test]
+fn str_radix_round_trips_across_radices() -> Result<(),String> {
+    for radix in [2, 3, 8, 10, 16, 32, 36] {
+        for size in [1, 8, 64] {
+            let x = random_biguint(size);
+            let s = x.to_str_radix(radix);
+
+            assert_eq!(x, BigUint::from_str_radix(&s, radix)?);
+            assert_eq!(x, BigUint::from_str_radix(&s.to_uppercase(), radix)?);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn str_radix_agrees_with_display_and_hex() -> Result<(),String> {
+    let x = random_biguint(64);
+
+    assert_eq!(x.to_string(), x.to_str_radix(10));
+    assert_eq!(format!("{x:x}"), x.to_str_radix(16));
+    assert_eq!(BigUint::from_hex(&x.to_hex())?, BigUint::from_str_radix(&x.to_str_radix(16), 16)?);
+    assert_eq!("11111111", BigUint::from(255u32).to_str_radix(2));
+    assert_eq!("z", BigUint::from(35u32).to_str_radix(36));
+    Ok(())
+}
+
+#[test]
+fn from_str_radix_names_offending_character_and_position() {
+    let err = BigUint::from_str_radix("1012", 2).unwrap_err();
+    assert!(err.contains("'2'"), "{err}");
+    assert!(err.contains("position 3"), "{err}");
+
+    assert!(BigUint::from_str_radix("10", 1).is_err());
+    assert!(BigUint::from_str_radix("10", 37).is_err());
+    assert!(BigUint::from_str_radix("", 10).is_err());
+}
+
+#[test]
+fn serde_uses_hex_for_json_and_bytes_for_bincode() -> Result<(),String> {
+    let x = BigUint::from(0xbeefu32);
+
+    assert_eq!("\"beef\"", serde_json::to_string(&x).map_err(|err| err.to_string())?);
+    assert_eq!(bincode::serialize(&vec![0xbeu8, 0xef]).map_err(|err| err.to_string())?, bincode::serialize(&x).map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+#[test]
+fn serde_round_trips_through_json_and_bincode() -> Result<(),String> {
+    let x = random_biguint(256);
+
+    let json = serde_json::to_string(&x).map_err(|err| err.to_string())?;
+    assert_eq!(x, serde_json::from_str::<BigUint>(&json).map_err(|err| err.to_string())?);
+
+    let bytes = bincode::serialize(&x).map_err(|err| err.to_string())?;
+    assert_eq!(x, bincode::deserialize::<BigUint>(&bytes).map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+#[test]
+fn bigint_serde_round_trips_negative_zero_and_positive_values() -> Result<(),String> {
+    let values = [
+        BigInt::new(Sign::Minus, vec![0xdeadbeef, 42]),
+        BigInt::new(Sign::NoSign, vec![0]),
+        BigInt::new(Sign::Plus, vec![0xdeadbeef, 42]),
+        BigInt::new(Sign::Minus, vec![1]),
+    ];
+
+    for x in values {
+        let json = serde_json::to_string(&x).map_err(|err| err.to_string())?;
+        assert_eq!(x, serde_json::from_str::<BigInt>(&json).map_err(|err| err.to_string())?);
+
+        let bytes = bincode::serialize(&x).map_err(|err| err.to_string())?;
+        assert_eq!(x, bincode::deserialize::<BigInt>(&bytes).map_err(|err| err.to_string())?);
+    }
+    Ok(())
+}
+
+#[test]
+fn bigint_json_puts_sign_outside_hex_magnitude() -> Result<(),String> {
+    let x = BigInt::new(Sign::Minus, vec![0xbeef]);
+
+    assert_eq!("\"-beef\"", serde_json::to_string(&x).map_err(|err| err.to_string())?);
+    assert_eq!("\"beef\"", serde_json::to_string(&BigUint::from(BigInt::new(Sign::Plus, vec![0xbeef]))).map_err(|err| err.to_string())?);
+    Ok(())
+}
+
+#[test]
+fn bigint_bincode_rejects_invalid_sign() -> Result<(),String> {
+    let bytes = bincode::serialize(&(2i8, vec![1u8])).map_err(|err| err.to_string())?;
+
+    assert!(bincode::deserialize::<BigInt>(&bytes).is_err());
+    Ok(())
+}
+
+#[test]
+fn bigint_rem_takes_sign_of_dividend_but_mod_floor_does_not() {
+    let minus_seven = BigInt::new(Sign::Minus, vec![7]);
+    let three = BigInt::new(Sign::Plus, vec![3]);
+
+    assert_eq!(BigInt::new(Sign::Minus, vec![1]), &minus_seven % &three);
+    assert_eq!(BigInt::new(Sign::Plus, vec![1]), BigInt::new(Sign::Plus, vec![7]) % three);
+    assert_eq!(BigUint::from(2u32), minus_seven.mod_floor(&BigUint::from(3u32)));
+    assert_eq!(BigUint::from(1u32), BigInt::new(Sign::Plus, vec![7]).mod_floor(&BigUint::from(3u32)));
+    assert_eq!(BigUint::from(0u32), BigInt::new(Sign::Minus, vec![9]).mod_floor(&BigUint::from(3u32)));
+}
+
+#[test]
+fn mod_floor_of_large_negative_values_is_in_range() {
+    let modulus = random_biguint(64) | BigUint::from(1u32);
+    let x = -BigInt::from(random_biguint(128));
+    let reduced = x.mod_floor(&modulus);
+
+    assert!(reduced < modulus);
+    assert_eq!(BigInt::new(Sign::NoSign, vec![0]), (&x - &BigInt::from(reduced)) % BigInt::from(modulus));
+}
+
+#[test]
+fn bigint_neg_abs_and_sign() {
+    let x = BigInt::new(Sign::Plus, vec![42]);
+    let minus_x = -&x;
+
+    assert_eq!(BigInt::new(Sign::Minus, vec![42]), minus_x);
+    assert_eq!(x, -minus_x.clone());
+    assert_eq!(x, minus_x.abs());
+    assert_eq!(x, x.abs());
+    assert_eq!(Sign::Minus, minus_x.sign());
+    assert_eq!(Sign::Plus, x.sign());
+    assert_eq!(Sign::NoSign, BigInt::new(Sign::Plus, vec![0]).sign());
+    assert!(minus_x.is_negative());
+    assert!(!x.is_negative());
+    assert!(!BigInt::new(Sign::Minus, vec![0]).is_negative());
+}
+
+#[test]
+fn digit_try_from_char_accepts_only_decimal_digits() -> Result<(),String> {
+    assert_eq!(Digit::_0, Digit::try_from('0')?);
+    assert_eq!(Digit::_7, Digit::try_from('7')?);
+    assert!(Digit::try_from('a').is_err());
+    assert!(Digit::try_from('٣').is_err());
+    assert!(Digit::try_from(' ').is_err());
+    Ok(())
+}
+
+#[test]
+fn digit_try_from_u8_accepts_only_values_below_ten() -> Result<(),String> {
+    assert_eq!(Digit::_0, Digit::try_from(0u8)?);
+    assert_eq!(Digit::_9, Digit::try_from(9u8)?);
+    assert!(Digit::try_from(10u8).is_err());
+    assert!(Digit::try_from(b'5').is_err());
+    Ok(())
+}
+
+#[test]
+fn from_digits_reads_base_10_number() -> Result<(),String> {
+    let digits = "65537".chars().map(Digit::try_from).collect::<Result<Vec<_>,_>>()?;
+
+    assert_eq!(BigUint::from(65537u32), BigUint::from_digits(&digits));
+    assert_eq!(BigUint::from(0u32), BigUint::from_digits(&[]));
+    assert_eq!(BigUint::from(7u32), BigUint::from_digits(&[Digit::_0, Digit::_0, Digit::_7]));
+    Ok(())
+}
+
+#[test]
+fn from_digits_agrees_with_parse() -> Result<(),String> {
+    let s = random_biguint(64).to_string();
+    let digits = s.chars().map(Digit::try_from).collect::<Result<Vec<_>,_>>()?;
+
+    assert_eq!(BigUint::parse(&s)?, BigUint::from_digits(&digits));
+    Ok(())
+}
+
+#[test]
+fn ct_eq_agrees_with_eq_on_equal_lengths() {
+    for _ in 0..50 {
+        let a = random_biguint(32);
+        let b = random_biguint(32);
+
+        assert_eq!(a == b, a.ct_eq(&b));
+        assert!(a.ct_eq(&a.clone()));
+    }
+}
+
+#[test]
+fn ct_eq_agrees_with_eq_on_different_lengths() {
+    for (a_size, b_size) in [(1, 2), (8, 64), (256, 3)] {
+        let a = random_biguint(a_size);
+        let b = random_biguint(b_size);
+
+        assert_eq!(a == b, a.ct_eq(&b));
+        assert_eq!(b == a, b.ct_eq(&a));
+    }
+    assert!(BigUint::from_bytes_be(&[0, 0, 5]).ct_eq(&BigUint::from(5u32)));
+}
+
+#[test]
+fn ct_eq_bytes_compares_contents_and_lengths() {
+    assert!(ct_eq_bytes(&[1, 2, 3], &[1, 2, 3]));
+    assert!(!ct_eq_bytes(&[1, 2, 3], &[1, 2, 4]));
+    assert!(!ct_eq_bytes(&[1, 2, 3], &[1, 2, 3, 0]));
+    assert!(!ct_eq_bytes(&[], &[0]));
+    assert!(ct_eq_bytes(&[], &[]));
+}
+
+#[test]
+fn zeroize_leaves_value_equal_to_zero() {
+    for size in [1, 8, 256] {
+        let mut x = random_biguint(size) | BigUint::from(1u32);
+        x.zeroize();
+
+        assert_eq!(BigUint::from(0u32), x);
+        assert_eq!(0, x.bit_size());
+        assert_eq!(vec![0], x.to_bytes_be());
+    }
+}
+
+#[test]
+fn zeroized_value_can_be_reused() {
+    let mut x = random_biguint(32);
+    x.zeroize();
+    x += BigUint::from(7u32);
+
+    assert_eq!(BigUint::from(7u32), x);
+}
+
+fn mersenne(exponent: u32) -> BigUint {
+    (BigUint::two_pow(exponent) - BigUint::from(1u32)).unwrap()
+}
+
+#[test]
+fn small_known_primes_are_prime() {
+    for p in [2u32, 3, 5, 7, 1999, 2003, 7919, 19997, 20011, 65537, 2147483647] {
+        assert!(BigUint::from(p).prime(), "{p} should be prime");
+    }
+}
+
+#[test]
+fn small_composites_are_not_prime() {
+    for n in [0u32, 1, 4, 9, 561, 1105, 1729, 2001, 4028033, 65535, 400640231] {
+        assert!(!BigUint::from(n).prime(), "{n} should not be prime");
+    }
+}
+
+#[test]
+fn carmichael_number_without_small_factors_is_not_prime() -> Result<(),String> {
+    let carmichael = BigUint::parse("10478971179371449")?;
+    assert_eq!(carmichael, BigUint::from(120427u32) * BigUint::from(240853u32) * BigUint::from(361279u32));
+
+    assert!(!carmichael.prime());
+    Ok(())
+}
+
+#[test]
+fn large_mersenne_primes_are_prime() {
+    let mut rng = rand::thread_rng();
+
+    assert!(mersenne(127).prime());
+    assert!(mersenne(521).prime());
+    assert!(mersenne(1279).is_probable_prime(4, &mut rng));
+}
+
+#[test]
+fn large_composites_are_not_prime() {
+    assert!(!(mersenne(127) * mersenne(61)).prime());
+    assert!(!mersenne(523).prime());
+    assert!(!(mersenne(521) * mersenne(607)).prime());
+}
+
+#[test]
+fn new_prime_in_range_gives_prime_in_range() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+
+    for (lo, hi) in [(BigUint::from(2u32), BigUint::from(3u32)), (BigUint::from(100u32), BigUint::from(200u32)), (BigUint::two_pow(255), BigUint::two_pow(256))] {
+        let p = new_prime_in_range(&lo, &hi, &mut rng)?;
+
+        assert!(lo <= p && p < hi, "{p} is not in [{lo}, {hi})");
+        assert!(p.prime());
+    }
+    Ok(())
+}
+
+#[test]
+fn new_prime_in_range_rejects_empty_ranges() {
+    let mut rng = rand::thread_rng();
+
+    assert!(new_prime_in_range(&BigUint::from(10u32), &BigUint::from(10u32), &mut rng).is_err());
+    assert!(new_prime_in_range(&BigUint::from(11u32), &BigUint::from(10u32), &mut rng).is_err());
+}
+
+#[test]
+fn new_prime_in_range_fails_for_range_without_primes() {
+    let mut rng = rand::thread_rng();
+
+    assert!(new_prime_in_range(&BigUint::from(24u32), &BigUint::from(29u32), &mut rng).is_err());
+}
+
+#[test]
+fn new_strong_prime_in_range_meets_the_auxiliary_prime_conditions() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    let (lo, hi) = (BigUint::two_pow(1023), BigUint::two_pow(1024));
+    let strong = new_strong_prime_in_range(&lo, &hi, &mut rng)?;
+    let (p, r, s, t) = (&strong.p, &strong.r, &strong.s, &strong.t);
+    let one = BigUint::one();
+
+    assert!(lo <= *p && *p < hi, "{p} is not in [{lo}, {hi})");
+    for (name, x) in [("p", p), ("r", r), ("s", s), ("t", t)] {
+        assert!(x.prime(), "{name} = {x} is not prime");
+    }
+    assert!((p - &one)? % r == BigUint::zero(), "r does not divide p - 1");
+    assert!((p + &one) % s == BigUint::zero(), "s does not divide p + 1");
+    assert!((r - &one)? % t == BigUint::zero(), "t does not divide r - 1");
+    for (name, x) in [("r", r), ("s", s), ("t", t)] {
+        assert!(x.bit_size() >= 480, "{name} has only {} bits", x.bit_size());
+    }
+    Ok(())
+}
+
+#[test]
+fn new_strong_prime_in_range_rejects_ranges_too_small_for_auxiliary_primes() {
+    let mut rng = rand::thread_rng();
+    assert!(new_strong_prime_in_range(&BigUint::two_pow(63), &BigUint::two_pow(64), &mut rng).is_err());
+}
+
+fn assert_safe_prime(p: &BigUint, bits: u32) {
+    let q = (p - &BigUint::from(1u32)).unwrap() >> 1u32;
+
+    assert_eq!(bits, p.bit_size());
+    assert!(p.prime(), "{p} is not prime");
+    assert!(q.prime(), "{p} is prime but (p - 1) / 2 = {q} is not");
+}
+
+#[test]
+fn new_safe_prime_gives_small_safe_primes() {
+    for bits in [3, 4, 16, 64] {
+        assert_safe_prime(&new_safe_prime(bits), bits as u32);
+    }
+}
+
+#[test]
+fn new_safe_prime_gives_512_bit_safe_prime() {
+    assert_safe_prime(&new_safe_prime(512), 512);
+}
+
+#[test]
+fn next_prime_of_small_values() {
+    let cases = [(0u32, 2u32), (1, 2), (2, 3), (3, 5), (7, 11), (8, 11), (13, 17), (1999, 2003), (2003, 2011), (65536, 65537)];
+
+    for (x, expected) in cases {
+        assert_eq!(BigUint::from(expected), BigUint::from(x).next_prime(), "next prime after {x}");
+    }
+}
+
+#[test]
+fn next_prime_finds_mersenne_prime() {
+    let below = (mersenne(127) - BigUint::from(10u32)).unwrap();
+
+    assert_eq!(mersenne(127), below.next_prime());
+}
+
+#[test]
+fn next_prime_of_512_bit_value_is_larger_and_prime() {
+    let x = BigUint::two_pow(511) + random_biguint(32);
+    let p = x.next_prime();
+
+    assert!(p > x);
+    assert!(p.prime());
+}
+
+#[test]
+fn biguint_zero_and_one_match_their_integer_values() {
+    assert_eq!(BigUint::from(0u32), BigUint::zero());
+    assert_eq!(BigUint::from(1u32), BigUint::one());
+}
+
+#[test]
+fn biguint_is_zero_only_for_zero() {
+    assert!(BigUint::zero().is_zero());
+    assert!(BigUint::from_bytes_be(&[0, 0, 0]).is_zero());
+    assert!(!BigUint::one().is_zero());
+    assert!(!BigUint::two_pow(64).is_zero());
+}
+
+#[test]
+fn biguint_is_one_only_for_one() {
+    assert!(BigUint::one().is_one());
+    assert!(BigUint::from_bytes_be(&[0, 0, 1]).is_one());
+    assert!(!BigUint::zero().is_one());
+    assert!(!BigUint::from(2u32).is_one());
+    assert!(!(BigUint::two_pow(64) + BigUint::one()).is_one());
+}
+
+#[test]
+fn bigint_zero_and_one_match_their_integer_values() -> Result<(),String> {
+    assert_eq!(BigInt::from_i32(0)?, BigInt::zero());
+    assert_eq!(BigInt::from_i32(1)?, BigInt::one());
+    Ok(())
+}
+
+#[test]
+fn bigint_is_zero_only_for_zero() -> Result<(),String> {
+    assert!(BigInt::zero().is_zero());
+    assert!((-BigInt::zero()).is_zero());
+    assert!(!BigInt::one().is_zero());
+    assert!(!BigInt::from_i32(-1)?.is_zero());
+    Ok(())
+}
+
+#[test]
+fn bigint_is_one_is_false_for_minus_one() -> Result<(),String> {
+    assert!(BigInt::one().is_one());
+    assert!(!BigInt::from_i32(-1)?.is_one());
+    assert!(!BigInt::zero().is_one());
+    assert!(!BigInt::from_i32(2)?.is_one());
+    Ok(())
+}
+
+#[test]
+fn sum_agrees_with_fold_for_owned_and_borrowed_items() {
+    let xs: Vec<BigUint> = (0..20).map(|_| random_biguint(32)).collect();
+    let expected = xs.iter().fold(BigUint::zero(), |acc, x| &acc + x);
+
+    assert_eq!(expected, xs.iter().sum::<BigUint>());
+    assert_eq!(expected, xs.into_iter().sum::<BigUint>());
+}
+
+#[test]
+fn product_agrees_with_fold_for_owned_and_borrowed_items() {
+    let xs: Vec<BigUint> = (0..20).map(|_| random_biguint(32)).collect();
+    let expected = xs.iter().fold(BigUint::one(), |acc, x| &acc * x);
+
+    assert_eq!(expected, xs.iter().product::<BigUint>());
+    assert_eq!(expected, xs.into_iter().product::<BigUint>());
+}
+
+#[test]
+fn sum_of_nothing_is_zero_and_product_of_nothing_is_one() {
+    assert_eq!(BigUint::zero(), Vec::<BigUint>::new().into_iter().sum::<BigUint>());
+    assert_eq!(BigUint::one(), Vec::<BigUint>::new().iter().product::<BigUint>());
+}
+
+#[test]
+fn scalar_operators_agree_with_big_operators() -> Result<(),String> {
+    let x = random_biguint(64);
+    for k in [1u32, 2, 3, 65537, u32::MAX] {
+        let big_k = BigUint::from(k);
+
+        assert_eq!(&x + &big_k, &x + k);
+        assert_eq!((&x - &big_k)?, (&x - k)?);
+        assert_eq!(&x * &big_k, &x * k);
+        assert_eq!(&x / &big_k, &x / k);
+        assert_eq!(&x % &big_k, &x % k);
+        assert_eq!(&x * &big_k, x.clone() * k);
+    }
+    Ok(())
+}
+
+#[test]
+fn scalar_sub_errors_on_underflow() {
+    assert!((BigUint::from(2u32) - 3u32).is_err());
+    assert!((&BigUint::zero() - 1u32).is_err());
+}
+
+#[test]
+fn scalar_sub_to_zero() -> Result<(),String> {
+    assert_eq!(BigUint::zero(), (BigUint::from(7u32) - 7u32)?);
+    Ok(())
+}
+
+#[test]
+fn bit_agrees_with_bytes() {
+    let x = random_biguint(64);
+    let bytes = x.to_bytes_le();
+
+    for i in 0..bytes.len() as u64 * 8 + 16 {
+        let expected = bytes.get((i / 8) as usize).is_some_and(|byte| byte >> (i % 8) & 1 == 1);
+        assert_eq!(expected, x.bit(i), "bit {i} of {x}");
+    }
+}
+
+#[test]
+fn set_bit_round_trips_random_bit_pattern() {
+    let mut rng = rand::thread_rng();
+    let bits: Vec<bool> = (0..300).map(|_| rng.gen()).collect();
+    let mut x = BigUint::zero();
+
+    for (i, bit) in bits.iter().enumerate() {
+        x.set_bit(i as u64, *bit);
+    }
+
+    for (i, bit) in bits.iter().enumerate() {
+        assert_eq!(*bit, x.bit(i as u64), "bit {i}");
+    }
+    let expected_size = bits.iter().rposition(|bit| *bit).map_or(0, |i| i as u32 + 1);
+    assert_eq!(expected_size, x.bit_size());
+}
+
+#[test]
+fn set_bit_beyond_length_grows_number() {
+    let mut x = BigUint::one();
+    x.set_bit(100, true);
+
+    assert_eq!(BigUint::two_pow(100) + 1u32, x);
+    assert_eq!(101, x.bit_size());
+}
+
+#[test]
+fn clearing_top_bit_shrinks_bit_size() {
+    let mut x = BigUint::two_pow(100) + 1u32;
+    x.set_bit(100, false);
+    x.set_bit(200, false);
+
+    assert_eq!(BigUint::one(), x);
+    assert_eq!(1, x.bit_size());
+}
+
+#[test]
+fn byte_size_rounds_bit_size_up_to_whole_bytes() {
+    assert_eq!(256, BigUint::two_pow(2047).byte_size());
+    assert_eq!(256, BigUint::two_pow(2040).byte_size());
+    assert_eq!(255, BigUint::two_pow(2039).byte_size());
+    assert_eq!(257, BigUint::two_pow(2048).byte_size());
+    assert_eq!(1, BigUint::one().byte_size());
+}
+
+#[test]
+fn byte_size_of_zero_is_zero() {
+    assert_eq!(0, BigUint::zero().byte_size());
+}
+
+#[test]
+fn checked_div_and_rem_agree_with_operators() {
+    let x = random_biguint(64);
+    let y = random_biguint(16);
+
+    assert_eq!(Some(&x / &y), x.checked_div(&y));
+    assert_eq!(Some(&x % &y), x.checked_rem(&y));
+}
+
+#[test]
+fn checked_div_and_rem_by_zero_give_none() {
+    let x = random_biguint(16);
+
+    assert_eq!(None, x.checked_div(&BigUint::zero()));
+    assert_eq!(None, x.checked_rem(&BigUint::zero()));
+}
+
+#[test]
+fn bigint_checked_div_and_rem_agree_with_operators() -> Result<(),String> {
+    let x = BigInt::from_i32(-1234567)?;
+    let y = BigInt::from_i32(89)?;
+
+    assert_eq!(Some(&x / &y), x.checked_div(&y));
+    assert_eq!(Some(&x % &y), x.checked_rem(&y));
+    assert_eq!(None, x.checked_div(&BigInt::zero()));
+    assert_eq!(None, x.checked_rem(&BigInt::zero()));
+    Ok(())
+}
+
+#[test]
+fn biguint_compares_with_primitives_in_both_directions() {
+    let x = BigUint::from(5u32);
+
+    assert!(x == 5u32);
+    assert!(5u32 == x);
+    assert!(x != 6u64);
+    assert!(6u64 != x);
+    assert!(x > 1u32);
+    assert!(3u32 < x);
+    assert!(x <= 5u64);
+    assert!(5u64 >= x);
+    assert!(BigUint::zero() == 0u32);
+}
+
+#[test]
+fn biguint_larger_than_primitive_range_compares_greater() {
+    let x = BigUint::two_pow(64);
+    let max = BigUint::from(u64::MAX);
+
+    assert!(x > u64::MAX);
+    assert!(u64::MAX < x);
+    assert!(x != u32::MAX);
+    assert!(max == u64::MAX);
+    assert!(max > u32::MAX);
+}
+
+#[test]
+fn bigint_compares_with_primitives_in_both_directions() -> Result<(),String> {
+    let x = BigInt::from_i32(-5)?;
+    let y = BigInt::from_i32(7)?;
+
+    assert!(x < 0u32);
+    assert!(0u32 > x);
+    assert!(x != 5u64);
+    assert!(5u64 != x);
+    assert!(y == 7u32);
+    assert!(7u64 == y);
+    assert!(BigInt::zero() == 0u64);
+    Ok(())
+}
+
+#[test]
+fn bigint_outside_primitive_range_compares_correctly() {
+    let big = BigInt::from(BigUint::two_pow(200));
+    let negative = -&big;
+
+    assert!(big > u64::MAX);
+    assert!(u64::MAX < big);
+    assert!(negative < 0u32);
+    assert!(0u64 > negative);
+}
+
+#[test]
+fn to_f64_lossy_is_exact_for_small_values() {
+    assert_eq!(0.0, BigUint::zero().to_f64_lossy());
+    assert_eq!(12345.0, BigUint::from(12345u32).to_f64_lossy());
+    assert_eq!(2f64.powi(1000), BigUint::two_pow(1000).to_f64_lossy());
+}
+
+#[test]
+fn to_f64_lossy_is_close_for_large_values() {
+    let x = random_biguint(64);
+    let approx = x.to_f64_lossy();
+    let reference: f64 = x.to_string().parse().unwrap();
+
+    assert!((approx - reference).abs() <= reference * 1e-15, "{approx} vs {reference}");
+}
+
+#[test]
+fn to_f64_lossy_of_value_beyond_f64_max_is_infinite() {
+    assert_eq!(f64::INFINITY, BigUint::two_pow(1024).to_f64_lossy());
+    assert_eq!(f64::INFINITY, BigUint::two_pow(4096).to_f64_lossy());
+}
+
+#[test]
+fn log2_lies_within_bit_size_bounds() {
+    for bytes in [1, 8, 9, 64, 512, 1024] {
+        let x = random_biguint(bytes) + 1u32;
+        let log = x.log2();
+        let bits = x.bit_size() as f64;
+
+        assert!(log.is_finite());
+        assert!(bits - 1.0 - 1e-9 <= log && log < bits, "log2 {log} of {bits} bit value");
+    }
+}
+
+#[test]
+fn log2_of_power_of_two_is_its_exponent() {
+    for exp in [0, 1, 63, 64, 65, 2048, 10000] {
+        assert!((BigUint::two_pow(exp).log2() - exp as f64).abs() < 1e-9, "log2 of 2^{exp}");
+    }
+}
+
+#[test]
+fn log2_of_zero_is_negative_infinity() {
+    assert_eq!(f64::NEG_INFINITY, BigUint::zero().log2());
+}
+
+#[test]
+fn to_u32_digits_round_trips_through_new() {
+    for bytes in [0, 1, 4, 5, 64, 257] {
+        let x = random_biguint(bytes);
+        assert_eq!(x, BigUint::new(x.to_u32_digits()));
+    }
+}
+
+#[test]
+fn to_u32_digits_is_least_significant_first() {
+    let x = BigUint::two_pow(64) + 7u32;
+
+    assert_eq!(vec![7, 0, 1], x.to_u32_digits());
+    assert!(BigUint::zero().to_u32_digits().is_empty());
+}
+
+#[test]
+fn small_primes_are_not_divisible_by_small_prime() {
+    for p in [2u32, 3, 7919, 19997] {
+        assert!(!BigUint::from(p).divisible_by_small_prime(), "{p}");
+    }
+}
+
+#[test]
+fn multiples_of_small_primes_are_divisible_by_small_prime() {
+    let x = random_biguint(128);
+    for p in [2u32, 3, 7919, 19997] {
+        assert!((&x * p).divisible_by_small_prime(), "{p} * {x}");
+    }
+    assert!(BigUint::zero().divisible_by_small_prime());
+}
+
+#[test]
+fn values_without_small_factors_are_not_divisible_by_small_prime() {
+    assert!(!BigUint::one().divisible_by_small_prime());
+    assert!(!BigUint::from(400640231u32).divisible_by_small_prime());
+    assert!(!mersenne(521).divisible_by_small_prime());
+}
+
+#[test]
+fn euclidean_division_agrees_with_i64_for_all_sign_combinations() -> Result<(),String> {
+    for (a, b) in [(7i32, 3i32), (-7, 3), (7, -3), (-7, -3), (-6, 3), (0, -5), (-1, 1000000007)] {
+        let (big_a, big_b) = (BigInt::from_i32(a)?, BigInt::from_i32(b)?);
+
+        assert_eq!(BigInt::from_i32(a.div_euclid(b))?, big_a.div_euclid(&big_b), "{a} div_euclid {b}");
+        assert_eq!(BigInt::from_i32(a.rem_euclid(b))?, big_a.rem_euclid(&big_b), "{a} rem_euclid {b}");
+    }
+    Ok(())
+}
+
+#[test]
+fn euclidean_division_reconstructs_negative_dividend() {
+    let a = -BigInt::from(random_biguint(64));
+    let b = BigInt::from(random_biguint(16) + 1u32);
+    let (q, r) = (a.div_euclid(&b), a.rem_euclid(&b));
+
+    assert!(!r.is_negative() && r < b);
+    assert_eq!(a, &q * &b + r);
+}
+
+#[test]
+fn bigint_from_str_round_trips_through_display() -> Result<(),String> {
+    for s in ["0", "1", "-1", "123456789012345678901234567890", "-123456789012345678901234567890"] {
+        let x: BigInt = s.parse()?;
+        assert_eq!(s, x.to_string());
+    }
+    Ok(())
+}
+
+#[test]
+fn bigint_from_str_of_minus_zero_is_zero() -> Result<(),String> {
+    let x: BigInt = "-0".parse()?;
+
+    assert!(x.is_zero());
+    assert_eq!("0", x.to_string());
+    Ok(())
+}
+
+#[test]
+fn bigint_from_str_rejects_malformed_input() {
+    for s in ["", "-", "--1", "1-", "+-1", "12a"] {
+        assert!(s.parse::<BigInt>().is_err(), "{s:?} should not parse");
+    }
+}
+
+#[test]
+fn bigint_hex_keeps_sign_outside_digits() -> Result<(),String> {
+    let x = BigInt::from_i32(-255)?;
+
+    assert_eq!("-ff", format!("{x:x}"));
+    assert_eq!("-FF", format!("{x:X}"));
+    assert_eq!("-0xff", format!("{x:#x}"));
+    assert_eq!("ff", format!("{:x}", -x));
+    assert_eq!("0", format!("{:x}", BigInt::zero()));
+    Ok(())
+}
+
+#[test]
+fn bigint_hex_round_trips_through_biguint_from_hex() -> Result<(),String> {
+    let magnitude = random_biguint(64);
+    let x = -BigInt::from(magnitude.clone());
+    let hex = format!("{x:x}");
+
+    assert_eq!(magnitude, BigUint::from_hex(hex.strip_prefix('-').ok_or("missing sign")?)?);
+    Ok(())
+}
+
+#[test]
+fn to_bytes_be_padded_left_pads_to_length() -> Result<(),String> {
+    let x = BigUint::from(0x0102u32);
+
+    assert_eq!(vec![0, 0, 1, 2], x.to_bytes_be_padded(4)?);
+    assert_eq!(vec![1, 2], x.to_bytes_be_padded(2)?);
+    Ok(())
+}
+
+#[test]
+fn to_bytes_be_padded_of_zero_is_all_zeros() -> Result<(),String> {
+    assert_eq!(vec![0; 3], BigUint::zero().to_bytes_be_padded(3)?);
+    assert!(BigUint::zero().to_bytes_be_padded(0)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn to_bytes_be_padded_fails_when_value_does_not_fit() {
+    assert!(BigUint::from(0x0102u32).to_bytes_be_padded(1).is_err());
+    assert!(BigUint::two_pow(2048).to_bytes_be_padded(256).is_err());
+}
+
+#[test]
+fn to_bytes_be_padded_round_trips_through_from_bytes_be() -> Result<(),String> {
+    let x = random_biguint(200);
+    let padded = x.to_bytes_be_padded(256)?;
+
+    assert_eq!(256, padded.len());
+    assert_eq!(x, BigUint::from_bytes_be(&padded));
+    Ok(())
+}
+
+#[test]
+fn from_bytes_be_ignores_leading_zeros() {
+    assert_eq!(BigUint::from(0x0102u32), BigUint::from_bytes_be(&[0, 0, 0, 1, 2]));
+    assert_eq!(BigUint::zero(), BigUint::from_bytes_be(&[0, 0]));
+    assert_eq!(BigUint::zero(), BigUint::from_bytes_be(&[]));
+}
+
+#[test]
+fn modinv_of_3_mod_7() -> Result<(),String> {
+    assert_eq!(BigUint::from(5u32), BigUint::from(3u32).modinv(&BigUint::from(7u32))?);
+    Ok(())
+}
+
+#[test]
+fn modinv_times_self_is_one_mod_modulus() -> Result<(),String> {
+    let modulus = BigUint::parse("170141183460469231731687303715884105727")?;
+    let x = BigUint::parse("123456789012345678901234567890")?;
+    let res = x.modinv(&modulus)?;
+
+    assert_eq!(BigUint::one(), (&x * &res) % &modulus);
+    Ok(())
+}
+
+#[test]
+fn modinv_of_non_coprime_value_fails() {
+    let (x, modulus) = (BigUint::from(6u32), BigUint::from(9u32));
+
+    assert_eq!(Err(modular::Error::NotInvertible { x: x.clone(), modulus: modulus.clone() }), x.modinv(&modulus));
+    assert_eq!(Err(modular::Error::ZeroModulus), BigUint::from(3u32).modinv(&BigUint::zero()));
+}
@@ -1,11 +1,700 @@
-use rustnetworking::{modular::{inverse}, big_num::BigUint};
+#![feature(test)]
+
+extern crate test;
+use test::Bencher;
+use rand::Rng;
+use rustnetworking::{modular::{inverse, inverse_signed, mod_add, mod_sub, mod_mul, mod_neg, crt, jacobi, legendre, is_quadratic_residue, sqrt, phi_from_primes, carmichael_from_primes, discrete_log, MAX_DISCRETE_LOG_BOUND, solve_linear, LinearSolutions, MontgomeryCtx, BarrettCtx, FixedBaseExp, MAX_WINDOW_BITS, Error}, big_num::{BigUint, BigInt, new_prime}, rsa::DEFAULT_E};
 
 #[test]
 fn test_inverse_of_3_mod_7() -> Result<(),String> {
     let x = BigUint::from_i32(3)?;
     let modulus = BigUint::from_i32(7)?;
-    let res = inverse(x, modulus)?;
+    let res = inverse(&x, &modulus)?;
 
     assert_eq!(BigUint::from_i32(5)?, res);
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn inverse_times_x_is_one_mod_modulus() -> Result<(),String> {
+    let modulus = BigUint::parse("170141183460469231731687303715884105727")?;
+    let x = BigUint::parse("123456789012345678901234567890")?;
+    let res = inverse(&x, &modulus)?;
+
+    assert_eq!(BigUint::from(1u32), (x * res) % modulus);
+    Ok(())
+}
+
+#[test]
+fn inverse_of_non_coprime_value_fails() -> Result<(),String> {
+    let x = BigUint::from_i32(6)?;
+    let modulus = BigUint::from_i32(9)?;
+
+    assert_eq!(Err(Error::NotInvertible { x: x.clone(), modulus: modulus.clone() }), inverse(&x, &modulus));
+    Ok(())
+}
+
+#[test]
+fn inverse_with_zero_modulus_fails() -> Result<(),String> {
+    assert_eq!(Err(Error::ZeroModulus), inverse(&BigUint::from(1u32), &BigUint::from(0u32)));
+    assert_eq!(Err(Error::ZeroModulus), inverse(&BigUint::from(3u32), &BigUint::from(0u32)));
+    Ok(())
+}
+
+#[test]
+fn inverse_error_converts_into_string_error() {
+    fn f() -> Result<BigUint,String> {
+        Ok(inverse(&BigUint::from(6u32), &BigUint::from(9u32))?)
+    }
+
+    assert!(f().is_err());
+}
+
+#[test]
+fn inverse_of_e_mod_2048_bit_totient_runs_on_small_stack() -> Result<(),String> {
+    let e = BigUint::from(DEFAULT_E);
+    let totient = loop {
+        let totient = (new_prime(1024) - 1u32)? * (new_prime(1024) - 1u32)?;
+        if e.co_prime(&totient) {
+            break totient
+        }
+    };
+
+    let handle = std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn({
+            let (e, totient) = (e.clone(), totient.clone());
+            move || inverse(&e, &totient)
+        })
+        .map_err(|err| err.to_string())?;
+    let d = handle.join().map_err(|_| "inverse overflowed the small stack")??;
+
+    assert_eq!(BigUint::one(), (e * d) % totient);
+    Ok(())
+}
+
+#[test]
+fn mod_sub_wraps_when_a_is_smaller_than_b() {
+    let n = BigUint::from(7u32);
+
+    assert_eq!(BigUint::from(5u32), mod_sub(&BigUint::from(2u32), &BigUint::from(4u32), &n));
+    assert_eq!(BigUint::from(6u32), mod_sub(&BigUint::zero(), &BigUint::one(), &n));
+    assert_eq!(BigUint::zero(), mod_sub(&BigUint::from(3u32), &BigUint::from(3u32), &n));
+}
+
+#[test]
+fn mod_sub_of_values_larger_than_n() {
+    let n = BigUint::from(7u32);
+
+    assert_eq!(BigUint::from(5u32), mod_sub(&BigUint::from(16u32), &BigUint::from(25u32), &n));
+    assert_eq!(BigUint::from(2u32), mod_sub(&BigUint::from(100u32), &BigUint::from(7u32), &n));
+}
+
+#[test]
+fn mod_sub_agrees_with_signed_arithmetic_for_large_values() {
+    let mut rng = rand::thread_rng();
+    let n = new_prime(256);
+    let bound = &n * 4u32;
+
+    for _ in 0..20 {
+        let a = BigUint::random_below(&bound, &mut rng);
+        let b = BigUint::random_below(&bound, &mut rng);
+        let expected = (BigInt::from(&a) - BigInt::from(&b)).mod_floor(&n);
+        assert_eq!(expected, mod_sub(&a, &b, &n), "{a} - {b} mod {n}");
+    }
+}
+
+#[test]
+fn mod_add_mul_and_neg_are_reduced() {
+    let n = BigUint::from(7u32);
+    let (a, b) = (BigUint::from(20u32), BigUint::from(30u32));
+
+    assert_eq!(BigUint::from(1u32), mod_add(&a, &b, &n));
+    assert_eq!(BigUint::from(5u32), mod_mul(&a, &b, &n));
+    assert_eq!(BigUint::from(1u32), mod_neg(&a, &n));
+    assert_eq!(BigUint::zero(), mod_neg(&BigUint::from(14u32), &n));
+}
+
+#[test]
+fn mod_neg_plus_value_is_zero() {
+    let n = new_prime(256);
+    let a = BigUint::two_pow(300) + 5u32;
+
+    assert_eq!(BigUint::zero(), mod_add(&a, &mod_neg(&a, &n), &n));
+}
+
+#[test]
+fn inverse_signed_of_negative_value() -> Result<(),String> {
+    let modulus = BigUint::from(7u32);
+
+    assert_eq!(BigUint::from(2u32), inverse_signed(&BigInt::from_i32(-3)?, &modulus)?);
+    assert_eq!(BigUint::from(2u32), inverse_signed(&BigInt::from_i32(-10)?, &modulus)?);
+    assert_eq!(BigUint::from(6u32), inverse_signed(&BigInt::from_i32(-1)?, &modulus)?);
+    Ok(())
+}
+
+#[test]
+fn inverse_signed_of_large_negative_value_times_x_is_one() -> Result<(),String> {
+    let modulus = BigUint::parse("170141183460469231731687303715884105727")?;
+    let x: BigInt = "-123456789012345678901234567890123456789012345678901234567890".parse()?;
+    let res = inverse_signed(&x, &modulus)?;
+
+    assert_eq!(BigUint::one(), (x * BigInt::from(res)).mod_floor(&modulus));
+    Ok(())
+}
+
+#[test]
+fn inverse_signed_agrees_with_inverse_for_positive_values() -> Result<(),String> {
+    let modulus = BigUint::parse("170141183460469231731687303715884105727")?;
+    for x in [BigUint::one(), BigUint::from(3u32), BigUint::parse("123456789012345678901234567890")?] {
+        assert_eq!(inverse(&x, &modulus)?, inverse_signed(&BigInt::from(&x), &modulus)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn inverse_signed_of_non_invertible_or_zero_modulus_fails() -> Result<(),String> {
+    let (x, modulus) = (BigInt::from_i32(-3)?, BigUint::from(9u32));
+
+    assert_eq!(Err(Error::NotInvertible { x: BigUint::from(6u32), modulus: modulus.clone() }), inverse_signed(&x, &modulus));
+    assert_eq!(Err(Error::ZeroModulus), inverse_signed(&x, &BigUint::zero()));
+    Ok(())
+}
+
+#[test]
+fn crt_solves_classic_two_modulus_system() -> Result<(),String> {
+    let x = crt(&[BigUint::from(2u32), BigUint::from(3u32)], &[BigUint::from(3u32), BigUint::from(5u32)])?;
+
+    assert_eq!(BigUint::from(8u32), x);
+    Ok(())
+}
+
+#[test]
+fn crt_solves_three_modulus_system() -> Result<(),String> {
+    let residues = [BigUint::from(2u32), BigUint::from(3u32), BigUint::from(2u32)];
+    let moduli = [BigUint::from(3u32), BigUint::from(5u32), BigUint::from(7u32)];
+
+    assert_eq!(BigUint::from(23u32), crt(&residues, &moduli)?);
+    Ok(())
+}
+
+#[test]
+fn crt_recovers_value_from_residues_mod_large_primes() -> Result<(),String> {
+    let moduli = [new_prime(128), new_prime(128), new_prime(128)];
+    let x = BigUint::two_pow(300) + 12345u32;
+    let residues: Vec<BigUint> = moduli.iter().map(|m| &x % m).collect();
+
+    assert_eq!(x, crt(&residues, &moduli)?);
+    Ok(())
+}
+
+#[test]
+fn crt_reduces_residues_larger_than_modulus() -> Result<(),String> {
+    let x = crt(&[BigUint::from(17u32), BigUint::from(13u32)], &[BigUint::from(3u32), BigUint::from(5u32)])?;
+
+    assert_eq!(BigUint::from(8u32), x);
+    Ok(())
+}
+
+#[test]
+fn crt_of_non_coprime_moduli_fails() {
+    let residues = [BigUint::from(1u32), BigUint::from(2u32), BigUint::from(3u32)];
+    let moduli = [BigUint::from(4u32), BigUint::from(9u32), BigUint::from(6u32)];
+
+    assert_eq!(Err(Error::NotCoprime { index: 2 }), crt(&residues, &moduli));
+}
+
+#[test]
+fn crt_of_mismatched_lengths_fails() {
+    let result = crt(&[BigUint::from(1u32)], &[BigUint::from(3u32), BigUint::from(5u32)]);
+
+    assert_eq!(Err(Error::LengthMismatch { residues: 1, moduli: 2 }), result);
+}
+
+#[test]
+fn crt_with_zero_modulus_fails() {
+    assert_eq!(Err(Error::ZeroModulus), crt(&[BigUint::from(1u32)], &[BigUint::zero()]));
+}
+
+#[test]
+fn crt_of_empty_system_is_zero() -> Result<(),String> {
+    assert_eq!(BigUint::zero(), crt(&[], &[])?);
+    Ok(())
+}
+
+#[test]
+fn jacobi_matches_known_table_values() -> Result<(),String> {
+    let table: [(u32, [i8; 16]); 4] = [
+        (1, [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]),
+        (15, [0, 1, 1, 0, 1, 0, 0, -1, 1, 0, 0, -1, 0, -1, -1, 0]),
+        (21, [0, 1, -1, 0, 1, 1, 0, 0, -1, 0, -1, -1, 0, -1, 0, 0]),
+        (45, [0, 1, -1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, -1, 1, 0]),
+    ];
+
+    for (n, row) in table {
+        for (a, expected) in row.into_iter().enumerate() {
+            assert_eq!(expected, jacobi(&BigUint::from(a), &BigUint::from(n))?, "({a}/{n})");
+        }
+    }
+    assert_eq!(-1, jacobi(&BigUint::from(1001u32), &BigUint::from(9907u32))?);
+    Ok(())
+}
+
+#[test]
+fn legendre_agrees_with_euler_criterion_for_small_primes() -> Result<(),String> {
+    for p in [3u32, 5, 7, 11, 13, 101, 7919] {
+        let p = BigUint::from(p);
+        let exponent = (&p - 1u32)? / 2u32;
+        for a in 0u32..50 {
+            let a = BigUint::from(a);
+            let expected = match a.modpow(&exponent, &p) {
+                x if x.is_zero() => 0,
+                x if x.is_one() => 1,
+                _ => -1,
+            };
+            assert_eq!(expected, legendre(&a, &p)?, "({a}/{p})");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn jacobi_with_even_modulus_fails() {
+    assert_eq!(Err(Error::EvenModulus), jacobi(&BigUint::from(3u32), &BigUint::from(8u32)));
+    assert_eq!(Err(Error::EvenModulus), jacobi(&BigUint::from(3u32), &BigUint::zero()));
+}
+
+#[test]
+fn is_quadratic_residue_matches_table_of_squares() -> Result<(),String> {
+    for p in [3u32, 5, 7, 11, 13, 17, 19, 23] {
+        let squares: Vec<u32> = (0..p).map(|r| r * r % p).collect();
+        for a in 0..2 * p {
+            let expected = squares.contains(&(a % p));
+            assert_eq!(expected, is_quadratic_residue(&BigUint::from(a), &BigUint::from(p))?, "{a} mod {p}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn is_quadratic_residue_agrees_with_legendre_for_256_bit_prime() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    let p = new_prime(256);
+
+    for _ in 0..20 {
+        let a = BigUint::random_below(&p, &mut rng);
+        let symbol = legendre(&a, &p)?;
+        assert_eq!(symbol != -1, is_quadratic_residue(&a, &p)?, "{a} mod {p}");
+        assert!(is_quadratic_residue(&(&a * &a), &p)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn is_quadratic_residue_of_multiple_of_p_is_true() -> Result<(),String> {
+    assert!(is_quadratic_residue(&BigUint::zero(), &BigUint::from(11u32))?);
+    assert!(is_quadratic_residue(&BigUint::from(22u32), &BigUint::from(11u32))?);
+    assert_eq!(Err(Error::EvenModulus), is_quadratic_residue(&BigUint::one(), &BigUint::from(10u32)));
+    Ok(())
+}
+
+fn sqrt_test_primes() -> Vec<BigUint> {
+    let goldilocks = (BigUint::two_pow(64) - BigUint::two_pow(32)).unwrap() + 1u32;
+    let curve25519 = (BigUint::two_pow(255) - 19u32).unwrap();
+    let mersenne_127 = (BigUint::two_pow(127) - 1u32).unwrap();
+    [3u32, 5, 7, 13, 17, 97, 7919, 65537].into_iter().map(BigUint::from)
+        .chain([goldilocks, curve25519, mersenne_127])
+        .collect()
+}
+
+#[test]
+fn sqrt_of_random_squares_round_trips() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    for p in sqrt_test_primes() {
+        for _ in 0..10 {
+            let r = BigUint::random_below(&p, &mut rng);
+            let a = &(&r * &r) % &p;
+            let root = sqrt(&a, &p)?.ok_or(format!("{a} is a square mod {p}"))?;
+
+            assert_eq!(a, &(&root * &root) % &p);
+            assert!(root == r || root == (&p - &r)?, "{root} is neither {r} nor -{r} mod {p}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn sqrt_of_non_residue_is_none() -> Result<(),String> {
+    for p in sqrt_test_primes() {
+        let non_residue = (2u32..).map(BigUint::from).find(|a| jacobi(a, &p) == Ok(-1)).ok_or("no non-residue")?;
+        assert_eq!(None, sqrt(&non_residue, &p)?, "{non_residue} mod {p}");
+    }
+    Ok(())
+}
+
+#[test]
+fn sqrt_of_multiple_of_p_is_zero() -> Result<(),String> {
+    assert_eq!(Some(BigUint::zero()), sqrt(&BigUint::zero(), &BigUint::from(17u32))?);
+    assert_eq!(Some(BigUint::zero()), sqrt(&BigUint::from(34u32), &BigUint::from(17u32))?);
+    Ok(())
+}
+
+#[test]
+fn sqrt_with_even_modulus_fails() {
+    assert_eq!(Err(Error::EvenModulus), sqrt(&BigUint::from(4u32), &BigUint::from(8u32)));
+}
+
+#[test]
+fn sqrt_with_modulus_that_is_not_prime_fails() {
+    // Every z is a square or shares a factor with an odd prime power, so there's no non-residue to find.
+    assert_eq!(Err(Error::NotPrime), sqrt(&BigUint::from(4u32), &BigUint::from(9u32)));
+    assert_eq!(Err(Error::NotPrime), sqrt(&BigUint::from(4u32), &BigUint::from(25u32)));
+    assert_eq!(Err(Error::NotPrime), sqrt(&BigUint::zero(), &BigUint::one()));
+    assert_eq!(Err(Error::NotPrime), sqrt(&BigUint::one(), &BigUint::one()));
+}
+
+#[test]
+fn phi_and_carmichael_of_small_products() {
+    let primes = [BigUint::from(3u32), BigUint::from(5u32), BigUint::from(7u32)];
+
+    assert_eq!(BigUint::from(48u32), phi_from_primes(&primes));
+    assert_eq!(BigUint::from(12u32), carmichael_from_primes(&primes));
+}
+
+#[test]
+fn phi_and_carmichael_of_no_primes_is_one() {
+    assert_eq!(BigUint::one(), phi_from_primes(&[]));
+    assert_eq!(BigUint::one(), carmichael_from_primes(&[]));
+}
+
+#[test]
+fn a_to_the_carmichael_lambda_is_one_mod_n() {
+    let mut rng = rand::thread_rng();
+    let primes = [new_prime(64), new_prime(64), new_prime(64)];
+    let n: BigUint = primes.iter().product();
+    let lambda = carmichael_from_primes(&primes);
+
+    assert!((phi_from_primes(&primes) % &lambda).is_zero());
+    for _ in 0..20 {
+        let a = BigUint::random_below(&n, &mut rng);
+        if a.co_prime(&n) {
+            assert_eq!(BigUint::one(), a.modpow(&lambda, &n), "{a}^λ(n) mod {n}");
+        }
+    }
+}
+
+#[test]
+fn discrete_log_recovers_exponents_up_to_millions() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    let modulus = BigUint::from(2147483647u32);
+    let base = BigUint::from(7u32);
+
+    for x in [0u64, 1, 2, 4999999, rng.gen_range(0..5000000)] {
+        let target = base.modpow(&BigUint::from(x), &modulus);
+        assert_eq!(Some(BigUint::from(x)), discrete_log(&base, &target, &modulus, 5000000)?, "7^{x}");
+    }
+    Ok(())
+}
+
+#[test]
+fn discrete_log_outside_subgroup_is_none() -> Result<(),String> {
+    let result = discrete_log(&BigUint::from(2u32), &BigUint::from(5u32), &BigUint::from(23u32), 100)?;
+
+    assert_eq!(None, result);
+    Ok(())
+}
+
+#[test]
+fn discrete_log_above_bound_is_none() -> Result<(),String> {
+    let modulus = BigUint::from(2147483647u32);
+    let base = BigUint::from(7u32);
+    let target = base.modpow(&BigUint::from(1000u32), &modulus);
+
+    assert_eq!(None, discrete_log(&base, &target, &modulus, 1000)?);
+    assert_eq!(Some(BigUint::from(1000u32)), discrete_log(&base, &target, &modulus, 1001)?);
+    Ok(())
+}
+
+#[test]
+fn discrete_log_rejects_cryptographic_bounds() {
+    let result = discrete_log(&BigUint::from(2u32), &BigUint::from(5u32), &BigUint::from(23u32), MAX_DISCRETE_LOG_BOUND + 1);
+
+    assert_eq!(Err(Error::BoundTooLarge { bound: MAX_DISCRETE_LOG_BOUND + 1 }), result);
+}
+
+#[test]
+fn discrete_log_with_zero_modulus_fails() {
+    assert_eq!(Err(Error::ZeroModulus), discrete_log(&BigUint::from(2u32), &BigUint::one(), &BigUint::zero(), 10));
+}
+
+fn biguints(values: &[u32]) -> Vec<BigUint> {
+    values.iter().map(|&x| BigUint::from(x)).collect()
+}
+
+#[test]
+fn solve_linear_with_coprime_a_has_unique_solution() -> Result<(),String> {
+    assert_eq!(biguints(&[6]), solve_linear(&BigUint::from(3u32), &BigUint::from(4u32), &BigUint::from(7u32))?.iter().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn solve_linear_has_gcd_many_solutions() -> Result<(),String> {
+    let solutions = solve_linear(&BigUint::from(6u32), &BigUint::from(9u32), &BigUint::from(15u32))?;
+
+    assert_eq!(biguints(&[4, 9, 14]), solutions.iter().collect::<Vec<_>>());
+    assert!(solutions.contains(&BigUint::from(9u32)));
+    assert!(!solutions.contains(&BigUint::from(8u32)));
+    assert!(!solutions.contains(&BigUint::from(19u32)));
+    Ok(())
+}
+
+#[test]
+fn solve_linear_solutions_satisfy_congruence() -> Result<(),String> {
+    let modulus = BigUint::parse("170141183460469231731687303715884105727")? * BigUint::from(360u32);
+    let a = BigUint::parse("123456789012345678901234567890")? * BigUint::from(24u32);
+    let gcd = a.gcd(&modulus);
+    let b = &gcd * 2u32;
+    let solutions = solve_linear(&a, &b, &modulus)?;
+
+    assert!(gcd > 24u32);
+    assert_eq!(gcd, solutions.count);
+    assert_eq!(gcd, BigUint::from(solutions.iter().count()));
+    for x in solutions.iter() {
+        assert!(x < modulus);
+        assert_eq!(b, (&a * &x) % &modulus);
+    }
+    Ok(())
+}
+
+#[test]
+fn solve_linear_of_zero_by_zero_keeps_every_residue_compact() -> Result<(),String> {
+    let modulus = BigUint::two_pow(2048) + 1u32;
+    let solutions = solve_linear(&BigUint::zero(), &BigUint::zero(), &modulus)?;
+
+    assert_eq!(LinearSolutions { first: BigUint::zero(), step: BigUint::one(), count: modulus.clone() }, solutions);
+    assert_eq!(biguints(&[0, 1, 2]), solutions.iter().take(3).collect::<Vec<_>>());
+    assert!(solutions.contains(&(&modulus - 1u32)?));
+    assert!(!solutions.contains(&modulus));
+    Ok(())
+}
+
+#[test]
+fn solve_linear_without_solution_fails() {
+    let result = solve_linear(&BigUint::from(6u32), &BigUint::from(4u32), &BigUint::from(15u32));
+
+    assert_eq!(Err(Error::NoSolution { gcd: BigUint::from(3u32), b: BigUint::from(4u32) }), result);
+}
+
+#[test]
+fn solve_linear_with_zero_modulus_fails() {
+    assert_eq!(Err(Error::ZeroModulus), solve_linear(&BigUint::one(), &BigUint::one(), &BigUint::zero()));
+}
+
+#[test]
+fn montgomery_mul_agrees_with_naive_modmul() -> Result<(),String> {
+    let modulus = new_prime(512);
+    let ctx = MontgomeryCtx::new(&modulus)?;
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..20 {
+        let a = BigUint::random_below(&modulus, &mut rng);
+        let b = BigUint::random_below(&modulus, &mut rng);
+        let product = ctx.mul(&ctx.to_mont(&a), &ctx.to_mont(&b));
+
+        assert_eq!((&a * &b) % &modulus, ctx.from_mont(&product));
+    }
+    Ok(())
+}
+
+#[test]
+fn montgomery_round_trips_and_reduces() -> Result<(),String> {
+    let modulus = BigUint::from(1000003u32);
+    let ctx = MontgomeryCtx::new(&modulus)?;
+
+    assert_eq!(modulus, ctx.modulus());
+    assert_eq!(BigUint::from(12345u32), ctx.from_mont(&ctx.to_mont(&BigUint::from(12345u32))));
+    assert_eq!(BigUint::from(5u32), ctx.from_mont(&ctx.to_mont(&(&modulus * 3u32 + 5u32))));
+    Ok(())
+}
+
+#[test]
+fn montgomery_pow_agrees_with_modpow() -> Result<(),String> {
+    let modulus = new_prime(256) * new_prime(256);
+    let ctx = MontgomeryCtx::new(&modulus)?;
+    let base = BigUint::from(65537u32);
+    let exponent = BigUint::two_pow(300) + 12345u32;
+
+    assert_eq!(base.modpow(&exponent, &modulus), ctx.pow(&base, &exponent));
+    Ok(())
+}
+
+#[test]
+fn montgomery_ctx_rejects_even_and_zero_moduli() {
+    assert_eq!(Some(Error::EvenModulus), MontgomeryCtx::new(&BigUint::from(1000u32)).err());
+    assert_eq!(Some(Error::ZeroModulus), MontgomeryCtx::new(&BigUint::zero()).err());
+}
+
+#[test]
+fn barrett_reduce_agrees_with_rem_for_odd_and_even_moduli() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    for modulus in [new_prime(512), new_prime(256) * 1024u32, BigUint::two_pow(300), BigUint::from(3u32), BigUint::one()] {
+        let ctx = BarrettCtx::new(&modulus)?;
+        let square = &modulus * &modulus;
+        for _ in 0..20 {
+            let x = BigUint::random_below(&square, &mut rng);
+            assert_eq!(&x % &modulus, ctx.reduce(&x), "{x} mod {modulus}");
+        }
+        assert_eq!(BigUint::zero(), ctx.reduce(&square));
+    }
+    Ok(())
+}
+
+#[test]
+fn barrett_reduce_of_values_above_modulus_squared() -> Result<(),String> {
+    let modulus = new_prime(128);
+    let ctx = BarrettCtx::new(&modulus)?;
+    let x = BigUint::two_pow(1000) + 42u32;
+
+    assert_eq!(&x % &modulus, ctx.reduce(&x));
+    Ok(())
+}
+
+#[test]
+fn barrett_mulmod_agrees_with_naive_modmul() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    let modulus = new_prime(512) * 2u32;
+    let ctx = BarrettCtx::new(&modulus)?;
+
+    for _ in 0..20 {
+        let a = BigUint::random_below(&modulus, &mut rng);
+        let b = BigUint::random_below(&modulus, &mut rng);
+        assert_eq!((&a * &b) % &modulus, ctx.mulmod(&a, &b));
+    }
+    assert_eq!(modulus, ctx.modulus());
+    Ok(())
+}
+
+#[test]
+fn barrett_ctx_rejects_zero_modulus() {
+    assert_eq!(Some(Error::ZeroModulus), BarrettCtx::new(&BigUint::zero()).err());
+}
+
+#[test]
+fn fixed_base_exp_agrees_with_modpow() -> Result<(),String> {
+    let mut rng = rand::thread_rng();
+    let modulus = new_prime(256) * new_prime(256);
+    let base = BigUint::random_below(&modulus, &mut rng);
+
+    for window_bits in [1, 3, 4, MAX_WINDOW_BITS] {
+        let exp = FixedBaseExp::new(&base, &modulus, window_bits)?;
+        for exponent in [BigUint::zero(), BigUint::one(), BigUint::random_below(&modulus, &mut rng), (&modulus - 1u32)?] {
+            assert_eq!(base.modpow(&exponent, &modulus), exp.pow(&exponent), "{window_bits} bit windows, exponent {exponent}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn fixed_base_exp_of_exponent_longer_than_modulus() -> Result<(),String> {
+    let modulus = new_prime(128);
+    let base = BigUint::from(3u32);
+    let exponent = BigUint::two_pow(1000) + 7u32;
+
+    assert_eq!(base.modpow(&exponent, &modulus), FixedBaseExp::new(&base, &modulus, 4)?.pow(&exponent));
+    Ok(())
+}
+
+#[test]
+fn fixed_base_exp_rejects_bad_windows_and_even_moduli() {
+    let (base, modulus) = (BigUint::from(3u32), BigUint::from(101u32));
+
+    assert_eq!(Some(Error::InvalidWindowBits { bits: 0 }), FixedBaseExp::new(&base, &modulus, 0).err());
+    assert_eq!(Some(Error::InvalidWindowBits { bits: MAX_WINDOW_BITS + 1 }), FixedBaseExp::new(&base, &modulus, MAX_WINDOW_BITS + 1).err());
+    assert_eq!(Some(Error::EvenModulus), FixedBaseExp::new(&base, &BigUint::from(100u32), 4).err());
+}
+
+#[cfg(feature = "proptest")]
+mod properties {
+    use proptest::prelude::*;
+    use rustnetworking::{modular::inverse, big_num::{BigUint, strategy::{up_to_bits, probable_prime}}};
+
+    proptest! {
+        #[test]
+        fn inverse_times_x_is_one_modulo_a_prime(x in up_to_bits(256), p in probable_prime(128)) {
+            prop_assume!(!(&x % &p).is_zero());
+            let res = inverse(&x, &p).map_err(|err| TestCaseError::fail(err.to_string()))?;
+
+            prop_assert!(res < p);
+            prop_assert_eq!(BigUint::one(), (x * res) % p);
+        }
+
+        #[test]
+        fn inverse_exists_exactly_when_coprime(x: BigUint, modulus in up_to_bits(128)) {
+            prop_assume!(modulus > 1u32);
+            let res = inverse(&x, &modulus);
+
+            prop_assert_eq!(x.co_prime(&modulus), res.is_ok());
+            if let Ok(res) = res {
+                prop_assert_eq!(BigUint::one(), (x * res) % modulus);
+            }
+        }
+    }
+}
+
+#[bench]
+fn bench_inverse_of_e_mod_2048_bit_value(b: &mut Bencher) {
+    let one = BigUint::from(1u32);
+    let p = (new_prime(1024) - one.clone()).unwrap();
+    let q = (new_prime(1024) - one).unwrap();
+    let modulus = p * q;
+    b.iter(|| inverse(&BigUint::from(65537u32), &modulus))
+}
+
+fn bench_operands() -> (BigUint, Vec<BigUint>) {
+    let modulus = new_prime(1024) * new_prime(1024);
+    let mut rng = rand::thread_rng();
+    let operands = (0..1000).map(|_| BigUint::random_below(&modulus, &mut rng)).collect();
+    (modulus, operands)
+}
+
+#[bench]
+fn bench_1000_modmuls_through_montgomery_ctx(b: &mut Bencher) {
+    let (modulus, operands) = bench_operands();
+    let ctx = MontgomeryCtx::new(&modulus).unwrap();
+    let operands: Vec<_> = operands.iter().map(|x| ctx.to_mont(x)).collect();
+    b.iter(|| operands.iter().fold(ctx.to_mont(&BigUint::one()), |acc, x| ctx.mul(&acc, x)))
+}
+
+#[bench]
+fn bench_1000_naive_modmuls(b: &mut Bencher) {
+    let (modulus, operands) = bench_operands();
+    b.iter(|| operands.iter().fold(BigUint::one(), |acc, x| &(&acc * x) % &modulus))
+}
+
+#[bench]
+fn bench_1000_modmuls_through_barrett_ctx(b: &mut Bencher) {
+    let (modulus, operands) = bench_operands();
+    let ctx = BarrettCtx::new(&modulus).unwrap();
+    b.iter(|| operands.iter().fold(BigUint::one(), |acc, x| ctx.mulmod(&acc, x)))
+}
+
+fn bench_exponents() -> (BigUint, BigUint, Vec<BigUint>) {
+    let modulus = new_prime(1024) * new_prime(1024);
+    let mut rng = rand::thread_rng();
+    let base = BigUint::random_below(&modulus, &mut rng);
+    let exponents = (0..100).map(|_| BigUint::random_below(&modulus, &mut rng)).collect();
+    (base, modulus, exponents)
+}
+
+#[bench]
+fn bench_100_exponentiations_with_fixed_base_exp(b: &mut Bencher) {
+    let (base, modulus, exponents) = bench_exponents();
+    let exp = FixedBaseExp::new(&base, &modulus, 4).unwrap();
+    b.iter(|| exponents.iter().map(|e| exp.pow(e)).collect::<Vec<_>>())
+}
+
+#[bench]
+fn bench_100_exponentiations_with_modpow(b: &mut Bencher) {
+    let (base, modulus, exponents) = bench_exponents();
+    b.iter(|| exponents.iter().map(|e| base.modpow(e, &modulus)).collect::<Vec<_>>())
+}
@@ -0,0 +1,98 @@
+use rustnetworking::rsa::{keygen, KeyId, Error as RsaError, agent::{AgentHandle, Error, KeyAgent}, authenticity::{verify, Verification}, confidentiality::encrypt};
+
+fn is_send_and_sync<T: Send + Sync>() {}
+
+#[test]
+fn handles_are_send_and_sync() {
+    is_send_and_sync::<AgentHandle>();
+}
+
+#[test]
+fn agent_signs_and_decrypts() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let agent = KeyAgent::new([sk]);
+    let handle = agent.handle();
+    let id = KeyId::of(&pk);
+
+    let signature = handle.sign(id, "sign this")?;
+    assert!(matches!(verify("sign this", signature, pk.clone()), Verification::Accept));
+    assert_eq!("decrypt this".as_bytes(), &handle.decrypt(id, encrypt("decrypt this", &pk)?)?[..]);
+    Ok(())
+}
+
+#[test]
+fn added_keys_can_be_used() -> Result<(),String> {
+    let agent = KeyAgent::new([]);
+    let (pk,sk) = keygen(512)?.split();
+    let id = agent.add(sk);
+    assert_eq!(KeyId::of(&pk), id);
+
+    let signature = agent.handle().sign(id, "added")?;
+    assert!(matches!(verify("added", signature, pk), Verification::Accept));
+    Ok(())
+}
+
+#[test]
+fn unknown_key_is_reported() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let agent = KeyAgent::new([sk]);
+    let other = KeyId::of(keygen(512)?.public());
+    assert!(matches!(agent.handle().sign(other, "who?"), Err(Error::UnknownKey(id)) if id == other));
+    Ok(())
+}
+
+#[test]
+fn failing_operation_is_reported() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let agent = KeyAgent::new([sk]);
+    let too_long = vec![0xff; 65];
+    assert!(matches!(agent.handle().decrypt(KeyId::of(&pk), too_long), Err(Error::Key(RsaError::MessageTooLong { .. }))));
+    Ok(())
+}
+
+#[test]
+fn handles_work_across_threads() -> Result<(),String> {
+    let key_pairs = [keygen(512)?, keygen(512)?];
+    let agent = KeyAgent::new(key_pairs.iter().map(|key_pair| key_pair.secret().clone()));
+    let handle = agent.handle();
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4).map(|thread| {
+            let handle = handle.clone();
+            let pk = key_pairs[thread % 2].public();
+            scope.spawn(move || -> Result<(),String> {
+                for i in 0..10 {
+                    let message = format!("thread {thread} message {i}");
+                    let signature = handle.sign(KeyId::of(pk), message.as_str())?;
+                    assert!(matches!(verify(message.as_str(), signature, pk.clone()), Verification::Accept));
+                    assert_eq!(message.as_bytes(), &handle.decrypt(KeyId::of(pk), encrypt(message.as_str(), pk)?)?[..]);
+                }
+                Ok(())
+            })
+        }).collect();
+        workers.into_iter().try_for_each(|worker| worker.join().expect("worker panicked"))
+    })
+}
+
+#[test]
+fn dropped_agent_fails_requests_cleanly() -> Result<(),String> {
+    let (pk,sk) = keygen(1024)?.split();
+    let id = KeyId::of(&pk);
+    let agent = KeyAgent::new([sk]);
+    let handle = agent.handle();
+
+    let pending: Vec<_> = (0..4).map(|_| {
+        let handle = handle.clone();
+        std::thread::spawn(move || (0..20).map(|_| handle.sign(id, "pending")).collect::<Vec<_>>())
+    }).collect();
+    drop(agent);
+
+    for results in pending {
+        for result in results.join().expect("requester panicked") {
+            assert!(matches!(result, Ok(_) | Err(Error::Stopped)), "{result:?}");
+        }
+    }
+    assert!(matches!(handle.sign(id, "after"), Err(Error::Stopped)));
+    assert!(matches!(handle.clone().decrypt(id, vec![1]), Err(Error::Stopped)));
+    Ok(())
+}
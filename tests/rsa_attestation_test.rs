@@ -0,0 +1,143 @@
+use std::time::{Duration, SystemTime};
+use rustnetworking::rsa::{attest, keygen, KeyId, attestation::{verify_chain, Attestation, AttestationError, Claims}};
+
+fn claims(name: &str) -> Claims {
+    Claims { name: name.into(), expires_at: None }
+}
+
+#[test]
+fn attestation_verifies_with_its_issuer() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let (subject,_) = keygen(512)?.split();
+    let attestation = attest(&subject, claims("bob"), &issuer)?;
+
+    assert_eq!(KeyId::of(issuer.public()), attestation.issuer);
+    attestation.verify(issuer.public(), SystemTime::now())?;
+    Ok(())
+}
+
+#[test]
+fn attestation_is_rejected_by_another_key() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let (subject,_) = keygen(512)?.split();
+    let attestation = attest(&subject, claims("bob"), &issuer)?;
+
+    let other = keygen(512)?;
+    assert_eq!(Err(AttestationError::BadSignature { index: 0 }), attestation.verify(other.public(), SystemTime::now()));
+    let mut claimed = attestation.clone();
+    claimed.issuer = KeyId::of(other.public());
+    assert_eq!(Err(AttestationError::BadSignature { index: 0 }), claimed.verify(other.public(), SystemTime::now()));
+    Ok(())
+}
+
+#[test]
+fn tampered_attestation_is_rejected() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let (subject,_) = keygen(512)?.split();
+    let attestation = attest(&subject, Claims { name: "bob".into(), expires_at: Some(SystemTime::now() + Duration::from_secs(3600)) }, &issuer)?;
+    let now = SystemTime::now();
+
+    let mut swapped = attestation.clone();
+    swapped.subject = keygen(512)?.split().0;
+    assert_eq!(Err(AttestationError::BadSignature { index: 0 }), swapped.verify(issuer.public(), now));
+
+    let mut renamed = attestation.clone();
+    renamed.claims.name = "mallory".into();
+    assert_eq!(Err(AttestationError::BadSignature { index: 0 }), renamed.verify(issuer.public(), now));
+
+    let mut extended = attestation.clone();
+    extended.claims.expires_at = None;
+    assert_eq!(Err(AttestationError::BadSignature { index: 0 }), extended.verify(issuer.public(), now));
+    Ok(())
+}
+
+#[test]
+fn attestation_expires_at_its_expiry() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let (subject,_) = keygen(512)?.split();
+    let expires_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let attestation = attest(&subject, Claims { name: "bob".into(), expires_at: Some(expires_at) }, &issuer)?;
+
+    attestation.verify(issuer.public(), expires_at - Duration::from_secs(1))?;
+    assert_eq!(Err(AttestationError::Expired { index: 0, expires_at }), attestation.verify(issuer.public(), expires_at));
+    assert!(attestation.verify(issuer.public(), SystemTime::now()).is_err());
+    Ok(())
+}
+
+#[test]
+fn attestation_round_trips_through_json() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let (subject,_) = keygen(512)?.split();
+    let attestation = attest(&subject, Claims { name: "bob".into(), expires_at: Some(SystemTime::now() + Duration::from_secs(60)) }, &issuer)?;
+
+    let json = serde_json::to_string(&attestation).map_err(|err| err.to_string())?;
+    let decoded: Attestation = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+    assert_eq!(attestation, decoded);
+    decoded.verify(issuer.public(), SystemTime::now())?;
+    Ok(())
+}
+
+#[test]
+fn valid_chain_gives_the_last_subject() -> Result<(),String> {
+    let root = keygen(512)?;
+    let intermediate = keygen(512)?;
+    let (leaf,_) = keygen(512)?.split();
+    let chain = [
+        attest(intermediate.public(), claims("intermediate"), &root)?,
+        attest(&leaf, claims("leaf"), &intermediate)?,
+    ];
+
+    assert_eq!(&leaf, verify_chain(&chain, root.public(), SystemTime::now())?);
+    Ok(())
+}
+
+#[test]
+fn chain_with_a_broken_link_is_rejected() -> Result<(),String> {
+    let root = keygen(512)?;
+    let intermediate = keygen(512)?;
+    let (leaf,_) = keygen(512)?.split();
+    let now = SystemTime::now();
+
+    // Signed by the root instead of the intermediate.
+    let skipping = [
+        attest(intermediate.public(), claims("intermediate"), &root)?,
+        attest(&leaf, claims("leaf"), &root)?,
+    ];
+    assert_eq!(Err(AttestationError::BadSignature { index: 1 }), verify_chain(&skipping, root.public(), now));
+
+    let mut tampered = [
+        attest(intermediate.public(), claims("intermediate"), &root)?,
+        attest(&leaf, claims("leaf"), &intermediate)?,
+    ];
+    tampered[0].subject = keygen(512)?.split().0;
+    assert_eq!(Err(AttestationError::BadSignature { index: 0 }), verify_chain(&tampered, root.public(), now));
+    Ok(())
+}
+
+#[test]
+fn chain_with_an_expired_link_is_rejected() -> Result<(),String> {
+    let root = keygen(512)?;
+    let intermediate = keygen(512)?;
+    let (leaf,_) = keygen(512)?.split();
+    let now = SystemTime::now();
+    let expires_at = now - Duration::from_secs(1);
+    let chain = [
+        attest(intermediate.public(), Claims { name: "intermediate".into(), expires_at: Some(expires_at) }, &root)?,
+        attest(&leaf, claims("leaf"), &intermediate)?,
+    ];
+
+    assert_eq!(Err(AttestationError::Expired { index: 0, expires_at }), verify_chain(&chain, root.public(), now));
+    Ok(())
+}
+
+#[test]
+fn empty_and_overlong_chains_are_rejected() -> Result<(),String> {
+    let root = keygen(512)?;
+    assert_eq!(Err(AttestationError::EmptyChain), verify_chain(&[], root.public(), SystemTime::now()));
+
+    let link = attest(root.public(), claims("root"), &root)?;
+    let chain = vec![link; 9];
+    assert_eq!(Err(AttestationError::ChainTooLong { length: 9 }), verify_chain(&chain, root.public(), SystemTime::now()));
+    assert_eq!(root.public(), verify_chain(&chain[..8], root.public(), SystemTime::now())?);
+    Ok(())
+}
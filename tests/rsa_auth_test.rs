@@ -1,12 +1,12 @@
 use rustnetworking::{
     rsa::{
         authenticity::{self as auth, Signature, Verification},
-        keygen, 
+        keygen, keygen_with_exponent, keygen_multiprime, keygen_insecure_for_tests, Error,
         confidentiality::{self as conf, Message}}};
 
 #[test]
 fn verification_of_correct_message_sign_pair_accepts() -> Result<(),String> {
-    let (pk,sk) = keygen(2048)?;
+    let (pk,sk) = keygen(2048)?.split();
     let m: Message = "This is a test".into();
     let s: Signature = auth::sign(m.clone(), sk)?;
     let v: Verification = auth::verify(m, s, pk);
@@ -19,7 +19,7 @@ fn verification_of_correct_message_sign_pair_accepts() -> Result<(),String> {
 
 #[test]
 fn verification_of_message_modified_by_adversary_rejects() -> Result<(),String> {
-    let (pk,sk) = keygen(2048)?;
+    let (pk,sk) = keygen(2048)?.split();
     let m: Message = "This is a test, once again".into();
     let s: Signature = auth::sign(m, sk)?;
     let v: Verification = auth::verify("This is a different message injected by an adversary >:D", s, pk);
@@ -33,14 +33,50 @@ fn verification_of_message_modified_by_adversary_rejects() -> Result<(),String>
 
 #[test]
 fn message_cannot_be_forged_so_verification_accepts() -> Result<(),String> {
-    let (pk,sk) = keygen(2048)?;
+    let (pk,sk) = keygen(2048)?.split();
     let real_message: Message = "This is an actual message".into();
     let s: Signature = auth::sign(real_message, sk)?;
-    let forgery: Message = conf::encrypt(s.clone(), &pk.clone());
+    let forgery: Message = conf::encrypt(s.clone(), &pk.clone())?;
     let v: Verification = auth::verify(forgery,s,pk);
 
     match v {
         Verification::Accept => Err("forgery was accepted by verify".into()),
         Verification::Reject => Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn sign_and_verify_use_the_key_exponent() -> Result<(),String> {
+    for e in [3, 17, 65537] {
+        let (pk,sk) = keygen_with_exponent(512, e)?.split();
+        let m: Message = "signed with a chosen exponent".into();
+        let s: Signature = auth::sign(m.clone(), sk)?;
+        assert!(matches!(auth::verify(m, s, pk), Verification::Accept), "e = {e}");
+    }
+    Ok(())
+}
+
+#[test]
+fn sign_with_modulus_smaller_than_digest_fails() -> Result<(),String> {
+    let (_,sk) = keygen_insecure_for_tests(128)?.split();
+    assert!(matches!(auth::sign("too small", sk), Err(Error::MessageTooLong { modulus_bits: 128, .. })));
+    Ok(())
+}
+
+#[test]
+fn key_pair_signature_verifies_with_its_public_key() -> Result<(),String> {
+    let pair = keygen(512)?;
+    let m: Message = "signed by a key pair".into();
+    let s: Signature = pair.sign(m.clone())?;
+    assert!(matches!(auth::verify(m, s, pair.public().clone()), Verification::Accept));
+    Ok(())
+}
+
+#[test]
+fn three_prime_key_signature_verifies() -> Result<(),String> {
+    let (pk,sk) = keygen_multiprime(2048, 3)?.split();
+    let m: Message = "signed with three primes".into();
+    let s: Signature = auth::sign(m.clone(), sk)?;
+    assert!(matches!(auth::verify(m, s, pk), Verification::Accept));
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use rustnetworking::{big_num::BigUint, rsa::{keygen, keygen_multiprime, PublicKey, SecretKey, Error, binary::FormatError, confidentiality::{self as conf, Message}}};
+
+// Made with `openssl genrsa -traditional 1024`.
+const OPENSSL_SECRET_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pem");
+
+#[test]
+fn public_key_round_trips_through_bytes() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let bytes = pk.to_bytes();
+
+    assert_eq!(b"RNPK", &bytes[..4]);
+    assert_eq!(1, bytes[4]);
+    assert_eq!(512u32.to_be_bytes(), bytes[5..9]);
+    assert_eq!(9 + 8 + 64, bytes.len());
+    assert_eq!(pk, PublicKey::from_bytes(&bytes)?);
+    Ok(())
+}
+
+#[test]
+fn secret_key_round_trips_through_bytes() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let bytes = sk.to_bytes();
+
+    assert_eq!(b"RNSK", &bytes[..4]);
+    let decoded = SecretKey::from_bytes(&bytes)?;
+    assert_eq!(sk, decoded);
+    let m: Message = "binary round trip".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, decoded)?);
+    Ok(())
+}
+
+#[test]
+fn three_prime_secret_key_round_trips_through_bytes() -> Result<(),String> {
+    let (_,sk) = keygen_multiprime(2048, 3)?.split();
+    let decoded = SecretKey::from_bytes(&sk.to_bytes())?;
+    assert_eq!(3, decoded.prime_count());
+    assert_eq!(sk, decoded);
+    Ok(())
+}
+
+#[test]
+fn openssl_key_round_trips_through_bytes() -> Result<(),String> {
+    let sk = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    assert_eq!(OPENSSL_SECRET_KEY, SecretKey::from_bytes(&sk.to_bytes())?.to_pem());
+    Ok(())
+}
+
+#[test]
+fn wrong_magic_is_rejected() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    assert!(matches!(PublicKey::from_bytes(&sk.to_bytes()), Err(Error::Format(FormatError::WrongMagic { .. }))));
+    assert!(matches!(SecretKey::from_bytes(&pk.to_bytes()), Err(Error::Format(FormatError::WrongMagic { .. }))));
+    assert!(matches!(PublicKey::from_bytes(b"random bytes that are not a key"), Err(Error::Format(FormatError::WrongMagic { .. }))));
+    assert!(matches!(PublicKey::from_bytes(b""), Err(Error::Format(FormatError::WrongMagic { .. }))));
+    Ok(())
+}
+
+#[test]
+fn unknown_version_is_rejected() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let mut public = pk.to_bytes();
+    public[4] = 2;
+    let mut secret = sk.to_bytes();
+    secret[4] = 0;
+    assert!(matches!(PublicKey::from_bytes(&public), Err(Error::Format(FormatError::UnknownVersion { version: 2 }))));
+    assert!(matches!(SecretKey::from_bytes(&secret), Err(Error::Format(FormatError::UnknownVersion { version: 0 }))));
+    Ok(())
+}
+
+#[test]
+fn truncated_bytes_are_rejected_at_every_length() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let public = pk.to_bytes();
+    for length in 4..public.len() {
+        assert!(matches!(PublicKey::from_bytes(&public[..length]), Err(Error::Format(FormatError::Truncated { .. }))), "{length}");
+    }
+    let secret = sk.to_bytes();
+    for length in 4..secret.len() {
+        assert!(matches!(SecretKey::from_bytes(&secret[..length]), Err(Error::Format(FormatError::Truncated { .. }))), "{length}");
+    }
+    Ok(())
+}
+
+#[test]
+fn trailing_bytes_are_rejected() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let mut bytes = pk.to_bytes();
+    bytes.push(0);
+    assert!(matches!(PublicKey::from_bytes(&bytes), Err(Error::Format(FormatError::TrailingData { count: 1 }))));
+    Ok(())
+}
+
+#[test]
+fn wrong_bit_size_is_rejected() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let mut bytes = pk.to_bytes();
+    // 511 bits, which has the same width as 512.
+    bytes[5..9].copy_from_slice(&511u32.to_be_bytes());
+    assert!(matches!(PublicKey::from_bytes(&bytes), Err(Error::Format(FormatError::Inconsistent(_)))));
+    Ok(())
+}
+
+#[test]
+fn corrupted_prime_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let mut bytes = sk.to_bytes();
+    *bytes.last_mut().unwrap() ^= 2;
+    assert!(matches!(SecretKey::from_bytes(&bytes), Err(Error::InvalidKey(_))));
+    Ok(())
+}
+
+/// An `RNSK` blob of `e`, `n`, `d` and `primes` of the given width.
+fn secret_key_bytes(e: u64, n: &BigUint, d: &BigUint, primes: &[&BigUint], width: usize) -> Result<Vec<u8>,String> {
+    let mut bytes = b"RNSK".to_vec();
+    bytes.push(1);
+    bytes.extend_from_slice(&n.bit_size().to_be_bytes());
+    bytes.push(primes.len() as u8);
+    bytes.extend_from_slice(&(width as u16).to_be_bytes());
+    bytes.extend_from_slice(&e.to_be_bytes());
+    bytes.extend(n.to_bytes_be_padded(n.byte_size())?);
+    bytes.extend(d.to_bytes_be_padded(n.byte_size())?);
+    for prime in primes {
+        bytes.extend(prime.to_bytes_be_padded(width)?);
+    }
+    Ok(bytes)
+}
+
+#[test]
+fn bad_primes_are_rejected() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    // p = 1 and q = n pass the product check, but decrypting divides by p - 1.
+    let trivial = secret_key_bytes(pk.exponent(), pk.n(), sk.d(), &[&BigUint::one(), pk.n()], 64)?;
+    let result = SecretKey::from_bytes(&trivial);
+    assert!(matches!(&result, Err(Error::InvalidKey(reason)) if reason.contains("below 2")), "{result:?}");
+
+    let square = sk.p() * sk.p();
+    let repeated = secret_key_bytes(pk.exponent(), &square, sk.d(), &[sk.p(), sk.p()], sk.p().byte_size())?;
+    let result = SecretKey::from_bytes(&repeated);
+    assert!(matches!(&result, Err(Error::InvalidKey(reason)) if reason.contains("repeated")), "{result:?}");
+    Ok(())
+}
+
+#[test]
+fn oversized_d_and_trivial_e_are_rejected() -> Result<(),String> {
+    let sk = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    let pk = sk.public_key();
+    let (p, q) = (sk.p(), sk.q());
+    let width = p.byte_size().max(q.byte_size());
+
+    // d + k λ(n) still has d e = 1 modulo λ(n), but no longer fits the width of n when it is written back.
+    let lambda = (p - 1u32)?.lcm(&(q - 1u32)?);
+    let mut oversized = sk.d().clone();
+    while &oversized < pk.n() {
+        oversized = &oversized + &lambda;
+    }
+    assert_eq!(pk.n().byte_size(), oversized.byte_size());
+    let result = SecretKey::from_bytes(&secret_key_bytes(pk.exponent(), pk.n(), &oversized, &[p, q], width)?);
+    assert!(matches!(&result, Err(Error::InvalidKey(reason)) if reason.contains("d is not below n")), "{result:?}");
+
+    // e = d = 1 has d e = 1 modulo anything, and encrypts to the message itself.
+    let result = SecretKey::from_bytes(&secret_key_bytes(1, pk.n(), &BigUint::one(), &[p, q], width)?);
+    assert!(matches!(&result, Err(Error::InvalidKey(reason)) if reason.contains("e is less than 3")), "{result:?}");
+    Ok(())
+}
@@ -2,7 +2,9 @@
 
 extern crate test;
 use test::Bencher;
-use rustnetworking::rsa::{confidentiality as conf, keygen};
+use std::time::{Duration, Instant};
+use rand::{SeedableRng, rngs::StdRng};
+use rustnetworking::rsa::{confidentiality as conf, keygen, KeyPair, PublicKey, keygen_with_rng, keygen_with_exponent, keygen_with_max_attempts, keygen_with_options, KeygenOptions, keygen_multiprime, keygen_insecure_for_tests, keygen_background, keygen_with_progress, KeygenEvent, RejectionReason, validate_keypair, KeygenError, Error, DEFAULT_E, MIN_KEY_BITS, MIN_MULTI_PRIME_BITS};
 
 #[test]
 fn test_keygen_doesnt_give_err() -> Result<(),String> {
@@ -12,11 +14,11 @@ fn test_keygen_doesnt_give_err() -> Result<(),String> {
 
 #[test]
 fn test_decrypted_cipher_gives_original_plaintext() -> Result<(),String> {
-    let plaintext = format!("this is a test");
+    let plaintext = "this is a test".to_string();
     let plaintext_bytes = dbg!(plaintext.clone().into_bytes());
 
-    let (pk,sk) = keygen(2048)?;
-    let cipher = dbg!(conf::encrypt(plaintext_bytes, &pk));
+    let (pk,sk) = keygen(2048)?.split();
+    let cipher = dbg!(conf::encrypt(plaintext_bytes, &pk)?);
 
     let res_bytes = dbg!(conf::decrypt(cipher, sk)?);
     let res = String::from_utf8(res_bytes).map_err(|x|x.to_string())?;
@@ -28,4 +30,377 @@ fn test_decrypted_cipher_gives_original_plaintext() -> Result<(),String> {
 #[bench]
 fn bench_generation(b: &mut Bencher) {
     b.iter(|| keygen(2048))
-}
\ No newline at end of file
+}
+#[bench]
+fn bench_generation_small_exponent(b: &mut Bencher) {
+    b.iter(|| keygen_with_exponent(2048, 3))
+}
+#[bench]
+fn bench_decryption(b: &mut Bencher) {
+    let (pk,sk) = keygen(2048).unwrap().split();
+    let cipher = conf::encrypt("this is a test", &pk).unwrap();
+    b.iter(|| conf::decrypt(cipher.clone(), sk.clone()))
+}
+#[bench]
+fn bench_decryption_three_primes(b: &mut Bencher) {
+    let (pk,sk) = keygen_multiprime(2048, 3).unwrap().split();
+    let cipher = conf::encrypt("this is a test", &pk).unwrap();
+    b.iter(|| conf::decrypt(cipher.clone(), sk.clone()))
+}
+
+#[test]
+fn test_keygen_gives_modulus_of_requested_bit_size() -> Result<(),String> {
+    for bit_size in [512, 513, 1024] {
+        let (pk,_) = keygen(bit_size)?.split();
+        assert_eq!(bit_size, pk.bit_size());
+    }
+    Ok(())
+}
+
+#[test]
+fn keygen_always_gives_exactly_the_requested_bit_size() -> Result<(),String> {
+    for _ in 0..5 {
+        let (pk,_) = keygen(1024)?.split();
+        assert_eq!(1024, pk.bit_size());
+    }
+    for bit_size in 60..70 {
+        let (pk,_) = keygen_insecure_for_tests(bit_size)?.split();
+        assert_eq!(bit_size, pk.bit_size());
+    }
+    for (bit_size, prime_count) in [(1536, 3), (1537, 3)] {
+        let (pk,_) = keygen_multiprime(bit_size, prime_count)?.split();
+        assert_eq!(bit_size, pk.bit_size(), "{prime_count} primes");
+    }
+    Ok(())
+}
+
+#[test]
+fn public_key_byte_size_covers_modulus() -> Result<(),String> {
+    for (bit_size, byte_size) in [(520, 65), (513, 65), (512, 64)] {
+        let (pk,_) = keygen(bit_size)?.split();
+        assert_eq!(byte_size, pk.byte_size(), "{bit_size} bit key");
+    }
+    Ok(())
+}
+
+#[test]
+fn keygen_gives_up_after_max_attempts() {
+    // Keys below MIN_KEY_BITS are refused before any attempt, so only zero attempts are sure to fail.
+    assert!(matches!(keygen_with_max_attempts(512, 0), Err(Error::KeyGeneration(KeygenError::TooManyAttempts { attempts: 0 }))));
+}
+
+#[test]
+fn keygen_with_max_attempts_succeeds_for_normal_sizes() -> Result<(),String> {
+    let (pk,sk) = keygen_with_max_attempts(512, 10)?.split();
+    let m = "bounded attempts".as_bytes().to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn encryption_round_trips_for_each_exponent() -> Result<(),String> {
+    for e in [3, 17, 65537] {
+        let (pk,sk) = keygen_with_exponent(512, e)?.split();
+        assert_eq!(e, pk.exponent());
+        let m = "chosen exponent".as_bytes().to_vec();
+        assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?, "e = {e}");
+    }
+    Ok(())
+}
+
+#[test]
+fn keygen_defaults_to_65537() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    assert_eq!(65537, DEFAULT_E);
+    assert_eq!(DEFAULT_E, pk.exponent());
+    Ok(())
+}
+
+#[test]
+fn keygen_rejects_even_and_tiny_exponents() {
+    for e in [0, 1, 2, 4, 65536] {
+        let result = keygen_with_exponent(512, e);
+        assert!(matches!(result, Err(Error::KeyGeneration(KeygenError::InvalidExponent { e: got })) if got == e), "e = {e}");
+    }
+}
+
+#[test]
+fn encrypt_rejects_messages_not_below_the_modulus() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let m = vec![0xff; pk.byte_size()];
+    assert!(matches!(conf::encrypt(m, &pk), Err(Error::MessageTooLong { message_bits: 512, modulus_bits: 512 })));
+    Ok(())
+}
+
+#[test]
+fn decrypt_rejects_ciphertexts_not_below_the_modulus() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let c = vec![0xff; pk.byte_size() + 1];
+    assert!(matches!(conf::decrypt(c, sk), Err(Error::MessageTooLong { message_bits: 520, modulus_bits: 512 })));
+    Ok(())
+}
+
+#[test]
+fn rsa_error_converts_into_string_error() {
+    fn f() -> Result<(),String> {
+        keygen_with_exponent(512, 4)?;
+        Ok(())
+    }
+
+    assert!(f().is_err());
+}
+
+#[test]
+fn public_key_derived_from_secret_key_matches_keygen() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let derived = sk.public_key();
+    assert_eq!(format!("{pk:?}"), format!("{derived:?}"));
+    assert_eq!(pk.fingerprint(), PublicKey::from(&sk).fingerprint());
+
+    let m = "derived public key".as_bytes().to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &derived)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn keygen_with_same_seed_gives_same_keys() -> Result<(),String> {
+    // StdRng is ChaCha12, so a seeded one replays the same stream.
+    let (pk_a,sk_a) = keygen_with_rng(512, &mut StdRng::seed_from_u64(7))?.split();
+    let (pk_b,sk_b) = keygen_with_rng(512, &mut StdRng::seed_from_u64(7))?.split();
+    assert_eq!(format!("{pk_a:?}"), format!("{pk_b:?}"));
+    assert_eq!(sk_a.debug_unsafe_full(), sk_b.debug_unsafe_full());
+    Ok(())
+}
+
+#[test]
+fn keygen_with_different_seeds_gives_different_keys() -> Result<(),String> {
+    let (pk_a,_) = keygen_with_rng(512, &mut StdRng::seed_from_u64(7))?.split();
+    let (pk_b,_) = keygen_with_rng(512, &mut StdRng::seed_from_u64(8))?.split();
+    assert_ne!(pk_a.fingerprint(), pk_b.fingerprint());
+    Ok(())
+}
+
+#[test]
+fn keygen_with_rng_gives_working_keys() -> Result<(),String> {
+    let (pk,sk) = keygen_with_rng(512, &mut StdRng::seed_from_u64(7))?.split();
+    let m = "seeded key".as_bytes().to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn key_pair_decrypts_with_its_secret_key() -> Result<(),String> {
+    let pair = keygen(512)?;
+    let m = "key pair".as_bytes().to_vec();
+    let c = conf::encrypt(m.clone(), pair.public())?;
+    assert_eq!(m, pair.decrypt(c.clone())?);
+    assert_eq!(m, conf::decrypt(c, pair.secret().clone())?);
+    Ok(())
+}
+
+#[test]
+fn key_pair_from_secret_key_has_the_same_public_key() -> Result<(),String> {
+    let pair = keygen(512)?;
+    let rebuilt = KeyPair::from(pair.secret().clone());
+    assert_eq!(pair.public().fingerprint(), rebuilt.public().fingerprint());
+    Ok(())
+}
+
+#[test]
+fn three_prime_key_decrypts_what_its_public_key_encrypts() -> Result<(),String> {
+    let (pk,sk) = keygen_multiprime(2048, 3)?.split();
+    assert_eq!(2048, pk.bit_size());
+    assert_eq!(3, sk.prime_count());
+
+    let m = "three primes".as_bytes().to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn keygen_multiprime_with_two_primes_is_keygen() -> Result<(),String> {
+    let (pk,sk) = keygen_multiprime(512, 2)?.split();
+    assert_eq!(512, pk.bit_size());
+    assert_eq!(2, sk.prime_count());
+    Ok(())
+}
+
+#[test]
+fn keygen_multiprime_rejects_too_few_or_too_small_primes() {
+    for (bit_size, prime_count) in [(2048, 0), (2048, 1), (2048, 5), (3 * MIN_MULTI_PRIME_BITS - 1, 3)] {
+        assert!(matches!(keygen_multiprime(bit_size, prime_count),
+            Err(Error::KeyGeneration(KeygenError::InvalidPrimeCount { prime_count: c, bit_size: s })) if c == prime_count && s == bit_size),
+            "{prime_count} primes of {bit_size} bits");
+    }
+}
+
+#[test]
+fn keygen_rejects_keys_below_the_minimum_size() {
+    for bit_size in [0, 8, 64, MIN_KEY_BITS - 1] {
+        assert!(matches!(keygen(bit_size), Err(Error::KeyTooSmall { requested, minimum: MIN_KEY_BITS }) if requested == bit_size),
+            "{bit_size} bit key");
+    }
+    assert!(matches!(keygen_with_rng(256, &mut StdRng::seed_from_u64(1)), Err(Error::KeyTooSmall { requested: 256, .. })));
+    assert!(matches!(keygen_multiprime(256, 2), Err(Error::KeyTooSmall { requested: 256, .. })));
+}
+
+#[test]
+fn key_too_small_error_names_both_sizes() {
+    let err = keygen(64).map(|_| ()).unwrap_err();
+    assert_eq!("a 64 bit key is too small, the minimum is 512 bits", err.to_string());
+}
+
+#[test]
+fn keygen_insecure_for_tests_makes_small_working_keys() -> Result<(),String> {
+    for (bit_size, byte_size) in [(128, 16), (505, 64), (504, 63)] {
+        let (pk,sk) = keygen_insecure_for_tests(bit_size)?.split();
+        assert_eq!(bit_size, pk.bit_size());
+        assert_eq!(byte_size, pk.byte_size(), "{bit_size} bit key");
+
+        let m = vec![0x2a];
+        assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn cloned_secret_key_still_decrypts_after_the_original_is_dropped() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let clone = sk.clone();
+    drop(sk);
+
+    let m = "outlives the original".as_bytes().to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, clone)?);
+    Ok(())
+}
+
+#[test]
+fn decrypting_repeatedly_gives_the_same_plaintext() -> Result<(),String> {
+    let pair = keygen(512)?;
+    let c = conf::encrypt("decrypted twice", pair.public())?;
+    let first = pair.decrypt(c.clone())?;
+    let second = pair.decrypt(c)?;
+    assert_eq!(first, second);
+    assert_eq!("decrypted twice".as_bytes(), first.as_slice());
+    Ok(())
+}
+
+#[test]
+fn cancelled_background_keygen_returns_promptly() {
+    let handle = keygen_background(4096);
+    std::thread::sleep(Duration::from_millis(50));
+    let start = Instant::now();
+    handle.cancel();
+    let result = handle.wait();
+
+    assert!(matches!(result, Err(Error::KeyGeneration(KeygenError::Cancelled))));
+    assert!(start.elapsed() < Duration::from_secs(5), "took {:?} to stop", start.elapsed());
+}
+
+#[test]
+fn dropping_a_background_keygen_does_not_wait_for_it() {
+    let start = Instant::now();
+    drop(keygen_background(4096));
+    assert!(start.elapsed() < Duration::from_secs(1), "took {:?} to drop", start.elapsed());
+}
+
+#[test]
+fn background_keygen_gives_a_valid_key_pair() -> Result<(),String> {
+    let (pk,sk) = keygen_background(512).wait()?.split();
+    assert_eq!(512, pk.bit_size());
+    validate_keypair(&pk, &sk)?;
+    Ok(())
+}
+
+#[test]
+fn background_keygen_result_is_received_once() -> Result<(),String> {
+    let mut handle = keygen_background(512);
+    let pair = loop {
+        match handle.try_recv() {
+            Some(result) => break result?,
+            None => std::thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    assert!(handle.try_recv().is_none());
+
+    let m = "generated in the background".as_bytes().to_vec();
+    assert_eq!(m, pair.decrypt(conf::encrypt(m.clone(), pair.public())?)?);
+    Ok(())
+}
+
+#[test]
+fn background_keygen_of_too_small_key_fails() {
+    assert!(matches!(keygen_background(64).wait(), Err(Error::KeyTooSmall { requested: 64, .. })));
+}
+
+#[test]
+fn keygen_with_strong_primes_gives_working_keys() -> Result<(),String> {
+    let options = KeygenOptions { strong_primes: true, ..Default::default() };
+    let (pk,sk) = keygen_with_options(1024, &options)?.split();
+    assert_eq!(1024, pk.bit_size());
+    validate_keypair(&pk, &sk)?;
+
+    let m = "strong primes".as_bytes().to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn keygen_options_default_to_keygen() {
+    let options = KeygenOptions::default();
+    assert_eq!(DEFAULT_E, options.e);
+    assert!(!options.strong_primes);
+}
+
+#[test]
+fn keygen_with_options_uses_the_exponent_and_attempts() -> Result<(),String> {
+    let (pk,_) = keygen_with_options(512, &KeygenOptions { e: 3, ..Default::default() })?.split();
+    assert_eq!(3, pk.exponent());
+    assert!(matches!(keygen_with_options(512, &KeygenOptions { max_attempts: 0, ..Default::default() }),
+        Err(Error::KeyGeneration(KeygenError::TooManyAttempts { attempts: 0 }))));
+    Ok(())
+}
+
+#[test]
+fn keygen_with_progress_reports_candidates_and_completion() -> Result<(),String> {
+    let mut events = Vec::new();
+    let (pk,sk) = keygen_with_progress(512, |event| events.push(event))?.split();
+    validate_keypair(&pk, &sk)?;
+
+    let tested = |index| events.iter().filter(|event| **event == KeygenEvent::CandidateTested { prime: index }).count();
+    assert!(tested(0) >= 1 && tested(1) >= 1, "{events:?}");
+    let composites = events.iter().filter(|event| **event == KeygenEvent::Rejected { reason: RejectionReason::Composite }).count();
+    assert!(composites < tested(0) + tested(1));
+    assert!(matches!(events.last(), Some(KeygenEvent::Completed { attempts: 1.. })), "{:?}", events.last());
+    Ok(())
+}
+
+#[test]
+fn keygen_progress_is_reported_on_the_calling_thread() -> Result<(),String> {
+    let caller = std::thread::current().id();
+    let mut elsewhere = 0;
+    keygen_with_progress(512, |_| if std::thread::current().id() != caller { elsewhere += 1 })?;
+    assert_eq!(0, elsewhere);
+    Ok(())
+}
+
+#[test]
+fn keygen_with_progress_of_too_small_key_fails_without_events() {
+    let mut events = 0;
+    assert!(matches!(keygen_with_progress(64, |_| events += 1), Err(Error::KeyTooSmall { .. })));
+    assert_eq!(0, events);
+}
+
+#[test]
+fn rejection_reasons_cover_every_rejection() {
+    // Exhaustive, so a new way of rejecting candidates has to be added here.
+    let describe = |reason| match reason {
+        RejectionReason::Composite => "composite",
+        RejectionReason::TooLarge => "too large",
+        RejectionReason::GcdFailure => "gcd failure",
+        RejectionReason::TooClose => "too close",
+    };
+    let reasons = [RejectionReason::Composite, RejectionReason::TooLarge, RejectionReason::GcdFailure, RejectionReason::TooClose];
+    let names: std::collections::HashSet<_> = reasons.into_iter().map(describe).collect();
+    assert_eq!(4, names.len());
+}
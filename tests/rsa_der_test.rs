@@ -0,0 +1,61 @@
+use rustnetworking::rsa::{keygen, PublicKey, Error};
+
+// `openssl rsa -RSAPublicKey_in -pubout -outform DER` of the PEM fixture, which
+// `openssl rsa -pubin -inform DER -text` reads as a 1024 bit key with exponent 65537.
+const OPENSSL_PUBLIC_KEY_PEM: &str = include_str!("fixtures/openssl_rsa_1024.pub.pem");
+const OPENSSL_PUBLIC_KEY_DER: &[u8] = include_bytes!("fixtures/openssl_rsa_1024.spki.der");
+
+#[test]
+fn public_key_round_trips_through_der() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let decoded = PublicKey::from_der(&pk.to_der())?;
+    assert_eq!(format!("{pk:?}"), format!("{decoded:?}"));
+    Ok(())
+}
+
+#[test]
+fn der_matches_openssl() -> Result<(),String> {
+    let pk = PublicKey::from_pem(OPENSSL_PUBLIC_KEY_PEM)?;
+    assert_eq!(OPENSSL_PUBLIC_KEY_DER, pk.to_der());
+    assert_eq!(format!("{pk:?}"), format!("{:?}", PublicKey::from_der(OPENSSL_PUBLIC_KEY_DER)?));
+    Ok(())
+}
+
+#[test]
+fn der_starts_with_the_rsa_encryption_algorithm() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let der = pk.to_der();
+    let algorithm = [0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01, 0x05, 0x00];
+    assert_eq!(&algorithm, &der[2..17]);
+    Ok(())
+}
+
+#[test]
+fn der_with_trailing_garbage_is_rejected() {
+    let mut der = OPENSSL_PUBLIC_KEY_DER.to_vec();
+    der.push(0);
+    assert!(matches!(PublicKey::from_der(&der), Err(Error::Encoding(_))));
+}
+
+#[test]
+fn truncated_der_is_rejected() {
+    let der = &OPENSSL_PUBLIC_KEY_DER[..OPENSSL_PUBLIC_KEY_DER.len() - 1];
+    assert!(matches!(PublicKey::from_der(der), Err(Error::Encoding(_))));
+}
+
+#[test]
+fn der_of_another_algorithm_is_rejected() {
+    let mut der = OPENSSL_PUBLIC_KEY_DER.to_vec();
+    // The last byte of the object identifier, making it 1.2.840.113549.1.1.2.
+    der[14] = 0x02;
+    assert!(matches!(PublicKey::from_der(&der), Err(Error::Encoding(_))));
+}
+
+#[test]
+fn bit_string_with_unused_bits_is_rejected() {
+    let mut der = OPENSSL_PUBLIC_KEY_DER.to_vec();
+    // The byte after the bit string's tag and two byte length counts its unused bits.
+    assert_eq!([0x03, 0x81, 0x8d, 0x00], der[18..22]);
+    der[21] = 1;
+    assert!(matches!(PublicKey::from_der(&der), Err(Error::Encoding(_))));
+}
@@ -0,0 +1,46 @@
+use rustnetworking::rsa::{keygen, keygen_with_exponent, PublicKey};
+
+const OPENSSL_PUBLIC_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pub.pem");
+
+#[test]
+fn fingerprint_of_fixed_key_is_pinned() -> Result<(),String> {
+    // SHA-256 of e = 65537 as 8 big endian bytes followed by the 128 bytes of n.
+    let pk = PublicKey::from_pem(OPENSSL_PUBLIC_KEY)?;
+    assert_eq!("8a155be7e9fc2fbd55cb08607d58f2b75c3c6d2315fecea1d1e03750a7e0997c", pk.fingerprint_hex());
+    Ok(())
+}
+
+#[test]
+fn fingerprint_hex_matches_fingerprint() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let hex = pk.fingerprint_hex();
+    assert_eq!(64, hex.len());
+    for (i, byte) in pk.fingerprint().iter().enumerate() {
+        assert_eq!(format!("{byte:02x}"), hex[2 * i..2 * i + 2]);
+    }
+    Ok(())
+}
+
+#[test]
+fn fingerprint_survives_serialization() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    assert_eq!(pk.fingerprint(), PublicKey::from_pem(&pk.to_pem())?.fingerprint());
+    assert_eq!(pk.fingerprint(), PublicKey::from_jwk(&pk.to_jwk())?.fingerprint());
+    Ok(())
+}
+
+#[test]
+fn different_keys_have_different_fingerprints() -> Result<(),String> {
+    let (a,_) = keygen(512)?.split();
+    let (b,_) = keygen(512)?.split();
+    assert_ne!(a.fingerprint(), b.fingerprint());
+    Ok(())
+}
+
+#[test]
+fn fingerprint_depends_on_the_exponent() -> Result<(),String> {
+    let (pk,_) = keygen_with_exponent(512, 3)?.split();
+    let jwk = pk.to_jwk().replace(r#""e":"Aw""#, r#""e":"AQAB""#);
+    assert_ne!(pk.fingerprint(), PublicKey::from_jwk(&jwk)?.fingerprint());
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use rustnetworking::{big_num::BigUint, modular::phi_from_primes, rsa::{keygen, validate_keypair, ExponentConvention, PublicKey, SecretKey, Error, confidentiality as conf}};
+
+// The primes of tests/fixtures/openssl_rsa_1024.pem, from `openssl rsa -text`.
+const OPENSSL_P: &str = "f790b60e24f4b93ab67a788afbe73d7c723ed65ad88c1b63b5fc7520157e67032363e78ed955594a9b4099169a71e2c4a37d69b85c791dd12da8b9004bb66101";
+const OPENSSL_Q: &str = "ebff7d5352ce7732c2013562bba669c474adb9d957e586c50a7509578d89bd099e9bfa16370c3d127a79681a8fd43c46211a0d7cbf9badd36baf9ffa93073a1d";
+// Its modulus and private exponent, from the same output.
+const OPENSSL_N: &str = "e438e97690e7687742810123527587c9e7522d94884f764df0d98c0f04d28d63691aad01f261506f7134d33f9da8a171dc18c8e510217f6419cdb530018e0e80a6b4bdda07f00ae6a406ce8ca3da8320db3cd03d578267e96fd2a720131e5ad4bb3236cceb80e7b0a84bdd2a53625aebd54b3941f2695d3d918c2bda1faa371d";
+const OPENSSL_D: &str = "8514cacee0e090023b3d8eb4523946dd6d8e7615cf06c72ddbec7fc7a24045425adfbb530f4bbfe3bc57d977972fe634c6eb8cfa45d5c2bb5470a7df3e88dfdafb602b7ef253b57242f9f623b6177b7fb4afb7279f2820a8a39c3c613c3b37e69461cd56586882bc01141cf9c60738c39d41b53e571d2969d212263d1956e001";
+const OPENSSL_SECRET_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pem");
+const OPENSSL_PUBLIC_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pub.pem");
+// Made with `openssl pkeyutl -encrypt -pkeyopt rsa_padding_mode:none` of the message below, zero padded to 128 bytes.
+const OPENSSL_CIPHERTEXT: &[u8] = include_bytes!("fixtures/openssl_rsa_1024.raw_ciphertext.bin");
+
+fn invalid_key_reason(result: Result<SecretKey,Error>) -> String {
+    match result {
+        Err(Error::InvalidKey(reason)) => reason,
+        Err(err) => panic!("expected an invalid key error, got {err}"),
+        Ok(_) => panic!("expected an invalid key error, the key was accepted"),
+    }
+}
+
+#[test]
+fn textbook_key_gives_the_known_answers() -> Result<(),String> {
+    // p = 61, q = 53, e = 17 gives n = 3233 and d = 2753, under which 65 encrypts to 2790.
+    let sk = SecretKey::from_primes_with_exponent(BigUint::from(61u32), BigUint::from(53u32), 17)?;
+    let pk = sk.public_key();
+    assert_eq!(12, pk.bit_size());
+    assert_eq!(17, pk.exponent());
+
+    assert_eq!(vec![0x0a, 0xe6], conf::encrypt(vec![65], &pk)?);
+    assert_eq!(vec![65], conf::decrypt(vec![0x0a, 0xe6], sk)?);
+    Ok(())
+}
+
+#[test]
+fn public_key_from_parts_encrypts_like_the_textbook() -> Result<(),String> {
+    let pk = PublicKey::from_parts(BigUint::from(3233u32), 17);
+    assert_eq!(vec![0x0a, 0xe6], conf::encrypt(vec![65], &pk)?);
+    Ok(())
+}
+
+#[test]
+fn openssl_primes_decrypt_an_openssl_ciphertext() -> Result<(),String> {
+    let sk = SecretKey::from_primes(BigUint::from_hex(OPENSSL_P)?, BigUint::from_hex(OPENSSL_Q)?)?;
+    let pk = PublicKey::from_pem(OPENSSL_PUBLIC_KEY)?;
+    assert_eq!(pk.to_pem(), sk.public_key().to_pem());
+    validate_keypair(&pk, &sk)?;
+
+    assert_eq!(b"decrypted from an openssl ciphertext".to_vec(), conf::decrypt(OPENSSL_CIPHERTEXT, sk)?);
+    Ok(())
+}
+
+#[test]
+fn from_primes_rejects_bad_primes() {
+    let (p, q) = (BigUint::from(61u32), BigUint::from(53u32));
+    assert_eq!("p is not prime", invalid_key_reason(SecretKey::from_primes_with_exponent(BigUint::from(63u32), q.clone(), 17)));
+    assert_eq!("q is not prime", invalid_key_reason(SecretKey::from_primes_with_exponent(p.clone(), BigUint::from(1u32), 17)));
+    assert_eq!("q is repeated", invalid_key_reason(SecretKey::from_primes_with_exponent(p.clone(), p.clone(), 17)));
+    // φ(3233) = 3120 is divisible by 3.
+    assert_eq!("e is not coprime to φ(n)", invalid_key_reason(SecretKey::from_primes_with_exponent(p.clone(), q.clone(), 3)));
+    assert_eq!("e is even or less than 3", invalid_key_reason(SecretKey::from_primes_with_exponent(p, q, 16)));
+}
+
+#[test]
+fn from_primes_rejects_close_primes() -> Result<(),String> {
+    let p = BigUint::from_hex(OPENSSL_P)?;
+    let q = (&p + BigUint::two_pow(20)).next_prime();
+    assert!(invalid_key_reason(SecretKey::from_primes(p, q)).starts_with("p and q are too close"));
+    Ok(())
+}
+
+#[test]
+fn openssl_key_has_euler_d_but_otherwise_matches_the_primes() -> Result<(),String> {
+    // `openssl genrsa` takes d modulo φ(n), so only d differs from the key of the same primes.
+    let openssl = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    let sk = SecretKey::from_primes(BigUint::from_hex(OPENSSL_P)?, BigUint::from_hex(OPENSSL_Q)?)?;
+    assert_eq!(ExponentConvention::Euler, openssl.exponent_convention());
+    assert_eq!(ExponentConvention::Carmichael, sk.exponent_convention());
+    assert_eq!(openssl.public_key(), sk.public_key());
+    assert_ne!(openssl, sk);
+    Ok(())
+}
+
+#[test]
+fn generated_keys_use_carmichael() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    assert_eq!(ExponentConvention::Carmichael, sk.exponent_convention());
+    Ok(())
+}
+
+#[test]
+fn old_euler_d_and_new_carmichael_d_decrypt_the_same() -> Result<(),String> {
+    let sk = SecretKey::from_primes(BigUint::from_hex(OPENSSL_P)?, BigUint::from_hex(OPENSSL_Q)?)?;
+    // Like a key stored before d was taken modulo λ(n).
+    let phi = phi_from_primes(&[BigUint::from_hex(OPENSSL_P)?, BigUint::from_hex(OPENSSL_Q)?]);
+    let mut json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    json["d"] = serde_json::to_value(BigUint::from(65537u32).modinv(&phi)?).map_err(|err| err.to_string())?;
+    let old: SecretKey = serde_json::from_value(json).map_err(|err| err.to_string())?;
+    assert_eq!(ExponentConvention::Euler, old.exponent_convention());
+    assert_ne!(sk, old);
+
+    let m = b"decrypted from an openssl ciphertext".to_vec();
+    assert_eq!(m, conf::decrypt(OPENSSL_CIPHERTEXT, old)?);
+    assert_eq!(m, conf::decrypt(OPENSSL_CIPHERTEXT, sk)?);
+    Ok(())
+}
+
+#[test]
+fn key_rebuilt_from_its_components_still_decrypts() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let rebuilt_pk = PublicKey::new(pk.n().clone(), pk.e());
+    let rebuilt_sk = SecretKey::from_primes_with_exponent(sk.p().clone(), sk.q().clone(), pk.e())?;
+
+    assert_eq!(pk, rebuilt_pk);
+    assert_eq!(sk.d(), rebuilt_sk.d());
+    assert_eq!([sk.p().clone(), sk.q().clone()], sk.primes());
+    assert_eq!(*pk.n(), sk.p() * sk.q());
+
+    let m = b"rebuilt from components".to_vec();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &rebuilt_pk)?, rebuilt_sk)?);
+    Ok(())
+}
+
+#[test]
+fn textbook_components_give_the_known_answers() -> Result<(),String> {
+    let sk = SecretKey::from_components(BigUint::from(3233u32), 17, BigUint::from(2753u32))?;
+    assert_eq!(&BigUint::from(61u32), sk.p());
+    assert_eq!(&BigUint::from(53u32), sk.q());
+    // 2753 is the inverse of 17 modulo φ(3233) = 3120, and it is kept instead of the 413 of λ(3233) = 780.
+    assert_eq!(ExponentConvention::Euler, sk.exponent_convention());
+    assert_eq!(vec![65], conf::decrypt(vec![0x0a, 0xe6], sk)?);
+    Ok(())
+}
+
+#[test]
+fn openssl_components_decrypt_an_openssl_ciphertext() -> Result<(),String> {
+    let sk = SecretKey::from_components(BigUint::from_hex(OPENSSL_N)?, 65537, BigUint::from_hex(OPENSSL_D)?)?;
+    validate_keypair(&PublicKey::from_pem(OPENSSL_PUBLIC_KEY)?, &sk)?;
+    assert_eq!(b"decrypted from an openssl ciphertext".to_vec(), conf::decrypt(OPENSSL_CIPHERTEXT, sk.clone())?);
+
+    // The primes and d are found as they are, so the key is exported exactly as openssl wrote it.
+    assert_eq!(&BigUint::from_hex(OPENSSL_P)?, sk.p());
+    assert_eq!(&BigUint::from_hex(OPENSSL_Q)?, sk.q());
+    assert_eq!(OPENSSL_SECRET_KEY, sk.to_pem());
+    Ok(())
+}
+
+#[test]
+fn components_of_generated_keys_give_the_key_back() -> Result<(),String> {
+    for _ in 0..5 {
+        let (_,sk) = keygen(512)?.split();
+        let recovered = SecretKey::from_components(sk.public_key().n().clone(), sk.public_key().exponent(), sk.d().clone())?;
+        // from_components puts the larger prime first, which keygen need not.
+        assert!(recovered.p() > recovered.q());
+        assert_eq!((sk.p().max(sk.q()), sk.p().min(sk.q())), (recovered.p(), recovered.q()));
+        assert_eq!(sk.d(), recovered.d());
+        assert_eq!(sk.public_key(), recovered.public_key());
+    }
+    Ok(())
+}
+
+#[test]
+fn from_components_rejects_a_wrong_d() -> Result<(),String> {
+    let n = BigUint::from_hex(OPENSSL_N)?;
+    let d = BigUint::from_hex(OPENSSL_D)?;
+    let reason = invalid_key_reason(SecretKey::from_components(n.clone(), 65537, &d + 2u32));
+    assert!(reason.contains("can't be factored"), "{reason}");
+    assert_eq!("d is not below n", invalid_key_reason(SecretKey::from_components(n.clone(), 65537, n.clone())));
+    assert_eq!("e is even or less than 3", invalid_key_reason(SecretKey::from_components(n, 65536, d)));
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use rustnetworking::rsa::{keygen, keygen_multiprime, PublicKey, SecretKey, Error, confidentiality::{self as conf, Message}};
+
+// Made with Node.js: `crypto.generateKeyPairSync('rsa', {modulusLength: 1024})` exported with `{format: 'jwk'}`.
+const NODE_SECRET_KEY: &str = r#"{"kty":"RSA","n":"1ivncDfsDuA10iySjZHogbOSO-ki-MfGlYlqKWJpuGXNdnppR0BO5mZeCP32_N_F_7TJghcxhBYO8J6rCNx-ds5OJYnlgfy8hUwOGzvyzRmLeRMmNASOSI8vwn9otzz3IHLVTgrTzPC3lFPxozQ5bW2cWRpBX1O9hNvwYqld9AE","e":"AQAB","d":"w0GxtcB2KTXGO8BBOGpuxFjXmGE7bf6MkOZ_8Az1_5RolnyRoNEDXdC0KJSJahAFbtP-5uiITzjhFAREQEbtz21qmUQCKsU_64UkPxpM8zDriDvI0lFltatgYp_t_LwMtSaSEPWn8_zf1Y8AUMVRe63kHVlBvdhf7P4QPxnwaaU","p":"9UwhvWJKVGzZtKhFK6cJrqVqHQ0Kz6QJsMjg2G89w2cyoFECSzcmnZFR1ohXLYnpfGEXnFb64c5m4pmTvb3_cw","q":"34Qb9hwcFH6BkV_p8RqZj9zufchPb-npPagbE8dwxLZIpnHOPKQTkMIitAXzfsTMBlACZTgFJ79SJtpwUw15uw","dp":"oLsNFKpWKDHXCXCFOpBOYNjUykpkfiBvlR5bbpadsu9pFfFe_mgZ7n9tT5SxAw7uJmj8bp3z8IgtlGerjlX0Dw","dq":"IwTzzI-C5sMbUceRQ7FDNgMLarGjoIkpffuEooiw5mEY6NsT0PR0DE4eWc5E5Cu5O5P1jB5G0H9tQ6Ed0CVgWQ","qi":"KfTYlhtXnkChl6AdXTffQXDJenlOxWGRIoeljdeKD-B-HTM-cPB1weUBFwxSDmYLaLKdPHP5W8mGW1Isu9jxiw"}"#;
+const NODE_PUBLIC_KEY: &str = r#"{"kty":"RSA","n":"1ivncDfsDuA10iySjZHogbOSO-ki-MfGlYlqKWJpuGXNdnppR0BO5mZeCP32_N_F_7TJghcxhBYO8J6rCNx-ds5OJYnlgfy8hUwOGzvyzRmLeRMmNASOSI8vwn9otzz3IHLVTgrTzPC3lFPxozQ5bW2cWRpBX1O9hNvwYqld9AE","e":"AQAB"}"#;
+
+fn encoding_error<T>(result: Result<T,Error>) -> String {
+    match result {
+        Err(Error::Encoding(reason)) => reason,
+        Err(err) => panic!("expected an encoding error, got {err}"),
+        Ok(_) => panic!("expected an encoding error, got a key"),
+    }
+}
+
+#[test]
+fn public_key_round_trips_through_jwk() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let decoded = PublicKey::from_jwk(&pk.to_jwk())?;
+    assert_eq!(format!("{pk:?}"), format!("{decoded:?}"));
+    Ok(())
+}
+
+#[test]
+fn secret_key_round_trips_through_jwk() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let decoded = SecretKey::from_jwk(&sk.to_jwk())?;
+
+    let m: Message = "jwk round trip".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, decoded)?);
+    Ok(())
+}
+
+#[test]
+fn node_keys_are_imported_and_exported_unchanged() -> Result<(),String> {
+    let pk = PublicKey::from_jwk(NODE_PUBLIC_KEY)?;
+    let sk = SecretKey::from_jwk(NODE_SECRET_KEY)?;
+
+    assert_eq!(1024, pk.bit_size());
+    assert_eq!(65537, pk.exponent());
+    assert_eq!(NODE_PUBLIC_KEY, pk.to_jwk());
+    assert_eq!(NODE_SECRET_KEY, sk.to_jwk());
+
+    let m: Message = "encrypted here, decrypted with a node key".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn three_prime_secret_key_round_trips_through_jwk() -> Result<(),String> {
+    let (pk,sk) = keygen_multiprime(2048, 3)?.split();
+    let jwk = sk.to_jwk();
+    let decoded = SecretKey::from_jwk(&jwk)?;
+
+    assert!(jwk.contains(r#""oth":[{"r":"#), "{jwk}");
+    assert_eq!(3, decoded.prime_count());
+    let m: Message = "multi-prime jwk round trip".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, decoded)?);
+    Ok(())
+}
+
+#[test]
+fn public_key_can_be_read_from_a_secret_key_jwk() -> Result<(),String> {
+    let pk = PublicKey::from_jwk(NODE_SECRET_KEY)?;
+    assert_eq!(NODE_PUBLIC_KEY, pk.to_jwk());
+    Ok(())
+}
+
+#[test]
+fn unknown_jwk_fields_are_ignored() -> Result<(),String> {
+    let jwk = NODE_PUBLIC_KEY.replacen('{', r#"{"kid":"node","alg":"RSA-OAEP","use":"enc","#, 1);
+    assert_eq!(NODE_PUBLIC_KEY, PublicKey::from_jwk(&jwk)?.to_jwk());
+    Ok(())
+}
+
+#[test]
+fn missing_jwk_fields_are_named() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let full: serde_json::Value = serde_json::from_str(&sk.to_jwk()).map_err(|err| err.to_string())?;
+    for field in ["kty", "n", "e", "d", "p", "q"] {
+        let mut jwk = full.clone();
+        jwk.as_object_mut().ok_or("jwk is not an object")?.remove(field);
+        let reason = encoding_error(SecretKey::from_jwk(&jwk.to_string()));
+        assert_eq!(format!("JWK is missing the required field `{field}`"), reason);
+    }
+    Ok(())
+}
+
+#[test]
+fn crt_jwk_fields_are_optional_on_import() -> Result<(),String> {
+    let mut jwk: serde_json::Value = serde_json::from_str(NODE_SECRET_KEY).map_err(|err| err.to_string())?;
+    for field in ["dp", "dq", "qi"] {
+        jwk.as_object_mut().ok_or("jwk is not an object")?.remove(field);
+    }
+    assert_eq!(NODE_SECRET_KEY, SecretKey::from_jwk(&jwk.to_string())?.to_jwk());
+    Ok(())
+}
+
+#[test]
+fn jwk_of_another_key_type_is_rejected() {
+    let jwk = NODE_PUBLIC_KEY.replacen(r#""kty":"RSA""#, r#""kty":"EC""#, 1);
+    assert_eq!("JWK key type is `EC`, not `RSA`", encoding_error(PublicKey::from_jwk(&jwk)));
+}
+
+#[test]
+fn jwk_with_standard_base64_is_rejected() {
+    let jwk = r#"{"kty":"RSA","n":"ab+/","e":"AQAB"}"#;
+    assert_eq!("JWK field `n` is not base64url", encoding_error(PublicKey::from_jwk(jwk)));
+}
+
+#[test]
+fn jwk_with_bad_primes_is_rejected() -> Result<(),String> {
+    let node: serde_json::Value = serde_json::from_str(NODE_SECRET_KEY).map_err(|err| err.to_string())?;
+    // p = 1 and q = n pass the product check, but decrypting divides by p - 1.
+    let mut trivial = node.clone();
+    trivial["p"] = "AQ".into();
+    trivial["q"] = node["n"].clone();
+    let result = SecretKey::from_jwk(&trivial.to_string());
+    assert!(matches!(&result, Err(Error::InvalidKey(reason)) if reason.contains("below 2")), "{result:?}");
+
+    let mut repeated = node.clone();
+    repeated["q"] = node["p"].clone();
+    assert!(matches!(SecretKey::from_jwk(&repeated.to_string()), Err(Error::InvalidKey(_))));
+
+    let mut wrong_d = node;
+    wrong_d["d"] = "Aw".into();
+    let result = SecretKey::from_jwk(&wrong_d.to_string());
+    assert!(matches!(&result, Err(Error::InvalidKey(reason)) if reason.contains("λ(n)")), "{result:?}");
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::time::{Duration, SystemTime};
+use rustnetworking::rsa::{keygen, pack, unpack_with_sender, IdentifiedKeyPair, KeyId, KeyInfo, PublicKey, SecretKey};
+
+// Made with `openssl genrsa -traditional 1024`.
+const OPENSSL_SECRET_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pem");
+
+#[test]
+fn identified_key_pair_round_trips_through_json() -> Result<(),String> {
+    let identified = IdentifiedKeyPair::new(keygen(512)?, Some(SystemTime::now() + Duration::from_secs(3600)));
+    let json = serde_json::to_string(&identified).map_err(|err| err.to_string())?;
+    let decoded: IdentifiedKeyPair = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+    assert_eq!(identified, decoded);
+    Ok(())
+}
+
+#[test]
+fn identified_key_pair_round_trips_through_bincode() -> Result<(),String> {
+    let identified = IdentifiedKeyPair::new(keygen(512)?, None);
+    let bytes = bincode::serialize(&identified).map_err(|err| err.to_string())?;
+    let decoded: IdentifiedKeyPair = bincode::deserialize(&bytes).map_err(|err| err.to_string())?;
+    assert_eq!(identified, decoded);
+    Ok(())
+}
+
+#[test]
+fn identified_key_pair_with_the_id_of_another_key_is_rejected() -> Result<(),String> {
+    let identified = IdentifiedKeyPair::new(keygen(512)?, None);
+    let other = IdentifiedKeyPair::new(keygen(512)?, None);
+    let mut json = serde_json::to_value(&identified).map_err(|err| err.to_string())?;
+    json["info"]["id"] = serde_json::to_value(other.id()).map_err(|err| err.to_string())?;
+
+    let err = serde_json::from_value::<IdentifiedKeyPair>(json).err().ok_or("mismatched id was accepted")?;
+    assert!(err.to_string().contains("is not that of the key"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn key_expires_at_its_expiry() -> Result<(),String> {
+    let created_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let expires_at = created_at + Duration::from_secs(86400);
+    let (pk,_) = keygen(512)?.split();
+    let info = KeyInfo { id: KeyId::of(&pk), created_at, expires_at: Some(expires_at) };
+
+    assert!(!info.is_expired(created_at));
+    assert!(!info.is_expired(expires_at - Duration::from_secs(1)));
+    assert!(info.is_expired(expires_at));
+    assert!(info.is_expired(expires_at + Duration::from_secs(1)));
+    Ok(())
+}
+
+#[test]
+fn key_without_expiry_never_expires() -> Result<(),String> {
+    let identified = IdentifiedKeyPair::new(keygen(512)?, None);
+    assert!(!identified.is_expired(SystemTime::now() + Duration::from_secs(100 * 365 * 86400)));
+    Ok(())
+}
+
+#[test]
+fn key_id_is_stable() -> Result<(),String> {
+    let sk = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    let id = KeyId::of(&sk.public_key());
+    assert_eq!(sk.public_key().fingerprint_hex()[..32], id.to_string());
+    assert_eq!("8a155be7e9fc2fbd55cb08607d58f2b7", id.to_string());
+    Ok(())
+}
+
+#[test]
+fn key_id_is_the_same_for_every_copy_of_a_key() -> Result<(),String> {
+    let identified = IdentifiedKeyPair::new(keygen(512)?, None);
+    let pk = identified.key_pair().public();
+    assert_eq!(identified.id(), KeyId::of(pk));
+    assert_eq!(identified.id(), KeyId::of(&PublicKey::from_pem(&pk.to_pem())?));
+    assert_eq!(identified.id(), KeyId::of(&PublicKey::from_jwk(&pk.to_jwk())?));
+    assert_ne!(identified.id(), KeyId::of(keygen(512)?.public()));
+    Ok(())
+}
+
+#[test]
+fn unpack_gives_the_id_of_the_sender() -> Result<(),String> {
+    let sender = IdentifiedKeyPair::new(keygen(512)?, None);
+    let (receiver_pk, receiver_sk) = keygen(2048)?.split();
+
+    let c = pack("who sent this?", sender.key_pair(), &receiver_pk)?;
+    let (m, id) = unpack_with_sender(c, receiver_sk)?;
+    assert_eq!("who sent this?".as_bytes(), &m[..]);
+    assert_eq!(sender.id(), id);
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use rustnetworking::rsa::{keygen, Error as RsaError, keystore::{Keystore, Error}};
+
+fn tempdir() -> Result<tempfile::TempDir,String> {
+    tempfile::tempdir().map_err(|err| err.to_string())
+}
+
+#[test]
+fn stored_key_pair_loads_back() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    let key_pair = keygen(512)?;
+    keystore.store("server", &key_pair)?;
+
+    let loaded = keystore.load("server")?;
+    assert_eq!(key_pair.public(), loaded.public());
+    assert_eq!(key_pair.secret(), loaded.secret());
+    Ok(())
+}
+
+#[test]
+fn keys_persist_across_opens() -> Result<(),String> {
+    let dir = tempdir()?;
+    let key_pair = keygen(512)?;
+    Keystore::open(dir.path())?.store("server", &key_pair)?;
+    assert_eq!(key_pair.public(), Keystore::open(dir.path())?.load("server")?.public());
+    Ok(())
+}
+
+#[test]
+fn open_creates_missing_directories() -> Result<(),String> {
+    let dir = tempdir()?;
+    let nested = dir.path().join("a").join("b");
+    let keystore = Keystore::open(&nested)?;
+    assert!(nested.is_dir());
+    assert_eq!(Vec::<String>::new(), keystore.list()?);
+    Ok(())
+}
+
+#[test]
+fn list_gives_sorted_names() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    let key_pair = keygen(512)?;
+    for name in ["zeta", "alpha", "mid.v2"] {
+        keystore.store(name, &key_pair)?;
+    }
+    std::fs::write(dir.path().join("notes.txt"), "not a key").map_err(|err| err.to_string())?;
+    assert_eq!(vec!["alpha", "mid.v2", "zeta"], keystore.list()?);
+    Ok(())
+}
+
+#[test]
+fn store_replaces_a_key_of_the_same_name() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    keystore.store("server", &keygen(512)?)?;
+    let replacement = keygen(512)?;
+    keystore.store("server", &replacement)?;
+
+    assert_eq!(replacement.public(), keystore.load("server")?.public());
+    assert_eq!(vec!["server"], keystore.list()?);
+    Ok(())
+}
+
+#[test]
+fn deleted_key_is_gone() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    keystore.store("server", &keygen(512)?)?;
+    keystore.delete("server")?;
+
+    assert_eq!(Vec::<String>::new(), keystore.list()?);
+    assert!(matches!(keystore.load("server"), Err(Error::NotFound(name)) if name == "server"));
+    assert!(matches!(keystore.delete("server"), Err(Error::NotFound(_))));
+    Ok(())
+}
+
+#[test]
+fn names_that_escape_the_directory_are_rejected() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    let key_pair = keygen(512)?;
+    for name in ["", "../server", "a/b", ".hidden", "spaced name"] {
+        assert!(matches!(keystore.store(name, &key_pair), Err(Error::InvalidName(_))), "{name}");
+        assert!(matches!(keystore.load(name), Err(Error::InvalidName(_))), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn interrupted_write_leaves_the_old_key() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    let key_pair = keygen(512)?;
+    keystore.store("server", &key_pair)?;
+
+    // What a write cut short before its rename leaves behind.
+    let key_file = std::fs::read(dir.path().join("server.key")).map_err(|err| err.to_string())?;
+    std::fs::write(dir.path().join(".server.4242.0.tmp"), &key_file[..key_file.len() / 2]).map_err(|err| err.to_string())?;
+
+    let reopened = Keystore::open(dir.path())?;
+    assert_eq!(vec!["server"], reopened.list()?);
+    assert_eq!(key_pair.public(), reopened.load("server")?.public());
+    Ok(())
+}
+
+#[test]
+fn truncated_key_file_is_reported_and_can_be_overwritten() -> Result<(),String> {
+    let dir = tempdir()?;
+    let keystore = Keystore::open(dir.path())?;
+    let key_pair = keygen(512)?;
+    keystore.store("server", &key_pair)?;
+
+    let path = dir.path().join("server.key");
+    let key_file = std::fs::read(&path).map_err(|err| err.to_string())?;
+    std::fs::write(&path, &key_file[..key_file.len() / 2]).map_err(|err| err.to_string())?;
+    assert!(matches!(keystore.load("server"), Err(Error::Key(RsaError::Encoding(_)))));
+
+    keystore.store("server", &key_pair)?;
+    assert_eq!(key_pair.public(), keystore.load("server")?.public());
+    Ok(())
+}
+
+#[test]
+fn concurrent_keystores_never_see_a_partial_key() -> Result<(),String> {
+    let dir = tempdir()?;
+    let key_pairs = [keygen(512)?, keygen(512)?];
+    Keystore::open(dir.path())?.store("shared", &key_pairs[0])?;
+
+    std::thread::scope(|scope| -> Result<(),String> {
+        let writers: Vec<_> = key_pairs.iter().map(|key_pair| scope.spawn(|| -> Result<(),String> {
+            let keystore = Keystore::open(dir.path())?;
+            for _ in 0..20 {
+                keystore.store("shared", key_pair)?;
+            }
+            Ok(())
+        })).collect();
+
+        let reader = Keystore::open(dir.path())?;
+        for _ in 0..40 {
+            let loaded = reader.load("shared")?;
+            assert!(key_pairs.iter().any(|key_pair| key_pair.public() == loaded.public()));
+        }
+        writers.into_iter().try_for_each(|writer| writer.join().expect("writer panicked"))
+    })?;
+
+    assert_eq!(vec!["shared"], Keystore::open(dir.path())?.list()?);
+    Ok(())
+}
+
+#[test]
+fn sealed_keystore_needs_the_passphrase() -> Result<(),String> {
+    let dir = tempdir()?;
+    let key_pair = keygen(512)?;
+    Keystore::open_sealed(dir.path(), "hunter2")?.store("server", &key_pair)?;
+
+    assert_eq!(key_pair.public(), Keystore::open_sealed(dir.path(), "hunter2")?.load("server")?.public());
+    assert!(matches!(Keystore::open_sealed(dir.path(), "hunter3")?.load("server"), Err(Error::Key(RsaError::WrongPassphrase))));
+    assert!(matches!(Keystore::open(dir.path())?.load("server"), Err(Error::Key(RsaError::Encoding(_)))));
+    Ok(())
+}
+
+#[test]
+fn debug_does_not_show_the_passphrase() -> Result<(),String> {
+    let dir = tempdir()?;
+    let debug = format!("{:?}", Keystore::open_sealed(dir.path(), "hunter2")?);
+    assert!(!debug.contains("hunter2"), "{debug}");
+    assert!(debug.contains("sealed: true"), "{debug}");
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use rustnetworking::rsa::{keygen, keygen_with_exponent, PublicKey, Error};
+
+// Made with `ssh-keygen -t rsa -b 1024 -C alice@example.com`, and the same key converted
+// to PKCS#1 with `ssh-keygen -e -m PEM`.
+const SSH_KEYGEN_LINE: &str = include_str!("fixtures/ssh_keygen_rsa_1024.pub");
+const SSH_KEYGEN_PEM: &str = include_str!("fixtures/ssh_keygen_rsa_1024.pkcs1.pem");
+
+#[test]
+fn ssh_keygen_line_is_parsed() -> Result<(),String> {
+    let pk = PublicKey::from_openssh(SSH_KEYGEN_LINE)?;
+    let expected = PublicKey::from_pem(SSH_KEYGEN_PEM)?;
+
+    assert_eq!(1024, pk.bit_size());
+    assert_eq!(format!("{expected:?}"), format!("{pk:?}"));
+    Ok(())
+}
+
+#[test]
+fn ssh_keygen_line_is_exported_unchanged() -> Result<(),String> {
+    let pk = PublicKey::from_openssh(SSH_KEYGEN_LINE)?;
+    assert_eq!(SSH_KEYGEN_LINE.trim_end(), pk.to_openssh("alice@example.com"));
+    Ok(())
+}
+
+#[test]
+fn public_key_round_trips_through_openssh() -> Result<(),String> {
+    for e in [3, 65537] {
+        let (pk,_) = keygen_with_exponent(512, e)?.split();
+        let decoded = PublicKey::from_openssh(&pk.to_openssh("peer"))?;
+        assert_eq!(format!("{pk:?}"), format!("{decoded:?}"));
+    }
+    Ok(())
+}
+
+#[test]
+fn openssh_comment_is_optional() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let line = pk.to_openssh("");
+    assert_eq!(2, line.split(' ').count(), "{line}");
+    assert_eq!(format!("{pk:?}"), format!("{:?}", PublicKey::from_openssh(&line)?));
+    Ok(())
+}
+
+#[test]
+fn other_key_types_are_rejected() {
+    let line = SSH_KEYGEN_LINE.replacen("ssh-rsa", "ssh-ed25519", 1);
+    assert!(matches!(PublicKey::from_openssh(&line), Err(Error::Encoding(_))));
+}
+
+// The blobs are `ssh-rsa`, then `e` and `n` as given, each prefixed by its four byte length.
+fn line_of(e: &[u8], n: &[u8]) -> String {
+    let mut blob = vec![0, 0, 0, 7];
+    blob.extend_from_slice(b"ssh-rsa");
+    for mpint in [e, n] {
+        blob.extend_from_slice(&(mpint.len() as u32).to_be_bytes());
+        blob.extend_from_slice(mpint);
+    }
+    format!("ssh-rsa {}", base64(&blob))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |acc, (i, &byte)| acc | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            out.push(if i <= chunk.len() { ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char } else { '=' });
+        }
+    }
+    out
+}
+
+#[test]
+fn mpint_with_leading_zero_for_top_bit_is_accepted() -> Result<(),String> {
+    let pk = PublicKey::from_openssh(&line_of(&[0x01, 0x00, 0x01], &[0x00, 0xc5]))?;
+    assert_eq!(65537, pk.exponent());
+    assert_eq!(8, pk.bit_size());
+    Ok(())
+}
+
+#[test]
+fn mpint_with_needless_leading_zero_is_rejected() {
+    assert!(matches!(PublicKey::from_openssh(&line_of(&[0x00, 0x01, 0x00, 0x01], &[0x00, 0xc5])), Err(Error::Encoding(_))));
+}
+
+#[test]
+fn negative_mpint_is_rejected() {
+    assert!(matches!(PublicKey::from_openssh(&line_of(&[0x01, 0x00, 0x01], &[0xc5])), Err(Error::Encoding(_))));
+}
@@ -0,0 +1,100 @@
+use rustnetworking::rsa::{keygen, SecretKey, Error, confidentiality::{encrypt, decrypt, encrypt_pkcs1v15, decrypt_pkcs1v15}};
+
+// Made with `openssl genrsa -traditional 1024`.
+const OPENSSL_SECRET_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pem");
+// Made with `openssl pkeyutl -encrypt -pkeyopt rsa_padding_mode:pkcs1` of the message below.
+const OPENSSL_CIPHERTEXT: &[u8] = include_bytes!("fixtures/openssl_rsa_1024.pkcs1v15_ciphertext.bin");
+const OPENSSL_MESSAGE: &str = "legacy systems still speak PKCS #1 v1.5";
+
+#[test]
+fn openssl_ciphertext_decrypts() -> Result<(),String> {
+    let sk = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    assert_eq!(OPENSSL_MESSAGE.as_bytes(), &decrypt_pkcs1v15(OPENSSL_CIPHERTEXT, &sk)?[..]);
+    Ok(())
+}
+
+#[test]
+fn encryption_round_trips() -> Result<(),String> {
+    let (pk,sk) = keygen(1024)?.split();
+    let mut rng = rand::thread_rng();
+    for message in ["", "a", "a longer message for pkcs #1"] {
+        let c = encrypt_pkcs1v15(message, &pk, &mut rng)?;
+        assert_eq!(128, c.len());
+        assert_eq!(message.as_bytes(), &decrypt_pkcs1v15(c, &sk)?[..]);
+    }
+    Ok(())
+}
+
+#[test]
+fn longest_message_has_eight_bytes_of_padding() -> Result<(),String> {
+    let (pk,sk) = keygen(1024)?.split();
+    let mut rng = rand::thread_rng();
+    let longest = vec![0xab; 128 - 11];
+    assert_eq!(longest, decrypt_pkcs1v15(encrypt_pkcs1v15(longest.clone(), &pk, &mut rng)?, &sk)?);
+
+    let too_long = vec![0xab; 128 - 10];
+    assert!(matches!(encrypt_pkcs1v15(too_long, &pk, &mut rng), Err(Error::MessageTooLong { .. })));
+    Ok(())
+}
+
+#[test]
+fn padding_is_random_and_nonzero() -> Result<(),String> {
+    let (pk,sk) = keygen(1024)?.split();
+    let mut rng = rand::thread_rng();
+    let message = "padded";
+
+    let first = encrypt_pkcs1v15(message, &pk, &mut rng)?;
+    assert_ne!(first, encrypt_pkcs1v15(message, &pk, &mut rng)?);
+
+    // Raw decryption drops the leading zero byte.
+    let encoded = decrypt(first, sk)?;
+    assert_eq!(127, encoded.len());
+    assert_eq!(0x02, encoded[0]);
+    let padding = &encoded[1..127 - message.len() - 1];
+    assert!(padding.len() >= 8);
+    assert!(padding.iter().all(|&byte| byte != 0));
+    assert_eq!(0x00, encoded[127 - message.len() - 1]);
+    assert_eq!(message.as_bytes(), &encoded[127 - message.len()..]);
+    Ok(())
+}
+
+#[test]
+fn badly_padded_messages_are_rejected() -> Result<(),String> {
+    let (pk,sk) = keygen(1024)?.split();
+    let padded = |block_type: u8, padding: usize| {
+        let mut encoded = vec![0x00, block_type];
+        encoded.extend(vec![0xff; padding]);
+        encoded.push(0x00);
+        encoded.resize(128, 0x61);
+        encoded
+    };
+    let raw = |encoded: Vec<u8>| encrypt(encoded, &pk).map(|c| {
+        let mut padded = vec![0; 128 - c.len()];
+        padded.extend(c);
+        padded
+    });
+
+    assert!(decrypt_pkcs1v15(raw(padded(0x02, 8))?, &sk).is_ok());
+    // The block type of signatures.
+    assert!(matches!(decrypt_pkcs1v15(raw(padded(0x01, 8))?, &sk), Err(Error::DecryptionFailed)));
+    assert!(matches!(decrypt_pkcs1v15(raw(padded(0x02, 7))?, &sk), Err(Error::DecryptionFailed)));
+    assert!(matches!(decrypt_pkcs1v15(raw(padded(0x02, 0))?, &sk), Err(Error::DecryptionFailed)));
+    let mut no_separator = vec![0x00, 0x02];
+    no_separator.resize(128, 0xff);
+    assert!(matches!(decrypt_pkcs1v15(raw(no_separator)?, &sk), Err(Error::DecryptionFailed)));
+    let mut nonzero_first = padded(0x02, 8);
+    nonzero_first[0] = 0x01;
+    assert!(matches!(decrypt_pkcs1v15(raw(nonzero_first)?, &sk), Err(Error::DecryptionFailed)));
+    Ok(())
+}
+
+#[test]
+fn ciphertext_of_the_wrong_length_is_rejected() -> Result<(),String> {
+    let sk = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    assert!(matches!(decrypt_pkcs1v15(&OPENSSL_CIPHERTEXT[1..], &sk), Err(Error::DecryptionFailed)));
+    let mut longer = vec![0];
+    longer.extend_from_slice(OPENSSL_CIPHERTEXT);
+    assert!(matches!(decrypt_pkcs1v15(longer, &sk), Err(Error::DecryptionFailed)));
+    assert!(matches!(decrypt_pkcs1v15(vec![0xff; 128], &sk), Err(Error::DecryptionFailed)));
+    Ok(())
+}
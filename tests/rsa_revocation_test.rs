@@ -0,0 +1,153 @@
+use rustnetworking::rsa::{keygen, pack, unpack_unless_revoked, Error, KeyId, authenticity::Verification, revocation::{RevocationList, RevocationReason}};
+
+fn accepts(verification: Verification) -> bool {
+    matches!(verification, Verification::Accept)
+}
+
+#[test]
+fn signed_list_verifies_with_its_issuer() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let mut list = RevocationList::new(issuer.public());
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::KeyCompromise);
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::Superseded);
+    list.sign(&issuer)?;
+
+    assert!(list.is_signed());
+    assert!(accepts(list.verify(issuer.public())));
+    assert!(!accepts(list.verify(keygen(512)?.public())));
+    Ok(())
+}
+
+#[test]
+fn empty_list_can_be_signed() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let mut list = RevocationList::new(issuer.public());
+    list.sign(&issuer)?;
+    assert!(accepts(list.verify(issuer.public())));
+    Ok(())
+}
+
+#[test]
+fn unsigned_list_is_rejected() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let mut list = RevocationList::new(issuer.public());
+    assert!(!accepts(list.verify(issuer.public())));
+
+    list.sign(&issuer)?;
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::Unspecified);
+    assert!(!list.is_signed());
+    assert!(!accepts(list.verify(issuer.public())));
+    Ok(())
+}
+
+#[test]
+fn only_the_issuer_can_sign() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let mut list = RevocationList::new(issuer.public());
+    assert!(matches!(list.sign(&keygen(512)?), Err(Error::InvalidKey(_))));
+    Ok(())
+}
+
+#[test]
+fn tampered_list_is_rejected() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let revoked = KeyId::of(keygen(512)?.public());
+    let mut list = RevocationList::new(issuer.public());
+    list.revoke(revoked, RevocationReason::KeyCompromise);
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::Superseded);
+    list.sign(&issuer)?;
+    let json = serde_json::to_value(&list).map_err(|err| err.to_string())?;
+
+    let mut dropped = json.clone();
+    dropped["revocations"].as_array_mut().ok_or("revocations are not an array")?.retain(|revocation| {
+        revocation["key"] != serde_json::to_value(revoked).unwrap()
+    });
+    let dropped: RevocationList = serde_json::from_value(dropped).map_err(|err| err.to_string())?;
+    assert!(!dropped.is_revoked(revoked));
+    assert!(!accepts(dropped.verify(issuer.public())));
+
+    let mut reason = json.clone();
+    reason["revocations"][0]["reason"] = "Unspecified".into();
+    let reason: RevocationList = serde_json::from_value(reason).map_err(|err| err.to_string())?;
+    assert!(!accepts(reason.verify(issuer.public())));
+
+    let mut time = json;
+    time["revocations"][1]["revoked_at"]["secs_since_epoch"] = 0.into();
+    let time: RevocationList = serde_json::from_value(time).map_err(|err| err.to_string())?;
+    assert!(!accepts(time.verify(issuer.public())));
+    Ok(())
+}
+
+#[test]
+fn list_round_trips_through_json() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let mut list = RevocationList::new(issuer.public());
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::CessationOfOperation);
+    list.sign(&issuer)?;
+
+    let json = serde_json::to_string(&list).map_err(|err| err.to_string())?;
+    let decoded: RevocationList = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+    assert_eq!(list, decoded);
+    assert!(accepts(decoded.verify(issuer.public())));
+    Ok(())
+}
+
+#[test]
+fn out_of_order_revocations_are_rejected() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let mut list = RevocationList::new(issuer.public());
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::Unspecified);
+    list.revoke(KeyId::of(keygen(512)?.public()), RevocationReason::Unspecified);
+    let mut json = serde_json::to_value(&list).map_err(|err| err.to_string())?;
+    json["revocations"].as_array_mut().ok_or("revocations are not an array")?.reverse();
+    assert!(serde_json::from_value::<RevocationList>(json).is_err());
+    Ok(())
+}
+
+#[test]
+fn is_revoked_finds_only_revoked_keys() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let ids: Vec<KeyId> = (0..5).map(|_| keygen(512).map(|key_pair| KeyId::of(key_pair.public()))).collect::<Result<_,_>>()?;
+    let mut list = RevocationList::new(issuer.public());
+    for id in &ids[..3] {
+        list.revoke(*id, RevocationReason::Superseded);
+    }
+
+    assert!(ids[..3].iter().all(|id| list.is_revoked(*id)));
+    assert!(ids[3..].iter().all(|id| !list.is_revoked(*id)));
+    assert!(list.revocations().windows(2).all(|pair| pair[0].key < pair[1].key));
+    Ok(())
+}
+
+#[test]
+fn revoking_again_keeps_the_first_revocation() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let id = KeyId::of(keygen(512)?.public());
+    let mut list = RevocationList::new(issuer.public());
+    list.revoke(id, RevocationReason::Superseded);
+    let first = *list.revocation(id).ok_or("key was not revoked")?;
+    list.sign(&issuer)?;
+    list.revoke(id, RevocationReason::KeyCompromise);
+
+    assert_eq!(1, list.revocations().len());
+    assert_eq!(&first, list.revocation(id).ok_or("key was not revoked")?);
+    assert!(list.is_signed());
+    Ok(())
+}
+
+#[test]
+fn unpack_refuses_revoked_senders() -> Result<(),String> {
+    let issuer = keygen(512)?;
+    let sender = keygen(512)?;
+    let (receiver_pk, receiver_sk) = keygen(2048)?.split();
+    let mut list = RevocationList::new(issuer.public());
+
+    let (m, id) = unpack_unless_revoked(pack("before", &sender, &receiver_pk)?, receiver_sk.clone(), &list)?;
+    assert_eq!("before".as_bytes(), &m[..]);
+    assert_eq!(KeyId::of(sender.public()), id);
+
+    list.revoke(id, RevocationReason::KeyCompromise);
+    let result = unpack_unless_revoked(pack("after", &sender, &receiver_pk)?, receiver_sk, &list);
+    assert!(matches!(result, Err(Error::Revoked(revoked)) if revoked == id));
+    Ok(())
+}
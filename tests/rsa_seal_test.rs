@@ -0,0 +1,77 @@
+use rustnetworking::rsa::{keygen, keygen_multiprime, SecretKey, Error, confidentiality::{self as conf, Message}};
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+#[test]
+fn secret_key_round_trips_through_seal() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let opened = SecretKey::open(&sk.seal(PASSPHRASE), PASSPHRASE)?;
+
+    assert_eq!(sk, opened);
+    let m: Message = "sealed and opened".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, opened)?);
+    Ok(())
+}
+
+#[test]
+fn three_prime_secret_key_round_trips_through_seal() -> Result<(),String> {
+    let (_,sk) = keygen_multiprime(2048, 3)?.split();
+    let opened = SecretKey::open(&sk.seal(PASSPHRASE), PASSPHRASE)?;
+    assert_eq!(3, opened.prime_count());
+    assert_eq!(sk, opened);
+    Ok(())
+}
+
+#[test]
+fn sealed_key_starts_with_version_and_iterations() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let sealed = sk.seal(PASSPHRASE);
+    assert_eq!(1, sealed[0]);
+    assert_eq!(600_000u32.to_be_bytes(), sealed[1..5]);
+    Ok(())
+}
+
+#[test]
+fn sealing_twice_gives_different_bytes() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    assert_ne!(sk.seal(PASSPHRASE), sk.seal(PASSPHRASE));
+    Ok(())
+}
+
+#[test]
+fn wrong_passphrase_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let sealed = sk.seal(PASSPHRASE);
+    assert!(matches!(SecretKey::open(&sealed, "Tr0ub4dor&3"), Err(Error::WrongPassphrase)));
+    assert!(matches!(SecretKey::open(&sealed, ""), Err(Error::WrongPassphrase)));
+    Ok(())
+}
+
+#[test]
+fn corrupted_ciphertext_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let mut sealed = sk.seal(PASSPHRASE);
+    let middle = sealed.len() / 2;
+    sealed[middle] ^= 1;
+    assert!(matches!(SecretKey::open(&sealed, PASSPHRASE), Err(Error::Encoding(_))));
+    Ok(())
+}
+
+#[test]
+fn corrupted_tag_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let mut sealed = sk.seal(PASSPHRASE);
+    *sealed.last_mut().unwrap() ^= 1;
+    assert!(matches!(SecretKey::open(&sealed, PASSPHRASE), Err(Error::Encoding(_))));
+    Ok(())
+}
+
+#[test]
+fn truncated_or_unknown_version_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let mut sealed = sk.seal(PASSPHRASE);
+    assert!(matches!(SecretKey::open(&sealed[..40], PASSPHRASE), Err(Error::Encoding(_))));
+    sealed[0] = 2;
+    assert!(matches!(SecretKey::open(&sealed, PASSPHRASE), Err(Error::Encoding(_))));
+    Ok(())
+}
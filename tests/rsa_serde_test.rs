@@ -0,0 +1,286 @@
+use std::{collections::{HashSet, hash_map::DefaultHasher}, hash::{Hash, Hasher}};
+use rustnetworking::big_num::BigUint;
+use rustnetworking::rsa::{
+    keygen, keygen_with_exponent, unpack, Data, Key, KeyPair, PublicKey, SecretKey, Error,
+    confidentiality::{self as conf, Message},
+    authenticity as auth};
+
+#[test]
+fn public_key_round_trips_through_json() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let json = serde_json::to_string(&pk).map_err(|err| err.to_string())?;
+    let decoded: PublicKey = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+
+    assert_eq!(format!("{pk:?}"), format!("{decoded:?}"));
+    Ok(())
+}
+
+#[test]
+fn public_key_json_is_a_hex_string() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let json = serde_json::to_value(&pk).map_err(|err| err.to_string())?;
+
+    assert!(json["n"].is_string(), "{json}");
+    Ok(())
+}
+
+#[test]
+fn secret_key_round_trips_through_json() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let json = serde_json::to_string(&sk).map_err(|err| err.to_string())?;
+    let decoded: SecretKey = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+
+    let m: Message = "json round trip".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, decoded)?);
+    Ok(())
+}
+
+#[test]
+fn data_round_trips_through_json() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let message: Message = "signed message".into();
+    let data = Data {
+        message: message.clone(),
+        signature: auth::sign(message.clone(), sk)?,
+        sender: pk,
+    };
+
+    let json = serde_json::to_string(&data).map_err(|err| err.to_string())?;
+    let decoded: Data = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+
+    assert_eq!(format!("{data:?}"), format!("{decoded:?}"));
+    Ok(())
+}
+
+#[test]
+fn bincode_encoding_of_keys_is_unchanged() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let decoded: PublicKey = bincode::deserialize(&bincode::serialize(&pk).map_err(|err| err.to_string())?).map_err(|err| err.to_string())?;
+    assert_eq!(format!("{pk:?}"), format!("{decoded:?}"));
+    Ok(())
+}
+
+#[test]
+fn secret_key_round_trips_through_bincode() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let bytes = bincode::serialize(&sk).map_err(|err| err.to_string())?;
+    let decoded: SecretKey = bincode::deserialize(&bytes).map_err(|err| err.to_string())?;
+
+    let m: Message = "bincode round trip".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, decoded)?);
+    Ok(())
+}
+
+#[test]
+fn secret_key_json_without_d_and_n_recomputes_them() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let mut json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    let fields = json.as_object_mut().ok_or("secret key is not a json object")?;
+    fields.remove("d");
+    fields.remove("n");
+    let decoded: SecretKey = serde_json::from_value(json).map_err(|err| err.to_string())?;
+
+    let m: Message = "old key".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, decoded)?);
+    Ok(())
+}
+
+#[test]
+fn secret_key_bincode_without_d_and_n_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    let p: BigUint = serde_json::from_value(json["p"].clone()).map_err(|err| err.to_string())?;
+    let q: BigUint = serde_json::from_value(json["q"].clone()).map_err(|err| err.to_string())?;
+    let old = bincode::serialize(&(p,q)).map_err(|err| err.to_string())?;
+
+    assert!(bincode::deserialize::<SecretKey>(&old).is_err());
+    Ok(())
+}
+
+#[test]
+fn keys_without_e_are_read_as_e_3() -> Result<(),String> {
+    let (pk,sk) = keygen_with_exponent(512, 3)?.split();
+    let mut pk_json = serde_json::to_value(&pk).map_err(|err| err.to_string())?;
+    let mut sk_json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    pk_json.as_object_mut().ok_or("public key is not a json object")?.remove("e");
+    sk_json.as_object_mut().ok_or("secret key is not a json object")?.remove("e");
+    let pk: PublicKey = serde_json::from_value(pk_json).map_err(|err| err.to_string())?;
+    let sk: SecretKey = serde_json::from_value(sk_json).map_err(|err| err.to_string())?;
+
+    assert_eq!(3, pk.exponent());
+    let m: Message = "key from before e was stored".into();
+    assert_eq!(m, conf::decrypt(conf::encrypt(m.clone(), &pk)?, sk)?);
+    Ok(())
+}
+
+#[test]
+fn secret_key_with_wrong_n_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let (pk,_) = keygen(512)?.split();
+    let mut json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    json["n"] = serde_json::to_value(&pk).map_err(|err| err.to_string())?["n"].clone();
+
+    let err = serde_json::from_value::<SecretKey>(json).err().ok_or("key with wrong n was accepted")?;
+    assert!(err.to_string().contains("n is not the product of the primes"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn secret_key_with_bad_primes_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    // p = 1 and q = n pass the product check, but decrypting divides by p - 1.
+    let mut trivial = json.clone();
+    trivial["p"] = serde_json::to_value(BigUint::one()).map_err(|err| err.to_string())?;
+    trivial["q"] = json["n"].clone();
+    let err = serde_json::from_value::<SecretKey>(trivial.clone()).err().ok_or("key with p = 1 was accepted")?;
+    assert!(err.to_string().contains("below 2"), "{err}");
+
+    trivial.as_object_mut().ok_or("secret key is not a json object")?.remove("d");
+    let err = serde_json::from_value::<SecretKey>(trivial).err().ok_or("key with p = 1 and no d was accepted")?;
+    assert!(err.to_string().contains("below 2"), "{err}");
+
+    let mut wrong_d = json;
+    wrong_d["d"] = serde_json::to_value(BigUint::from(3u32)).map_err(|err| err.to_string())?;
+    let err = serde_json::from_value::<SecretKey>(wrong_d).err().ok_or("key with wrong d was accepted")?;
+    assert!(err.to_string().contains("λ(n)"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn unpack_of_data_that_is_not_bincode_fails_to_deserialize() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let c = conf::encrypt(vec![0xff; 16], &pk)?;
+    assert!(matches!(unpack(c, sk), Err(Error::Serialization(_))));
+    Ok(())
+}
+
+#[test]
+fn secret_key_debug_does_not_show_the_primes() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let json = serde_json::to_value(&sk).map_err(|err| err.to_string())?;
+    let p_hex = json["p"].as_str().ok_or("p is not a string")?.to_string();
+    let p_decimal = BigUint::from_hex(&p_hex)?.to_string();
+
+    let debug = format!("{sk:?}");
+    assert!(!debug.contains(&p_hex), "{debug}");
+    assert!(!debug.contains(&p_decimal), "{debug}");
+    assert_eq!(format!("SecretKey {{ bits: 512, primes: 2, fingerprint: {}.., .. }}", &pk.fingerprint_hex()[..8]), debug);
+    // KeyPair's derived Debug goes through the redacted one.
+    assert!(!format!("{:?}", KeyPair::from(sk.clone())).contains(&p_decimal));
+    Ok(())
+}
+
+#[test]
+fn secret_key_debug_unsafe_full_shows_everything() -> Result<(),String> {
+    let sk = SecretKey::from_pem(include_str!("fixtures/openssl_rsa_1024.pem"))?;
+    let full = sk.debug_unsafe_full();
+    assert!(full.starts_with("SecretKey { primes: ["), "{full}");
+    assert!(full.contains("e: 65537"), "{full}");
+    Ok(())
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn deserialized_copies_of_a_public_key_are_equal_and_hash_the_same() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let json = serde_json::to_string(&pk).map_err(|err| err.to_string())?;
+    let a: PublicKey = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+    let b: PublicKey = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+
+    assert_eq!(a, b);
+    assert_eq!(pk, a);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    Ok(())
+}
+
+#[test]
+fn public_keys_are_deduplicated_in_a_hash_set() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let (other,_) = keygen(512)?.split();
+    let bytes = bincode::serialize(&pk).map_err(|err| err.to_string())?;
+    let copy: PublicKey = bincode::deserialize(&bytes).map_err(|err| err.to_string())?;
+
+    let trusted: HashSet<PublicKey> = [pk.clone(), copy, other.clone()].into_iter().collect();
+    assert_eq!(2, trusted.len());
+    assert!(trusted.contains(&pk) && trusted.contains(&other));
+    Ok(())
+}
+
+#[test]
+fn public_keys_with_the_same_n_and_another_e_differ() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let mut json = serde_json::to_value(&pk).map_err(|err| err.to_string())?;
+    json["e"] = 3.into();
+    let other: PublicKey = serde_json::from_value(json).map_err(|err| err.to_string())?;
+
+    assert_ne!(pk, other);
+    assert_eq!(pk, sk.public_key());
+    Ok(())
+}
+
+#[test]
+fn deserialized_copies_of_a_secret_key_are_equal() -> Result<(),String> {
+    let (_,sk) = keygen(512)?.split();
+    let (_,other) = keygen(512)?.split();
+    let json = serde_json::to_string(&sk).map_err(|err| err.to_string())?;
+    let copy: SecretKey = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+
+    assert_eq!(sk, copy);
+    assert_ne!(sk, other);
+    Ok(())
+}
+
+#[test]
+fn keys_of_both_kinds_round_trip_through_json() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let keys = vec![Key::from(pk), Key::from(sk)];
+    let json = serde_json::to_string(&keys).map_err(|err| err.to_string())?;
+    let decoded: Vec<Key> = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+
+    assert!(json.starts_with(r#"[{"Public":{"n":"#), "{json}");
+    assert_eq!(keys, decoded);
+    Ok(())
+}
+
+#[test]
+fn keys_of_both_kinds_round_trip_through_bincode() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    for key in [Key::Public(pk), Key::Secret(sk)] {
+        let bytes = bincode::serialize(&key).map_err(|err| err.to_string())?;
+        let decoded: Key = bincode::deserialize(&bytes).map_err(|err| err.to_string())?;
+        assert_eq!(key, decoded);
+    }
+    Ok(())
+}
+
+#[test]
+fn public_and_secret_key_share_size_and_fingerprint() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let (public, secret) = (Key::Public(pk.clone()), Key::Secret(sk));
+
+    assert!(!public.is_secret());
+    assert!(secret.is_secret());
+    assert_eq!(512, public.bit_size());
+    assert_eq!(512, secret.bit_size());
+    assert_eq!(pk.fingerprint(), public.fingerprint());
+    assert_eq!(public.fingerprint(), secret.fingerprint());
+    assert_eq!(pk, secret.public_key());
+    Ok(())
+}
+
+#[test]
+fn key_converts_only_into_its_own_kind() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+
+    assert_eq!(pk, PublicKey::try_from(Key::Public(pk.clone())).map_err(|_| "not public")?);
+    assert_eq!(sk, SecretKey::try_from(Key::Secret(sk.clone())).map_err(|_| "not secret")?);
+    assert_eq!(Err(Key::Secret(sk.clone())), PublicKey::try_from(Key::Secret(sk)));
+    assert_eq!(Err(Key::Public(pk.clone())), SecretKey::try_from(Key::Public(pk)));
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use rand::{SeedableRng, rngs::StdRng};
+use rustnetworking::{big_num::BigUint, rsa::{keygen, keygen_multiprime, keygen_with_rng, keygen_checked, validate_keypair, check_prime_distance, exponent_coprime_to, keygen_with_exponent, pairwise_consistency_test, KeyPair, PublicKey, SecretKey, Error}};
+
+const OPENSSL_SECRET_KEY: &str = include_str!("fixtures/openssl_rsa_1024.pem");
+
+fn invalid_key_reason(result: Result<(),Error>) -> String {
+    match result {
+        Err(Error::InvalidKey(reason)) => reason,
+        Err(err) => panic!("expected an invalid key error, got {err}"),
+        Ok(()) => panic!("expected an invalid key error, the key was accepted"),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<serde_json::Value,String> {
+    serde_json::to_value(value).map_err(|err| err.to_string())
+}
+
+#[test]
+fn generated_key_pair_is_valid() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    pk.validate()?;
+    validate_keypair(&pk, &sk)?;
+    Ok(())
+}
+
+#[test]
+fn three_prime_key_pair_is_valid() -> Result<(),String> {
+    let (pk,sk) = keygen_multiprime(2048, 3)?.split();
+    validate_keypair(&pk, &sk)?;
+    Ok(())
+}
+
+#[test]
+fn openssl_key_pair_is_valid() -> Result<(),String> {
+    let sk = SecretKey::from_pem(OPENSSL_SECRET_KEY)?;
+    validate_keypair(&sk.public_key(), &sk)?;
+    Ok(())
+}
+
+#[test]
+fn key_pair_with_swapped_bytes_in_q_is_rejected() -> Result<(),String> {
+    let (_,sk) = keygen_with_rng(512, &mut StdRng::seed_from_u64(3))?.split();
+    let mut json = to_json(&sk)?;
+    let q = json["q"].as_str().ok_or("q is not a string")?.to_string();
+    // Swap the second and third byte of q's hex, and drop d and n so they are recomputed from the corrupted q.
+    let corrupted = format!("{}{}{}{}", &q[..2], &q[4..6], &q[2..4], &q[6..]);
+    assert_ne!(q, corrupted);
+    json["q"] = corrupted.into();
+    let fields = json.as_object_mut().ok_or("secret key is not an object")?;
+    fields.remove("d");
+    fields.remove("n");
+    let corrupted: SecretKey = serde_json::from_value(json).map_err(|err| err.to_string())?;
+
+    assert_eq!("q is not prime", invalid_key_reason(validate_keypair(&corrupted.public_key(), &corrupted)));
+    Ok(())
+}
+
+#[test]
+fn close_primes_are_rejected() -> Result<(),String> {
+    let (_,sk) = keygen_with_rng(512, &mut StdRng::seed_from_u64(5))?.split();
+    let mut json = to_json(&sk)?;
+    let p = BigUint::from_hex(json["p"].as_str().ok_or("p is not a string")?)?;
+    let q = (&p + BigUint::two_pow(20)).next_prime();
+    assert!(matches!(check_prime_distance(&p, &q, 512), Err(Error::InvalidKey(_))));
+
+    json["q"] = to_json(&q)?;
+    let fields = json.as_object_mut().ok_or("secret key is not an object")?;
+    fields.remove("d");
+    fields.remove("n");
+    let close: SecretKey = serde_json::from_value(json).map_err(|err| err.to_string())?;
+
+    assert_eq!("p and q are too close, |p - q| is not above 2^156", invalid_key_reason(validate_keypair(&close.public_key(), &close)));
+    Ok(())
+}
+
+#[test]
+fn generated_primes_are_far_apart() -> Result<(),String> {
+    for seed in 0..10 {
+        let (_,sk) = keygen_with_rng(512, &mut StdRng::seed_from_u64(seed))?.split();
+        let json = to_json(&sk)?;
+        let p = BigUint::from_hex(json["p"].as_str().ok_or("p is not a string")?)?;
+        let q = BigUint::from_hex(json["q"].as_str().ok_or("q is not a string")?)?;
+        check_prime_distance(&p, &q, 512)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn key_pair_with_wrong_d_is_rejected() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let mut json = to_json(&sk)?;
+    json["d"] = to_json(&BigUint::from(65537u32))?;
+
+    // Caught when the key is read, before it could reach validate_keypair.
+    let err = serde_json::from_value::<SecretKey>(json).err().ok_or("key with wrong d was accepted")?;
+    assert!(err.to_string().contains("d e is not 1 modulo λ(n)"), "{err}");
+    validate_keypair(&pk, &sk)?;
+    Ok(())
+}
+
+#[test]
+fn mismatched_key_pair_is_rejected() -> Result<(),String> {
+    let (pk,_) = keygen(512)?.split();
+    let (_,sk) = keygen(512)?.split();
+    assert_eq!("the public key is not the one of the secret key", invalid_key_reason(validate_keypair(&pk, &sk)));
+    Ok(())
+}
+
+#[test]
+fn public_key_with_even_n_is_rejected() -> Result<(),String> {
+    let pk: PublicKey = serde_json::from_value(serde_json::json!({"n": BigUint::two_pow(1023), "e": 65537}))
+        .map_err(|err| err.to_string())?;
+    assert_eq!("n is even", invalid_key_reason(pk.validate()));
+    Ok(())
+}
+
+#[test]
+fn public_key_with_small_n_is_rejected() -> Result<(),String> {
+    let pk: PublicKey = serde_json::from_value(serde_json::json!({"n": BigUint::two_pow(255) + 1u32, "e": 65537}))
+        .map_err(|err| err.to_string())?;
+    assert_eq!("n has 256 bits, fewer than the minimum of 512", invalid_key_reason(pk.validate()));
+    Ok(())
+}
+
+#[test]
+fn public_key_with_even_e_is_rejected() -> Result<(),String> {
+    let pk: PublicKey = serde_json::from_value(serde_json::json!({"n": BigUint::two_pow(1023) + 1u32, "e": 65536}))
+        .map_err(|err| err.to_string())?;
+    assert_eq!("e is even or less than 3", invalid_key_reason(pk.validate()));
+    Ok(())
+}
+
+#[test]
+fn keygen_checked_gives_a_consistent_key_pair() -> Result<(),String> {
+    let pair = keygen_checked(512)?;
+    pairwise_consistency_test(&pair)?;
+    validate_keypair(pair.public(), pair.secret())?;
+    Ok(())
+}
+
+#[test]
+fn key_pair_with_d_off_by_a_multiple_of_lambda_passes_the_self_test() -> Result<(),String> {
+    let (pk,sk) = keygen(512)?.split();
+    let lambda = (sk.p() - 1u32)?.lcm(&(sk.q() - 1u32)?);
+    let mut json = to_json(&sk)?;
+    json["d"] = to_json(&(sk.d() + &lambda))?;
+    let shifted: SecretKey = serde_json::from_value(json).map_err(|err| err.to_string())?;
+
+    validate_keypair(&pk, &shifted)?;
+    pairwise_consistency_test(&KeyPair::from(shifted))?;
+    Ok(())
+}
+
+#[test]
+fn exponent_coprime_to_checks_p_minus_one() {
+    // 7 - 1 = 6 and 13 - 1 = 12 are multiples of 3, 5 - 1 = 4 and 11 - 1 = 10 are not.
+    assert!(!exponent_coprime_to(3, &BigUint::from(7u32)));
+    assert!(!exponent_coprime_to(3, &BigUint::from(13u32)));
+    assert!(exponent_coprime_to(3, &BigUint::from(5u32)));
+    assert!(exponent_coprime_to(3, &BigUint::from(11u32)));
+    // 2 * 65537 + 1 - 1 shares 65537 with e, 65537 * 2 + 3 - 1 doesn't.
+    assert!(!exponent_coprime_to(65537, &BigUint::from(131075u32)));
+    assert!(exponent_coprime_to(65537, &BigUint::from(131077u32)));
+}
+
+#[test]
+fn keygen_with_small_exponent_gives_primes_coprime_to_it() -> Result<(),String> {
+    for _ in 0..5 {
+        let (_,sk) = keygen_with_exponent(512, 3)?.split();
+        assert!(exponent_coprime_to(3, sk.p()));
+        assert!(exponent_coprime_to(3, sk.q()));
+        validate_keypair(&sk.public_key(), &sk)?;
+    }
+    Ok(())
+}